@@ -22,7 +22,9 @@ import mozilla.telemetry.glean.net.PingUploader
     "languageBindingName",
     "uploadEnabled",
     "maxEvents",
-    "delayPingLifetimeIO"
+    "delayPingLifetimeIO",
+    "compressPings",
+    "extraServerEndpoints"
 )
 internal class FfiConfiguration(
     dataDir: String,
@@ -30,7 +32,9 @@ internal class FfiConfiguration(
     languageBindingName: String,
     uploadEnabled: Boolean,
     maxEvents: Int? = null,
-    delayPingLifetimeIO: Boolean
+    delayPingLifetimeIO: Boolean,
+    compressPings: Boolean = true,
+    extraServerEndpoints: String? = null
 ) : Structure() {
     /**
      * Expose all structure fields as actual fields,
@@ -49,6 +53,10 @@ internal class FfiConfiguration(
     public var maxEvents: IntByReference = if (maxEvents == null) IntByReference() else IntByReference(maxEvents)
     @JvmField
     public var delayPingLifetimeIO: Byte = delayPingLifetimeIO.toByte()
+    @JvmField
+    public var compressPings: Byte = compressPings.toByte()
+    @JvmField
+    public var extraServerEndpoints: String? = extraServerEndpoints
 
     init {
         // Force UTF-8 string encoding when passing strings over the FFI
@@ -24,14 +24,15 @@ enum class UploadTaskTag {
     Done
 }
 
-@Structure.FieldOrder("tag", "documentId", "path", "body", "headers")
+@Structure.FieldOrder("tag", "documentId", "path", "body", "headers", "serverUrl")
 internal class UploadBody(
     // NOTE: We need to provide defaults here, so that JNA can create this object.
     @JvmField val tag: Byte = UploadTaskTag.Done.ordinal.toByte(),
     @JvmField val documentId: Pointer? = null,
     @JvmField val path: Pointer? = null,
     @JvmField var body: RustBuffer = RustBuffer(),
-    @JvmField val headers: Pointer? = null
+    @JvmField val headers: Pointer? = null,
+    @JvmField val serverUrl: Pointer? = null
 ) : Structure() {
     fun toPingRequest(): PingRequest {
         return PingRequest(
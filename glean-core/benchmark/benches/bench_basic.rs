@@ -7,14 +7,20 @@ use criterion::{criterion_group, criterion_main, Criterion};
 /// before benchmarking the set/submit functionality.
 pub fn criterion_benchmark(c: &mut Criterion) {
     let data_dir = tempfile::tempdir().unwrap();
-    let tmpname = data_dir.path().display().to_string();
     let cfg = Configuration {
         upload_enabled: true,
-        data_path: tmpname,
+        data_path: data_dir.path().into(),
         application_id: "glean.bench".into(),
         language_binding_name: "Rust".into(),
         max_events: None,
         delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
     };
 
     let mut glean = Glean::new(cfg).unwrap();
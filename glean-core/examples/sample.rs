@@ -1,4 +1,5 @@
 use std::env;
+use std::path::PathBuf;
 
 use glean_core::metrics::*;
 use glean_core::ping::PingMaker;
@@ -11,10 +12,10 @@ fn main() {
     let mut args = env::args().skip(1);
 
     let data_path = if let Some(path) = args.next() {
-        path
+        PathBuf::from(path)
     } else {
         let root = Builder::new().prefix("simple-db").tempdir().unwrap();
-        root.path().display().to_string()
+        root.path().to_path_buf()
     };
 
     let cfg = glean_core::Configuration {
@@ -24,6 +25,13 @@ fn main() {
         upload_enabled: true,
         max_events: None,
         delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
     };
     let mut glean = Glean::new(cfg).unwrap();
     glean.register_ping_type(&PingType::new("baseline", true, false, vec![]));
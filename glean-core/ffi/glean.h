@@ -174,6 +174,8 @@ typedef struct {
   uint8_t upload_enabled;
   const int32_t *max_events;
   uint8_t delay_ping_lifetime_io;
+  uint8_t compress_pings;
+  FfiStr extra_server_endpoints;
 } FfiConfiguration;
 
 typedef const char *const *RawStringArray;
@@ -253,8 +255,9 @@ typedef struct {
  *   FfiPingUploadTask_Tag tag;
  *   char *document_id;
  *   char *path;
- *   char *body;
+ *   ByteBuffer body;
  *   char *headers;
+ *   char *server_url;
  * } FfiPingUploadTask_Upload_Body;
  *
  * typedef union {
@@ -272,6 +275,12 @@ typedef struct {
  *
  * String fields are encoded into null-terminated UTF-8 C strings.
  *
+ * The `body` is handed over as the raw request bytes, without copying them into a string.
+ * It is gzip compressed, unless `compress_pings` was disabled in the configuration.
+ *
+ * The `server_url` is the endpoint the ping is sent to, if it is configured in
+ * `extra_server_endpoints`. It is a null pointer if the default endpoint should be used.
+ *
  * * The language binding should copy out the data and turn these into their equivalent string type.
  * * The language binding should _not_ free these fields individually.
  *   Instead `glean_process_ping_upload_response` will receive the whole enum, taking care of
@@ -295,6 +304,7 @@ typedef struct {
   char *path;
   ByteBuffer body;
   char *headers;
+  char *server_url;
 } FfiPingUploadTask_Upload_Body;
 
 typedef union {
@@ -331,6 +341,15 @@ void glean_enable_logging(void);
  */
 void glean_enable_logging_to_fd(uint64_t fd);
 
+/**
+ * Enables or disables the panic-safe mode.
+ *
+ * By default a panic inside Glean aborts the process.
+ * In panic-safe mode it is caught and logged instead,
+ * and the failing call returns its default value.
+ */
+void glean_set_panic_safe_mode(uint8_t enabled);
+
 /**
  * # Safety
  *
@@ -527,6 +546,27 @@ uint8_t glean_event_test_has_value(uint64_t metric_id, FfiStr storage_name);
 
 char *glean_event_test_get_value_as_json_string(uint64_t metric_id, FfiStr storage_name);
 
+void glean_destroy_gauge_metric(uint64_t v);
+
+uint64_t glean_new_gauge_metric(FfiStr category,
+                                FfiStr name,
+                                RawStringArray send_in_pings,
+                                int32_t send_in_pings_len,
+                                Lifetime lifetime,
+                                uint8_t disabled);
+
+int32_t glean_gauge_test_get_num_recorded_errors(uint64_t metric_id,
+                                                 int32_t error_type,
+                                                 FfiStr storage_name);
+
+void glean_gauge_set(uint64_t metric_id, int64_t value);
+
+void glean_gauge_add(uint64_t metric_id, int64_t delta);
+
+uint8_t glean_gauge_test_has_value(uint64_t metric_id, FfiStr storage_name);
+
+int64_t glean_gauge_test_get_value(uint64_t metric_id, FfiStr storage_name);
+
 void glean_destroy_jwe_metric(uint64_t v);
 
 uint64_t glean_new_jwe_metric(FfiStr category,
@@ -624,6 +664,30 @@ int32_t glean_labeled_string_test_get_num_recorded_errors(uint64_t metric_id,
                                                           int32_t error_type,
                                                           FfiStr storage_name);
 
+void glean_destroy_labeled_memory_distribution_metric(uint64_t v);
+
+/**
+ * Create a new labeled metric.
+ */
+uint64_t glean_new_labeled_memory_distribution_metric(FfiStr category,
+                                                      FfiStr name,
+                                                      RawStringArray send_in_pings,
+                                                      int32_t send_in_pings_len,
+                                                      int32_t lifetime,
+                                                      uint8_t disabled,
+                                                      RawStringArray labels,
+                                                      int32_t label_count,
+                                                      MemoryUnit memory_unit);
+
+/**
+ * Create a new instance of the sub-metric of this labeled metric.
+ */
+uint64_t glean_labeled_memory_distribution_metric_get(uint64_t handle, FfiStr label);
+
+int32_t glean_labeled_memory_distribution_test_get_num_recorded_errors(uint64_t metric_id,
+                                                                       int32_t error_type,
+                                                                       FfiStr storage_name);
+
 void glean_destroy_memory_distribution_metric(uint64_t v);
 
 uint64_t glean_new_memory_distribution_metric(FfiStr category,
@@ -649,6 +713,25 @@ uint8_t glean_memory_distribution_test_has_value(uint64_t metric_id, FfiStr stor
 char *glean_memory_distribution_test_get_value_as_json_string(uint64_t metric_id,
                                                               FfiStr storage_name);
 
+void glean_destroy_object_metric(uint64_t v);
+
+uint64_t glean_new_object_metric(FfiStr category,
+                                 FfiStr name,
+                                 RawStringArray send_in_pings,
+                                 int32_t send_in_pings_len,
+                                 Lifetime lifetime,
+                                 uint8_t disabled);
+
+int32_t glean_object_test_get_num_recorded_errors(uint64_t metric_id,
+                                                  int32_t error_type,
+                                                  FfiStr storage_name);
+
+void glean_object_set(uint64_t metric_id, FfiStr value);
+
+uint8_t glean_object_test_has_value(uint64_t metric_id, FfiStr storage_name);
+
+char *glean_object_test_get_value_as_json_string(uint64_t metric_id, FfiStr storage_name);
+
 void glean_destroy_ping_type(uint64_t v);
 
 uint64_t glean_new_ping_type(FfiStr ping_name,
@@ -676,10 +759,35 @@ int32_t glean_quantity_test_get_num_recorded_errors(uint64_t metric_id,
 
 void glean_quantity_set(uint64_t metric_id, int64_t value);
 
+void glean_quantity_add(uint64_t metric_id, int64_t amount);
+
 uint8_t glean_quantity_test_has_value(uint64_t metric_id, FfiStr storage_name);
 
 int64_t glean_quantity_test_get_value(uint64_t metric_id, FfiStr storage_name);
 
+void glean_destroy_rate_metric(uint64_t v);
+
+uint64_t glean_new_rate_metric(FfiStr category,
+                               FfiStr name,
+                               RawStringArray send_in_pings,
+                               int32_t send_in_pings_len,
+                               Lifetime lifetime,
+                               uint8_t disabled);
+
+int32_t glean_rate_test_get_num_recorded_errors(uint64_t metric_id,
+                                                int32_t error_type,
+                                                FfiStr storage_name);
+
+void glean_rate_add_to_numerator(uint64_t metric_id, int32_t amount);
+
+void glean_rate_add_to_denominator(uint64_t metric_id, int32_t amount);
+
+uint8_t glean_rate_test_has_value(uint64_t metric_id, FfiStr storage_name);
+
+int32_t glean_rate_test_get_numerator(uint64_t metric_id, FfiStr storage_name);
+
+int32_t glean_rate_test_get_denominator(uint64_t metric_id, FfiStr storage_name);
+
 void glean_destroy_string_metric(uint64_t v);
 
 uint64_t glean_new_string_metric(FfiStr category,
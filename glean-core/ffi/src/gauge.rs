@@ -0,0 +1,36 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use ffi_support::FfiStr;
+
+use crate::{define_metric, handlemap_ext::HandleMapExtension, with_glean_value, Lifetime};
+
+define_metric!(GaugeMetric => GAUGE_METRICS {
+    new           -> glean_new_gauge_metric(),
+    test_get_num_recorded_errors -> glean_gauge_test_get_num_recorded_errors,
+    destroy       -> glean_destroy_gauge_metric,
+
+    set -> glean_gauge_set(value: i64),
+    add -> glean_gauge_add(delta: i64),
+});
+
+#[no_mangle]
+pub extern "C" fn glean_gauge_test_has_value(metric_id: u64, storage_name: FfiStr) -> u8 {
+    with_glean_value(|glean| {
+        GAUGE_METRICS.call_infallible(metric_id, |metric| {
+            metric
+                .test_get_value(glean, storage_name.as_str())
+                .is_some()
+        })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glean_gauge_test_get_value(metric_id: u64, storage_name: FfiStr) -> i64 {
+    with_glean_value(|glean| {
+        GAUGE_METRICS.call_infallible(metric_id, |metric| {
+            metric.test_get_value(glean, storage_name.as_str()).unwrap()
+        })
+    })
+}
@@ -14,22 +14,69 @@
 //! using returned values.
 //! This is only relevant for creation of the main object and metrics as its the only things where
 //! we return something potentially fallible.
+//!
+//! By default a panic inside an FFI call aborts the process, so it never unwinds into the caller.
+//! In panic-safe mode (see [`set_panic_safe_mode`]) panics are caught instead,
+//! logged and the default FFI value is returned.
 
 use std::panic::UnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use ffi_support::{ConcurrentHandleMap, ExternError, IntoFfi};
 
+/// Whether panics in FFI calls are caught and logged, instead of aborting the process.
+static PANIC_SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables the panic-safe mode.
+pub fn set_panic_safe_mode(enabled: bool) {
+    PANIC_SAFE_MODE.store(enabled, Ordering::SeqCst);
+}
+
+/// Whether the panic-safe mode is enabled.
+pub fn panic_safe_mode() -> bool {
+    PANIC_SAFE_MODE.load(Ordering::SeqCst)
+}
+
+/// Calls the callback, never letting a panic unwind into the FFI caller.
+///
+/// In panic-safe mode a panic is reported through `error`,
+/// otherwise the process is aborted.
+pub fn call_with_result<R, E, F>(error: &mut ExternError, callback: F) -> R::Value
+where
+    F: UnwindSafe + FnOnce() -> Result<R, E>,
+    E: Into<ExternError>,
+    R: IntoFfi,
+{
+    if panic_safe_mode() {
+        ffi_support::call_with_result(error, callback)
+    } else {
+        ffi_support::abort_on_panic::call_with_result(error, callback)
+    }
+}
+
 pub fn handle_result<R, F>(callback: F) -> R::Value
 where
     F: UnwindSafe + FnOnce() -> Result<R, glean_core::Error>,
     R: IntoFfi,
 {
     let mut error = ffi_support::ExternError::success();
-    let res = ffi_support::abort_on_panic::call_with_result(&mut error, callback);
+    let res = call_with_result(&mut error, callback);
     log_if_error(error);
     res
 }
 
+/// Handles the error of an infallible call.
+///
+/// The only possible errors are panics (or invalid handles),
+/// which are logged in panic-safe mode.
+fn check_infallible(error: ExternError) {
+    if panic_safe_mode() {
+        log_if_error(error);
+    } else {
+        debug_assert!(error.get_code().is_success());
+    }
+}
+
 /// Warns if an error occurred and then release the allocated memory.
 ///
 /// This is a helper for the case where we aren't exposing this back over the FFI.
@@ -122,7 +169,7 @@ impl<T> HandleMapExtension for ConcurrentHandleMap<T> {
     {
         let mut error = ExternError::success();
         let res = self.call_with_output(&mut error, h, callback);
-        debug_assert!(error.get_code().is_success());
+        check_infallible(error);
         res
     }
 
@@ -133,7 +180,7 @@ impl<T> HandleMapExtension for ConcurrentHandleMap<T> {
     {
         let mut error = ExternError::success();
         let res = self.call_with_output_mut(&mut error, h, callback);
-        debug_assert!(error.get_code().is_success());
+        check_infallible(error);
         res
     }
 
@@ -148,3 +195,33 @@ impl<T> HandleMapExtension for ConcurrentHandleMap<T> {
         res
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn panics_are_contained_in_panic_safe_mode() {
+        set_panic_safe_mode(true);
+
+        let res: u8 = handle_result(|| -> Result<u8, glean_core::Error> {
+            panic!("handle_result should not unwind");
+        });
+        assert_eq!(0, res);
+
+        let map: ConcurrentHandleMap<String> = ConcurrentHandleMap::new();
+        let handle = map.insert_with_log(|| Ok("glean".to_string()));
+        assert_ne!(0, handle);
+
+        let res: u8 = map.call_infallible(handle, |value| -> u8 {
+            // A conversion gone wrong.
+            value.parse().unwrap()
+        });
+        assert_eq!(0, res);
+
+        // The panic poisoned the handle map,
+        // later calls return the default value instead of panicking again.
+        let len: u32 = map.call_infallible(handle, |value| value.len() as u32);
+        assert_eq!(0, len);
+    }
+}
@@ -8,6 +8,7 @@ use glean_core::{metrics::*, CommonMetricData, Lifetime};
 
 use crate::boolean::BOOLEAN_METRICS;
 use crate::counter::COUNTER_METRICS;
+use crate::memory_distribution::MEMORY_DISTRIBUTION_METRICS;
 use crate::string::STRING_METRICS;
 use crate::*;
 
@@ -17,7 +18,9 @@ use crate::*;
 /// `LabeledMetric::new` and LabeledMetric.get`.
 /// The constructor function takes the general common meta data.
 ///
-/// If any additional data needs to be passed in, this macro cannot be used.
+/// Additional (non-common) arguments of the underlying metric's constructor
+/// can be appended, e.g. `memory_unit: MemoryUnit`.
+/// They are passed after the labels.
 ///
 /// Arguments:
 ///
@@ -27,8 +30,10 @@ use crate::*;
 /// * `new_name` - Function name to create a new labeled metric of this type.
 /// * `destroy_name` - Function name to destroy the labeled metric.
 /// * `get_name` - Function name to get a new instance of the underlying metric.
+/// * `test_get_num_recorded_errors` - Function name to get the number of recorded errors.
+/// * `new_argname: new_argtyp` - (optional) Additional arguments of the underlying metric's constructor.
 macro_rules! impl_labeled_metric {
-    ($metric:ty, $global:ident, $metric_global:ident, $new_name:ident, $destroy_name:ident, $get_name:ident, $test_get_num_recorded_errors:ident) => {
+    ($metric:ty, $global:ident, $metric_global:ident, $new_name:ident, $destroy_name:ident, $get_name:ident, $test_get_num_recorded_errors:ident $(, $new_argname:ident: $new_argtyp:ty)*) => {
         static $global: once_cell::sync::Lazy<ConcurrentHandleMap<LabeledMetric<$metric>>> =
             once_cell::sync::Lazy::new(ConcurrentHandleMap::new);
         $crate::define_infallible_handle_map_deleter!($global, $destroy_name);
@@ -44,6 +49,7 @@ macro_rules! impl_labeled_metric {
             disabled: u8,
             labels: RawStringArray,
             label_count: i32,
+            $($new_argname: $new_argtyp),*
         ) -> u64 {
             $global.insert_with_log(|| {
                 let name = name.to_string_fallible()?;
@@ -58,14 +64,17 @@ macro_rules! impl_labeled_metric {
                 let lifetime = Lifetime::try_from(lifetime)?;
 
                 Ok(LabeledMetric::new(
-                    <$metric>::new(CommonMetricData {
-                        name,
-                        category,
-                        send_in_pings,
-                        lifetime,
-                        disabled: disabled != 0,
-                        ..Default::default()
-                    }),
+                    <$metric>::new(
+                        CommonMetricData {
+                            name,
+                            category,
+                            send_in_pings,
+                            lifetime,
+                            disabled: disabled != 0,
+                            ..Default::default()
+                        },
+                        $($new_argname),*
+                    ),
                     labels,
                 ))
             })
@@ -136,3 +145,15 @@ impl_labeled_metric!(
     glean_labeled_string_metric_get,
     glean_labeled_string_test_get_num_recorded_errors
 );
+
+// Create the required FFI functions for LabeledMetric<MemoryDistributionMetric>
+impl_labeled_metric!(
+    MemoryDistributionMetric,
+    LABELED_MEMORY_DISTRIBUTION,
+    MEMORY_DISTRIBUTION_METRICS,
+    glean_new_labeled_memory_distribution_metric,
+    glean_destroy_labeled_memory_distribution_metric,
+    glean_labeled_memory_distribution_metric_get,
+    glean_labeled_memory_distribution_test_get_num_recorded_errors,
+    memory_unit: MemoryUnit
+);
@@ -2,10 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::ffi::CStr;
 use std::os::raw::c_char;
 use std::panic::UnwindSafe;
+use std::sync::PoisonError;
 
 use ffi_support::{define_string_destructor, ConcurrentHandleMap, FfiStr, IntoFfi};
 
@@ -15,6 +17,7 @@ use once_cell::sync::OnceCell;
 pub use glean_core::metrics::MemoryUnit;
 pub use glean_core::metrics::TimeUnit;
 pub use glean_core::upload::ffi_upload_result::*;
+use glean_core::upload::PingUploadTask;
 use glean_core::Glean;
 pub use glean_core::Lifetime;
 
@@ -28,12 +31,15 @@ mod datetime;
 mod event;
 mod ffi_string_ext;
 mod from_raw;
+mod gauge;
 mod handlemap_ext;
 mod jwe;
 mod labeled;
 mod memory_distribution;
+mod object;
 pub mod ping_type;
 mod quantity;
+mod rate;
 mod string;
 mod string_list;
 mod timespan;
@@ -63,17 +69,14 @@ where
     R: IntoFfi,
 {
     let mut error = ffi_support::ExternError::success();
-    let res =
-        ffi_support::abort_on_panic::call_with_result(
-            &mut error,
-            || match glean_core::global_glean() {
-                Some(glean) => {
-                    let glean = glean.lock().unwrap();
-                    callback(&glean)
-                }
-                None => Err(glean_core::Error::not_initialized()),
-            },
-        );
+    let res = handlemap_ext::call_with_result(&mut error, || match glean_core::global_glean() {
+        Some(glean) => {
+            // In panic-safe mode a caught panic may leave the lock poisoned.
+            let glean = glean.lock().unwrap_or_else(PoisonError::into_inner);
+            callback(&glean)
+        }
+        None => Err(glean_core::Error::not_initialized()),
+    });
     handlemap_ext::log_if_error(error);
     res
 }
@@ -91,17 +94,14 @@ where
     R: IntoFfi,
 {
     let mut error = ffi_support::ExternError::success();
-    let res =
-        ffi_support::abort_on_panic::call_with_result(
-            &mut error,
-            || match glean_core::global_glean() {
-                Some(glean) => {
-                    let mut glean = glean.lock().unwrap();
-                    callback(&mut glean)
-                }
-                None => Err(glean_core::Error::not_initialized()),
-            },
-        );
+    let res = handlemap_ext::call_with_result(&mut error, || match glean_core::global_glean() {
+        Some(glean) => {
+            // In panic-safe mode a caught panic may leave the lock poisoned.
+            let mut glean = glean.lock().unwrap_or_else(PoisonError::into_inner);
+            callback(&mut glean)
+        }
+        None => Err(glean_core::Error::not_initialized()),
+    });
     handlemap_ext::log_if_error(error);
     res
 }
@@ -219,6 +219,16 @@ pub unsafe extern "C" fn glean_enable_logging_to_fd(fd: u64) {
     };
 }
 
+/// Enables or disables the panic-safe mode.
+///
+/// By default a panic inside Glean aborts the process.
+/// In panic-safe mode it is caught and logged instead,
+/// and the failing call returns its default value.
+#[no_mangle]
+pub extern "C" fn glean_set_panic_safe_mode(enabled: u8) {
+    handlemap_ext::set_panic_safe_mode(enabled != 0);
+}
+
 /// Configuration over FFI.
 ///
 /// **CAUTION**: This must match _exactly_ the definition on the Kotlin side.
@@ -231,6 +241,8 @@ pub struct FfiConfiguration<'a> {
     pub upload_enabled: u8,
     pub max_events: Option<&'a i32>,
     pub delay_ping_lifetime_io: u8,
+    pub compress_pings: u8,
+    pub extra_server_endpoints: FfiStr<'a>,
 }
 
 /// Convert the FFI-compatible configuration object into the proper Rust configuration object.
@@ -238,12 +250,18 @@ impl TryFrom<&FfiConfiguration<'_>> for glean_core::Configuration {
     type Error = glean_core::Error;
 
     fn try_from(cfg: &FfiConfiguration) -> Result<Self, Self::Error> {
-        let data_path = cfg.data_dir.to_string_fallible()?;
+        let data_path = cfg.data_dir.to_string_fallible()?.into();
         let application_id = cfg.package_name.to_string_fallible()?;
         let language_binding_name = cfg.language_binding_name.to_string_fallible()?;
         let upload_enabled = cfg.upload_enabled != 0;
         let max_events = cfg.max_events.filter(|&&i| i >= 0).map(|m| *m as usize);
         let delay_ping_lifetime_io = cfg.delay_ping_lifetime_io != 0;
+        let compress_pings = cfg.compress_pings != 0;
+        // A JSON object mapping ping names to server endpoints, or null.
+        let extra_server_endpoints = match cfg.extra_server_endpoints.as_opt_str() {
+            Some(endpoints) => serde_json::from_str(endpoints)?,
+            None => HashMap::new(),
+        };
 
         Ok(Self {
             upload_enabled,
@@ -252,6 +270,13 @@ impl TryFrom<&FfiConfiguration<'_>> for glean_core::Configuration {
             language_binding_name,
             max_events,
             delay_ping_lifetime_io,
+            compress_pings,
+            max_pings_per_minute: None,
+            extra_server_endpoints,
+            disable_internal_error_metrics: false,
+            rate_limit: None,
+            event_dedup_window: None,
+            enable_trace_context: false,
         })
     }
 }
@@ -400,7 +425,12 @@ pub extern "C" fn glean_is_first_run() -> u8 {
 #[no_mangle]
 pub extern "C" fn glean_get_upload_task(result: *mut FfiPingUploadTask) {
     with_glean_value(|glean| {
-        let ffi_task = FfiPingUploadTask::from(glean.get_upload_task());
+        let task = glean.get_upload_task();
+        let server_url = match &task {
+            PingUploadTask::Upload(request) => glean.server_endpoint_for(request),
+            _ => None,
+        };
+        let ffi_task = FfiPingUploadTask::new(task, server_url);
         unsafe {
             std::ptr::write(result, ffi_task);
         }
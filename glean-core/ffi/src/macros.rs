@@ -4,7 +4,7 @@ macro_rules! define_infallible_handle_map_deleter {
         #[no_mangle]
         pub extern "C" fn $destructor_name(v: u64) {
             let mut error = ffi_support::ExternError::success();
-            let res = ffi_support::abort_on_panic::call_with_result(&mut error, || {
+            let res = $crate::handlemap_ext::call_with_result(&mut error, || {
                 let map: &$crate::ConcurrentHandleMap<_> = &*$HANDLE_MAP_NAME;
                 map.delete_u64(v)
             });
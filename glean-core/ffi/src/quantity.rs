@@ -12,6 +12,7 @@ define_metric!(QuantityMetric => QUANTITY_METRICS {
     destroy       -> glean_destroy_quantity_metric,
 
     set -> glean_quantity_set(value: i64),
+    add -> glean_quantity_add(amount: i64),
 });
 
 #[no_mangle]
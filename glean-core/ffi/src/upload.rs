@@ -9,6 +9,7 @@
 
 use std::ffi::CString;
 use std::os::raw::c_char;
+use std::ptr;
 
 use ffi_support::IntoFfi;
 
@@ -67,8 +68,9 @@ pub mod upload_result {
 ///   FfiPingUploadTask_Tag tag;
 ///   char *document_id;
 ///   char *path;
-///   char *body;
+///   ByteBuffer body;
 ///   char *headers;
+///   char *server_url;
 /// } FfiPingUploadTask_Upload_Body;
 ///
 /// typedef union {
@@ -86,6 +88,12 @@ pub mod upload_result {
 ///
 /// String fields are encoded into null-terminated UTF-8 C strings.
 ///
+/// The `body` is handed over as the raw request bytes, without copying them into a string.
+/// It is gzip compressed, unless `compress_pings` was disabled in the configuration.
+///
+/// The `server_url` is the endpoint the ping is sent to, if it is configured in
+/// `extra_server_endpoints`. It is a null pointer if the default endpoint should be used.
+///
 /// * The language binding should copy out the data and turn these into their equivalent string type.
 /// * The language binding should _not_ free these fields individually.
 ///   Instead `glean_process_ping_upload_response` will receive the whole enum, taking care of
@@ -103,13 +111,20 @@ pub enum FfiPingUploadTask {
         path: *mut c_char,
         body: ByteBuffer,
         headers: *mut c_char,
+        server_url: *mut c_char,
     },
     Wait,
     Done,
 }
 
-impl From<PingUploadTask> for FfiPingUploadTask {
-    fn from(task: PingUploadTask) -> Self {
+impl FfiPingUploadTask {
+    /// Converts an upload task into its FFI-compatible representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The upload task.
+    /// * `server_url` - The endpoint an uploaded ping is sent to, or `None` for the default endpoint.
+    pub fn new(task: PingUploadTask, server_url: Option<&str>) -> Self {
         match task {
             PingUploadTask::Upload(request) => {
                 // Safe unwraps:
@@ -119,14 +134,20 @@ impl From<PingUploadTask> for FfiPingUploadTask {
                 let path = CString::new(request.path.to_owned()).unwrap();
                 let headers =
                     CString::new(serde_json::to_string(&request.headers).unwrap()).unwrap();
+                // A URL containing a null byte can't be handed over, the default endpoint is used instead.
+                let server_url = server_url
+                    .and_then(|url| CString::new(url).ok())
+                    .map_or(ptr::null_mut(), CString::into_raw);
                 FfiPingUploadTask::Upload {
                     document_id: document_id.into_raw(),
                     path: path.into_raw(),
                     body: ByteBuffer::from_vec(request.body),
                     headers: headers.into_raw(),
+                    server_url,
                 }
             }
-            PingUploadTask::Wait => FfiPingUploadTask::Wait,
+            // The language bindings use their own backoff, so the suggested wait time is dropped.
+            PingUploadTask::Wait(_) => FfiPingUploadTask::Wait,
             PingUploadTask::Done => FfiPingUploadTask::Done,
         }
     }
@@ -139,13 +160,16 @@ impl Drop for FfiPingUploadTask {
             path,
             body,
             headers,
+            server_url,
         } = self
         {
             // We need to free the previously allocated strings before dropping.
+            // Freeing a null `server_url` does nothing.
             unsafe {
                 glean_str_free(*document_id);
                 glean_str_free(*path);
                 glean_str_free(*headers);
+                glean_str_free(*server_url);
             }
             // Unfortunately, we cannot directly call `body.destroy();` as
             // we're behind a mutable reference, so we have to manually take the
@@ -141,7 +141,9 @@ func withFfiConfiguration<R>(
         language_binding_name: languageBindingName,
         upload_enabled: uploadEnabled.toByte(),
         max_events: maxEventsPtr,
-        delay_ping_lifetime_io: false.toByte()
+        delay_ping_lifetime_io: false.toByte(),
+        compress_pings: true.toByte(),
+        extra_server_endpoints: nil
     )
     return body(cfg)
 }
@@ -101,6 +101,8 @@ def make_config(
     cfg.upload_enabled = upload_enabled
     cfg.max_events = max_events
     cfg.delay_ping_lifetime_io = False
+    cfg.compress_pings = True
+    cfg.extra_server_endpoints = ffi.NULL
 
     # This ensures the ffi objects created live as long as cfg lives,
     # otherwise they get garbage collected once this function returns.
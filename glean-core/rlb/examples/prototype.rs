@@ -42,21 +42,20 @@ fn main() -> Result<(), Error> {
         root.path().display().to_string()
     };
 
-    let cfg = Configuration {
-        data_path,
-        application_id: "org.mozilla.glean_core.example".into(),
-        upload_enabled: true,
-        max_events: None,
-        delay_ping_lifetime_io: false,
-        channel: None,
-    };
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id("org.mozilla.glean_core.example")
+        .upload_enabled(true)
+        .build()
+        .expect("The example configuration must be valid");
 
     let client_info = ClientInfoMetrics {
         app_build: env!("CARGO_PKG_VERSION").to_string(),
         app_display_version: env!("CARGO_PKG_VERSION").to_string(),
+        locale: None,
     };
 
-    glean::initialize(cfg, client_info);
+    glean::initialize(cfg, client_info).unwrap();
     glean::register_ping_type(&PrototypePing);
 
     glean_metrics::sample_boolean.set(true);
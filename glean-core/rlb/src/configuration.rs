@@ -2,6 +2,20 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::net::PingUploader;
+
+/// The shortest interval the `events` ping can be submitted periodically at.
+pub(crate) const MIN_EVENTS_PING_INTERVAL: Duration = Duration::from_secs(1);
+
 /// The Glean configuration.
 ///
 /// Optional values will be filled in with default values.
@@ -10,13 +24,791 @@ pub struct Configuration {
     /// Whether upload should be enabled.
     pub upload_enabled: bool,
     /// Path to a directory to store all data in.
-    pub data_path: String,
+    pub data_path: PathBuf,
     /// The application ID (will be sanitized during initialization).
     pub application_id: String,
     /// The maximum number of events to store before sending a ping containing events.
     pub max_events: Option<usize>,
     /// Whether Glean should delay persistence of data from metrics with ping lifetime.
     pub delay_ping_lifetime_io: bool,
+    /// Whether ping request bodies are gzip compressed before upload.
+    pub compress_pings: bool,
     /// The release channel the application is on, if known.
     pub channel: Option<String>,
+    /// The maximum number of pending pings. The oldest pings are deleted once exceeded.
+    pub max_pending_pings: Option<usize>,
+    /// The uploader to send pings with. Pings are not uploaded if not set.
+    pub uploader: Option<Arc<dyn PingUploader>>,
+    /// The server pings are sent to. Defaults to `https://incoming.telemetry.mozilla.org`.
+    pub server_endpoint: Option<String>,
+    /// A suffix appended to ping names when uploading, e.g. `staging` uploads
+    /// the `baseline` ping as `baseline-staging`. Stored data uses the ping names as defined.
+    pub ping_name_suffix: Option<String>,
+    /// How long `glean::shutdown` waits for pending tasks. Waits as long as needed if not set.
+    pub dispatcher_flush_timeout: Option<Duration>,
+    /// How often the `events` ping is submitted with the reason `periodic`, at least every second.
+    /// Not submitted periodically if not set.
+    pub events_ping_interval: Option<Duration>,
+    /// The maximum number of times each ping can be submitted per minute. Not limited if not set.
+    pub max_pings_per_minute: Option<u32>,
+    /// Servers for pings not sent to `server_endpoint`, by ping name.
+    /// Ping names may contain `*` wildcards, matching any sequence of characters.
+    pub extra_server_endpoints: HashMap<String, String>,
+    /// Whether pending pings are uploaded before upload is disabled, instead of being deleted.
+    pub upload_pending_pings_before_disabling: bool,
+    /// Whether the `metrics` ping is submitted daily.
+    pub enable_metrics_ping: bool,
+    /// Whether the core metrics ping scheduler is started.
+    /// The `metrics` ping is only submitted daily if `enable_metrics_ping` is set, too.
+    pub use_core_mps: bool,
+    /// Whether Glean's own `glean.error`, `glean.upload`, `glean.database` and `glean.metrics` metrics are not recorded.
+    pub disable_internal_error_metrics: bool,
+    /// The maximum number of ping uploads per interval, as `(max_uploads, interval)`.
+    /// Defaults to 15 uploads every 60 seconds if not set.
+    pub rate_limit: Option<(u32, Duration)>,
+    /// If set, identical events recorded within this window of the last recorded one are dropped.
+    pub event_dedup_window: Option<Duration>,
+    /// Whether pings carry a `traceparent` header with the OpenTelemetry span they were submitted in.
+    /// Only takes effect with the `opentelemetry` feature.
+    pub enable_trace_context: bool,
+    /// The maximum number of tasks queued for processing in the background, once Glean is initialized.
+    /// Further tasks are dropped. Defaults to 1000 if not set.
+    pub max_dispatch_queue: Option<usize>,
+    /// The disk space Glean's data is expected to stay within, in bytes, if known.
+    /// Not enforced by Glean, see `glean::max_storage_bytes`.
+    pub max_storage_bytes: Option<u64>,
+    /// Called once with the outcome of the initialization, if set.
+    pub on_init: Option<InitCallback>,
+}
+
+impl Configuration {
+    /// Creates a new builder for a `Configuration`.
+    ///
+    /// See [`ConfigurationBuilder`](struct.ConfigurationBuilder.html) for the available options.
+    pub fn builder() -> ConfigurationBuilder {
+        ConfigurationBuilder::default()
+    }
+
+    /// Overrides values with the ones set in well-known environment variables.
+    ///
+    /// This is called by `glean::initialize`.
+    /// The following variables are read:
+    ///
+    /// * `GLEAN_UPLOAD_ENABLED` - `0` or `1`
+    /// * `GLEAN_DATA_PATH` - a path to a directory
+    /// * `GLEAN_MAX_EVENTS` - a number
+    /// * `GLEAN_CHANNEL` - the release channel
+    /// * `GLEAN_DELAY_PING_LIFETIME_IO` - `0` or `1`
+    ///
+    /// Invalid values are logged and ignored.
+    pub fn apply_env_overrides(&mut self) {
+        if let Some(value) = env_override("GLEAN_UPLOAD_ENABLED", parse_bool) {
+            self.upload_enabled = value;
+        }
+        // Data paths don't need to be valid unicode.
+        match env::var_os("GLEAN_DATA_PATH") {
+            Some(value) if value.is_empty() => {
+                log::warn!("Ignoring empty value of GLEAN_DATA_PATH");
+            }
+            Some(value) => self.data_path = value.into(),
+            None => {}
+        }
+        if let Some(value) = env_override("GLEAN_MAX_EVENTS", |value| value.parse().ok()) {
+            self.max_events = Some(value);
+        }
+        if let Some(value) = env_override("GLEAN_CHANNEL", |value| Some(value.to_string())) {
+            self.channel = Some(value);
+        }
+        if let Some(value) = env_override("GLEAN_DELAY_PING_LIFETIME_IO", parse_bool) {
+            self.delay_ping_lifetime_io = value;
+        }
+    }
+}
+
+/// A function called once with the outcome of the initialization.
+type InitCallbackFn = Box<dyn FnOnce(Result<(), crate::Error>) + Send>;
+
+/// A callback called once with the outcome of the initialization.
+///
+/// Clones share the callback, so it is only called once,
+/// even if Glean is initialized again with a clone of the configuration.
+#[derive(Clone)]
+pub struct InitCallback(Arc<Mutex<Option<InitCallbackFn>>>);
+
+impl InitCallback {
+    /// Creates a new callback from a function.
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: FnOnce(Result<(), crate::Error>) + Send + 'static,
+    {
+        Self(Arc::new(Mutex::new(Some(Box::new(callback)))))
+    }
+
+    /// Calls the function with the outcome of the initialization, unless it was called before.
+    pub(crate) fn call(&self, result: Result<(), crate::Error>) {
+        let callback = self.0.lock().unwrap().take();
+        if let Some(callback) = callback {
+            callback(result);
+        }
+    }
+}
+
+impl fmt::Debug for InitCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InitCallback").finish()
+    }
+}
+
+/// Reads and parses an environment variable.
+///
+/// # Returns
+///
+/// The parsed value, or `None` if the variable is not set or can't be parsed.
+/// A warning is logged if it can't be parsed.
+fn env_override<T, F>(name: &str, parse: F) -> Option<T>
+where
+    F: FnOnce(&str) -> Option<T>,
+{
+    let value = env::var_os(name)?;
+    let parsed = value.to_str().and_then(parse);
+    if parsed.is_none() {
+        log::warn!("Ignoring invalid value {:?} of {}", value, name);
+    }
+    parsed
+}
+
+/// Parses `0` and `1` as booleans.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+/// The error returned when building an invalid `Configuration`.
+#[derive(Error, Debug, PartialEq)]
+pub enum ConfigurationError {
+    /// No application ID was provided
+    #[error("The application ID must not be empty")]
+    EmptyApplicationId,
+
+    /// The application ID is not a reverse-domain-name style identifier
+    #[error("Invalid application ID: {0:?}")]
+    InvalidApplicationId(String),
+
+    /// No data path was provided
+    #[error("The data path must not be empty")]
+    EmptyDataPath,
+
+    /// The ping name suffix is not made of lowercase ASCII alphanumerics and dashes
+    #[error("Invalid ping name suffix: {0:?}")]
+    InvalidPingNameSuffix(String),
+
+    /// The interval of the periodic `events` ping is shorter than a second
+    #[error("The events ping interval must be at least 1 second, got {0:?}")]
+    EventsPingIntervalTooShort(Duration),
+}
+
+/// A builder for a [`Configuration`](struct.Configuration.html).
+///
+/// ## Example
+///
+/// ```rust
+/// # use glean::Configuration;
+/// let cfg = Configuration::builder()
+///     .data_path("/tmp/data")
+///     .application_id("org.mozilla.glean_core.example")
+///     .upload_enabled(true)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ConfigurationBuilder {
+    upload_enabled: bool,
+    data_path: PathBuf,
+    application_id: String,
+    max_events: Option<usize>,
+    delay_ping_lifetime_io: bool,
+    compress_pings: bool,
+    channel: Option<String>,
+    max_pending_pings: Option<usize>,
+    uploader: Option<Arc<dyn PingUploader>>,
+    server_endpoint: Option<String>,
+    ping_name_suffix: Option<String>,
+    dispatcher_flush_timeout: Option<Duration>,
+    events_ping_interval: Option<Duration>,
+    max_pings_per_minute: Option<u32>,
+    extra_server_endpoints: HashMap<String, String>,
+    upload_pending_pings_before_disabling: bool,
+    enable_metrics_ping: bool,
+    use_core_mps: bool,
+    disable_internal_error_metrics: bool,
+    rate_limit: Option<(u32, Duration)>,
+    event_dedup_window: Option<Duration>,
+    enable_trace_context: bool,
+    max_dispatch_queue: Option<usize>,
+    max_storage_bytes: Option<u64>,
+    on_init: Option<InitCallback>,
+}
+
+impl Default for ConfigurationBuilder {
+    fn default() -> Self {
+        Self {
+            upload_enabled: true,
+            data_path: PathBuf::new(),
+            application_id: String::new(),
+            max_events: None,
+            delay_ping_lifetime_io: false,
+            compress_pings: true,
+            channel: None,
+            max_pending_pings: None,
+            uploader: None,
+            server_endpoint: None,
+            ping_name_suffix: None,
+            dispatcher_flush_timeout: None,
+            events_ping_interval: None,
+            max_pings_per_minute: None,
+            extra_server_endpoints: HashMap::new(),
+            upload_pending_pings_before_disabling: false,
+            enable_metrics_ping: true,
+            use_core_mps: true,
+            disable_internal_error_metrics: false,
+            rate_limit: None,
+            event_dedup_window: None,
+            enable_trace_context: false,
+            max_dispatch_queue: None,
+            max_storage_bytes: None,
+            on_init: None,
+        }
+    }
+}
+
+impl ConfigurationBuilder {
+    /// Sets the path to a directory to store all data in. Required.
+    pub fn data_path<P: Into<PathBuf>>(mut self, value: P) -> Self {
+        self.data_path = value.into();
+        self
+    }
+
+    /// Sets the application ID. Required.
+    ///
+    /// This should be a reverse-domain-name style identifier, e.g. `org.mozilla.example`.
+    pub fn application_id<S: Into<String>>(mut self, value: S) -> Self {
+        self.application_id = value.into();
+        self
+    }
+
+    /// Sets whether upload should be enabled. Defaults to `true`.
+    pub fn upload_enabled(mut self, value: bool) -> Self {
+        self.upload_enabled = value;
+        self
+    }
+
+    /// Sets the maximum number of events to store before sending a ping containing events.
+    pub fn max_events(mut self, value: usize) -> Self {
+        self.max_events = Some(value);
+        self
+    }
+
+    /// Sets whether Glean should delay persistence of data from metrics with ping lifetime.
+    /// Defaults to `false`.
+    pub fn delay_ping_lifetime_io(mut self, value: bool) -> Self {
+        self.delay_ping_lifetime_io = value;
+        self
+    }
+
+    /// Sets whether ping request bodies are gzip compressed before upload.
+    /// Defaults to `true`.
+    pub fn compress_pings(mut self, value: bool) -> Self {
+        self.compress_pings = value;
+        self
+    }
+
+    /// Sets the release channel the application is on.
+    pub fn channel<S: Into<String>>(mut self, value: S) -> Self {
+        self.channel = Some(value.into());
+        self
+    }
+
+    /// Sets the maximum number of pending pings.
+    ///
+    /// Once exceeded, the oldest pending pings are deleted.
+    pub fn max_pending_pings(mut self, value: usize) -> Self {
+        self.max_pending_pings = Some(value);
+        self
+    }
+
+    /// Sets the uploader to send pings with.
+    ///
+    /// Pings are not uploaded if no uploader is set.
+    /// Use [`NoopUploader`](net/struct.NoopUploader.html) to drop them instead.
+    /// With the `reqwest-uploader` feature, `net::ReqwestUploader` sends them over HTTP.
+    pub fn uploader<U: PingUploader + 'static>(mut self, value: U) -> Self {
+        self.uploader = Some(Arc::new(value));
+        self
+    }
+
+    /// Sets the server pings are sent to.
+    ///
+    /// This needs to be a http or https URL.
+    /// If it isn't, Glean logs an error on initialization and uses the default server.
+    pub fn server_endpoint<S: Into<String>>(mut self, value: S) -> Self {
+        self.server_endpoint = Some(value.into());
+        self
+    }
+
+    /// Sends pings with matching names to a different server.
+    ///
+    /// The pattern either is a ping name or contains `*` wildcards, matching any sequence of characters.
+    /// Exact ping names take precedence over patterns with wildcards,
+    /// otherwise the longest matching pattern is used.
+    /// Pings not matching any pattern are sent to the server set with
+    /// [`server_endpoint`](#method.server_endpoint).
+    ///
+    /// The server needs to be a http or https URL.
+    /// If it isn't, Glean logs an error on initialization and uses the default server.
+    pub fn extra_server_endpoint<P: Into<String>, S: Into<String>>(
+        mut self,
+        pattern: P,
+        value: S,
+    ) -> Self {
+        self.extra_server_endpoints
+            .insert(pattern.into(), value.into());
+        self
+    }
+
+    /// Sets whether pending pings are uploaded before upload is disabled.
+    ///
+    /// By default, disabling upload deletes all pending pings right away
+    /// and only the `deletion-request` ping is sent.
+    /// If set, disabling upload first waits until the uploader went through all pending pings,
+    /// then disables upload and sends the `deletion-request` ping.
+    /// Pings that failed to upload with a recoverable error are still deleted at that point,
+    /// so that a server outage can't keep disabling upload waiting.
+    pub fn upload_pending_pings_before_disabling(mut self, value: bool) -> Self {
+        self.upload_pending_pings_before_disabling = value;
+        self
+    }
+
+    /// Sets whether the `metrics` ping is submitted daily. Enabled by default.
+    ///
+    /// The ping is scheduled for 04:00 local time and submitted with the reason
+    /// `today`, `tomorrow` or `reschedule`.
+    /// If it wasn't sent on the current calendar day and that time has passed,
+    /// it is submitted on initialization with the reason `overdue`.
+    /// Without it no scheduler thread is started. The `metrics` ping is still
+    /// registered and can be submitted by the application.
+    pub fn enable_metrics_ping(mut self, value: bool) -> Self {
+        self.enable_metrics_ping = value;
+        self
+    }
+
+    /// Sets whether the core metrics ping scheduler is started. Enabled by default.
+    ///
+    /// Without it no scheduler thread is started, as with
+    /// [`enable_metrics_ping`](Self::enable_metrics_ping).
+    /// The `metrics` ping is still registered and can be submitted by the application.
+    pub fn use_core_mps(mut self, value: bool) -> Self {
+        self.use_core_mps = value;
+        self
+    }
+
+    /// Sets whether Glean's own `glean.error`, `glean.upload`, `glean.database` and `glean.metrics` metrics are not recorded.
+    ///
+    /// With this set, recording these metrics is a no-op, e.g. a string that is too long
+    /// is still truncated, but no `glean.error.invalid_overflow` is counted for it.
+    /// Errors are still logged.
+    /// Only meant for products that can't send these metrics. Disabled by default.
+    pub fn disable_internal_error_metrics(mut self, value: bool) -> Self {
+        self.disable_internal_error_metrics = value;
+        self
+    }
+
+    /// Sets the maximum number of ping uploads per `interval`.
+    ///
+    /// When the limit is reached, the uploader waits until the current interval ends.
+    /// Defaults to 15 uploads every 60 seconds.
+    pub fn rate_limit(mut self, max_uploads: u32, interval: Duration) -> Self {
+        self.rate_limit = Some((max_uploads, interval));
+        self
+    }
+
+    /// Sets a window in which identical events are only recorded once.
+    ///
+    /// Some UI frameworks fire the same event more than once per frame.
+    /// With this set, an event with the same extras as the last recorded event of the same metric,
+    /// within `window` of it, is dropped and counted in `glean.error.event_duplicates_suppressed`.
+    /// Not set by default.
+    pub fn event_dedup_window(mut self, window: Duration) -> Self {
+        self.event_dedup_window = Some(window);
+        self
+    }
+
+    /// Sets whether pings carry a `traceparent` header with the OpenTelemetry span they were submitted in.
+    ///
+    /// This lets pings be correlated with distributed traces.
+    /// Pings submitted outside of a span get no header.
+    /// Only takes effect with the `opentelemetry` feature. Disabled by default.
+    pub fn enable_trace_context(mut self, enabled: bool) -> Self {
+        self.enable_trace_context = enabled;
+        self
+    }
+
+    /// Sets the maximum number of tasks queued for processing in the background.
+    ///
+    /// This applies once Glean is initialized: before that, up to 100 tasks are buffered.
+    /// Metrics recorded while the queue is full are dropped, and the number of dropped tasks
+    /// is reported in `glean.error.dispatch_queue_overflow`. Defaults to 1000.
+    pub fn max_dispatch_queue(mut self, value: usize) -> Self {
+        self.max_dispatch_queue = Some(value);
+        self
+    }
+
+    /// Sets the disk space Glean's data is expected to stay within, in bytes.
+    ///
+    /// Glean doesn't enforce this limit: it is reported by `glean::max_storage_bytes`,
+    /// to be compared with `glean::storage_size_bytes` when monitoring the disk usage.
+    pub fn max_storage_bytes(mut self, value: u64) -> Self {
+        self.max_storage_bytes = Some(value);
+        self
+    }
+
+    /// Sets a function called once with the outcome of the initialization.
+    ///
+    /// The initialization happens in the background, so this lets the application react
+    /// when Glean could not be set up, e.g. by hiding its telemetry settings.
+    /// It is called on the initialization thread,
+    /// or right away if `glean::initialize` fails before starting it.
+    /// It is not called if Glean is already initialized.
+    pub fn on_init<F>(mut self, callback: F) -> Self
+    where
+        F: FnOnce(Result<(), crate::Error>) + Send + 'static,
+    {
+        self.on_init = Some(InitCallback::new(callback));
+        self
+    }
+
+    /// Sets a suffix appended to ping names when uploading.
+    ///
+    /// With a suffix of `staging`, the `baseline` ping is uploaded as `baseline-staging`.
+    /// Pings are still defined and stored under their own name.
+    /// The suffix must be at most 30 lowercase ASCII alphanumerics and dashes.
+    pub fn ping_name_suffix<S: Into<String>>(mut self, value: S) -> Self {
+        self.ping_name_suffix = Some(value.into());
+        self
+    }
+
+    /// Sets how long `glean::shutdown` waits for pending tasks.
+    ///
+    /// If they are not done in time, shutdown skips submitting the `baseline` ping
+    /// and persisting data, and the pending tasks finish in the background.
+    pub fn dispatcher_flush_timeout(mut self, value: Duration) -> Self {
+        self.dispatcher_flush_timeout = Some(value);
+        self
+    }
+
+    /// Sets how often the `events` ping is submitted with the reason `periodic`.
+    ///
+    /// This bounds how long recorded events wait to be sent.
+    /// The ping is only sent if it contains events.
+    /// The interval must be at least 1 second.
+    pub fn events_ping_interval(mut self, value: Duration) -> Self {
+        self.events_ping_interval = Some(value);
+        self
+    }
+
+    /// Sets the maximum number of times each ping can be submitted per minute.
+    ///
+    /// Further submissions within a minute are dropped,
+    /// recording a `glean.error.ping_rate_limit_exceeded` error for the ping.
+    /// The `baseline` and `deletion-request` pings are never limited.
+    pub fn max_pings_per_minute(mut self, value: u32) -> Self {
+        self.max_pings_per_minute = Some(value);
+        self
+    }
+
+    /// Consumes the builder and creates a `Configuration`.
+    ///
+    /// # Errors
+    ///
+    /// If the application ID, the data path or the ping name suffix are missing or invalid,
+    /// or if the `events` ping interval is shorter than a second.
+    pub fn build(self) -> Result<Configuration, ConfigurationError> {
+        validate_application_id(&self.application_id)?;
+
+        if self.data_path.as_os_str().is_empty() {
+            return Err(ConfigurationError::EmptyDataPath);
+        }
+
+        if let Some(suffix) = &self.ping_name_suffix {
+            validate_ping_name_suffix(suffix)?;
+        }
+
+        if let Some(interval) = self.events_ping_interval {
+            if interval < MIN_EVENTS_PING_INTERVAL {
+                return Err(ConfigurationError::EventsPingIntervalTooShort(interval));
+            }
+        }
+
+        Ok(Configuration {
+            upload_enabled: self.upload_enabled,
+            data_path: self.data_path,
+            application_id: self.application_id,
+            max_events: self.max_events,
+            delay_ping_lifetime_io: self.delay_ping_lifetime_io,
+            compress_pings: self.compress_pings,
+            channel: self.channel,
+            max_pending_pings: self.max_pending_pings,
+            uploader: self.uploader,
+            server_endpoint: self.server_endpoint,
+            ping_name_suffix: self.ping_name_suffix,
+            dispatcher_flush_timeout: self.dispatcher_flush_timeout,
+            events_ping_interval: self.events_ping_interval,
+            max_pings_per_minute: self.max_pings_per_minute,
+            extra_server_endpoints: self.extra_server_endpoints,
+            upload_pending_pings_before_disabling: self.upload_pending_pings_before_disabling,
+            enable_metrics_ping: self.enable_metrics_ping,
+            use_core_mps: self.use_core_mps,
+            disable_internal_error_metrics: self.disable_internal_error_metrics,
+            rate_limit: self.rate_limit,
+            event_dedup_window: self.event_dedup_window,
+            enable_trace_context: self.enable_trace_context,
+            max_dispatch_queue: self.max_dispatch_queue,
+            max_storage_bytes: self.max_storage_bytes,
+            on_init: self.on_init,
+        })
+    }
+}
+
+/// Checks that the application ID is a reverse-domain-name style identifier:
+/// dot-separated, non-empty segments of ASCII alphanumerics, dashes and underscores.
+fn validate_application_id(application_id: &str) -> Result<(), ConfigurationError> {
+    if application_id.is_empty() {
+        return Err(ConfigurationError::EmptyApplicationId);
+    }
+
+    let valid = application_id.split('.').all(|segment| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigurationError::InvalidApplicationId(
+            application_id.to_string(),
+        ))
+    }
+}
+
+/// Checks that the ping name suffix is made of 1 to 30
+/// lowercase ASCII alphanumerics and dashes.
+fn validate_ping_name_suffix(suffix: &str) -> Result<(), ConfigurationError> {
+    let valid = !suffix.is_empty()
+        && suffix.len() <= 30
+        && suffix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigurationError::InvalidPingNameSuffix(
+            suffix.to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn builder_sets_all_values() {
+        let cfg = Configuration::builder()
+            .data_path("/tmp/glean")
+            .application_id("org.mozilla.glean_test")
+            .upload_enabled(false)
+            .max_events(10)
+            .delay_ping_lifetime_io(true)
+            .compress_pings(false)
+            .channel("nightly")
+            .max_pending_pings(5)
+            .uploader(crate::test::MockUploader::new())
+            .server_endpoint("http://localhost:8080")
+            .ping_name_suffix("staging")
+            .dispatcher_flush_timeout(Duration::from_secs(2))
+            .events_ping_interval(Duration::from_secs(300))
+            .max_pings_per_minute(20)
+            .extra_server_endpoint("crash", "https://crash.example.com")
+            .upload_pending_pings_before_disabling(true)
+            .enable_metrics_ping(false)
+            .use_core_mps(false)
+            .disable_internal_error_metrics(true)
+            .rate_limit(5, Duration::from_secs(10))
+            .event_dedup_window(Duration::from_millis(16))
+            .enable_trace_context(true)
+            .max_dispatch_queue(50)
+            .max_storage_bytes(1_000_000)
+            .on_init(|_| {})
+            .build()
+            .unwrap();
+
+        assert_eq!(PathBuf::from("/tmp/glean"), cfg.data_path);
+        assert_eq!("org.mozilla.glean_test", cfg.application_id);
+        assert!(!cfg.upload_enabled);
+        assert_eq!(Some(10), cfg.max_events);
+        assert!(cfg.delay_ping_lifetime_io);
+        assert!(!cfg.compress_pings);
+        assert_eq!(Some("nightly".to_string()), cfg.channel);
+        assert_eq!(Some(5), cfg.max_pending_pings);
+        assert!(cfg.uploader.is_some());
+        assert_eq!(
+            Some("http://localhost:8080".to_string()),
+            cfg.server_endpoint
+        );
+        assert_eq!(Some("staging".to_string()), cfg.ping_name_suffix);
+        assert_eq!(Some(Duration::from_secs(2)), cfg.dispatcher_flush_timeout);
+        assert_eq!(Some(Duration::from_secs(300)), cfg.events_ping_interval);
+        assert_eq!(Some(20), cfg.max_pings_per_minute);
+        assert_eq!(
+            Some(&"https://crash.example.com".to_string()),
+            cfg.extra_server_endpoints.get("crash")
+        );
+        assert!(cfg.upload_pending_pings_before_disabling);
+        assert!(!cfg.enable_metrics_ping);
+        assert!(!cfg.use_core_mps);
+        assert!(cfg.disable_internal_error_metrics);
+        assert_eq!(Some((5, Duration::from_secs(10))), cfg.rate_limit);
+        assert_eq!(Some(Duration::from_millis(16)), cfg.event_dedup_window);
+        assert!(cfg.enable_trace_context);
+        assert_eq!(Some(50), cfg.max_dispatch_queue);
+        assert_eq!(Some(1_000_000), cfg.max_storage_bytes);
+        assert!(cfg.on_init.is_some());
+    }
+
+    #[test]
+    fn use_core_mps_is_set_independently_of_enable_metrics_ping() {
+        let cfg = Configuration::builder()
+            .data_path("/tmp/glean")
+            .application_id("org.mozilla.glean_test")
+            .use_core_mps(false)
+            .build()
+            .unwrap();
+
+        assert!(!cfg.use_core_mps);
+        assert!(cfg.enable_metrics_ping);
+    }
+
+    #[test]
+    fn builder_uses_defaults() {
+        let cfg = Configuration::builder()
+            .data_path("/tmp/glean")
+            .application_id("org.mozilla.glean_test")
+            .build()
+            .unwrap();
+
+        assert!(cfg.upload_enabled);
+        assert_eq!(None, cfg.max_events);
+        assert!(!cfg.delay_ping_lifetime_io);
+        assert!(cfg.compress_pings);
+        assert_eq!(None, cfg.channel);
+        assert_eq!(None, cfg.max_pending_pings);
+        assert!(cfg.uploader.is_none());
+        assert_eq!(None, cfg.server_endpoint);
+        assert_eq!(None, cfg.ping_name_suffix);
+        assert_eq!(None, cfg.dispatcher_flush_timeout);
+        assert_eq!(None, cfg.events_ping_interval);
+        assert_eq!(None, cfg.max_pings_per_minute);
+        assert!(cfg.extra_server_endpoints.is_empty());
+        assert!(!cfg.upload_pending_pings_before_disabling);
+        assert!(cfg.enable_metrics_ping);
+        assert!(cfg.use_core_mps);
+        assert!(!cfg.disable_internal_error_metrics);
+        assert_eq!(None, cfg.rate_limit);
+        assert_eq!(None, cfg.event_dedup_window);
+        assert!(!cfg.enable_trace_context);
+        assert_eq!(None, cfg.max_dispatch_queue);
+        assert_eq!(None, cfg.max_storage_bytes);
+        assert!(cfg.on_init.is_none());
+    }
+
+    #[test]
+    fn builder_requires_application_id() {
+        let err = Configuration::builder()
+            .data_path("/tmp/glean")
+            .build()
+            .unwrap_err();
+        assert_eq!(ConfigurationError::EmptyApplicationId, err);
+
+        for invalid in &["org mozilla", "org..mozilla", ".org.mozilla", "org/mozilla"] {
+            let err = Configuration::builder()
+                .data_path("/tmp/glean")
+                .application_id(*invalid)
+                .build()
+                .unwrap_err();
+            assert_eq!(
+                ConfigurationError::InvalidApplicationId(invalid.to_string()),
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn builder_requires_data_path() {
+        let err = Configuration::builder()
+            .application_id("org.mozilla.glean_test")
+            .build()
+            .unwrap_err();
+        assert_eq!(ConfigurationError::EmptyDataPath, err);
+    }
+
+    #[test]
+    fn builder_validates_ping_name_suffix() {
+        for invalid in &[
+            "",
+            "Staging",
+            "stag ing",
+            "staging/",
+            "a-very-long-suffix-of-31-chars-",
+        ] {
+            let err = Configuration::builder()
+                .data_path("/tmp/glean")
+                .application_id("org.mozilla.glean_test")
+                .ping_name_suffix(*invalid)
+                .build()
+                .unwrap_err();
+            assert_eq!(
+                ConfigurationError::InvalidPingNameSuffix(invalid.to_string()),
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn builder_rejects_short_events_ping_intervals() {
+        for invalid in &[Duration::from_secs(0), Duration::from_millis(999)] {
+            let err = Configuration::builder()
+                .data_path("/tmp/glean")
+                .application_id("org.mozilla.glean_test")
+                .events_ping_interval(*invalid)
+                .build()
+                .unwrap_err();
+            assert_eq!(
+                ConfigurationError::EventsPingIntervalTooShort(*invalid),
+                err
+            );
+        }
+
+        let cfg = Configuration::builder()
+            .data_path("/tmp/glean")
+            .application_id("org.mozilla.glean_test")
+            .events_ping_interval(MIN_EVENTS_PING_INTERVAL)
+            .build()
+            .unwrap();
+        assert_eq!(Some(MIN_EVENTS_PING_INTERVAL), cfg.events_ping_interval);
+    }
 }
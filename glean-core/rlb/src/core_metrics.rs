@@ -2,15 +2,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use glean_core::{metrics::StringMetric, CommonMetricData, Lifetime};
+use glean_core::{
+    metrics::{CounterMetric, StringMetric},
+    CommonMetricData, Lifetime,
+};
 
 /// Metrics included in every ping as `client_info`.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClientInfoMetrics {
     /// The build identifier generated by the CI system (e.g. "1234/A").
     pub app_build: String,
     /// The user visible version string (e.g. "1.0.3").
     pub app_display_version: String,
+    /// The locale of the application (e.g. "es-ES").
+    /// If `None`, it is detected from the environment.
+    pub locale: Option<String>,
 }
 
 impl ClientInfoMetrics {
@@ -19,6 +25,57 @@ impl ClientInfoMetrics {
         ClientInfoMetrics {
             app_build: "unknown".to_string(),
             app_display_version: "unknown".to_string(),
+            locale: None,
+        }
+    }
+
+    /// Creates a builder for the client info.
+    ///
+    /// Values that are not set default to `"unknown"`.
+    pub fn builder() -> ClientInfoMetricsBuilder {
+        ClientInfoMetricsBuilder::default()
+    }
+}
+
+/// Builds the [`ClientInfoMetrics`].
+///
+/// Details of the system, like the OS version and the device model,
+/// are detected by Glean itself.
+#[derive(Debug, Default)]
+pub struct ClientInfoMetricsBuilder {
+    app_build: Option<String>,
+    app_display_version: Option<String>,
+    locale: Option<String>,
+}
+
+impl ClientInfoMetricsBuilder {
+    /// Sets the build identifier generated by the CI system (e.g. "1234/A").
+    pub fn app_build<S: Into<String>>(mut self, app_build: S) -> Self {
+        self.app_build = Some(app_build.into());
+        self
+    }
+
+    /// Sets the user visible version string (e.g. "1.0.3").
+    pub fn app_display_version<S: Into<String>>(mut self, app_display_version: S) -> Self {
+        self.app_display_version = Some(app_display_version.into());
+        self
+    }
+
+    /// Sets the locale of the application (e.g. "es-ES"), instead of detecting it.
+    pub fn locale<S: Into<String>>(mut self, locale: S) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Builds the client info.
+    pub fn build(self) -> ClientInfoMetrics {
+        let unknown = ClientInfoMetrics::unknown();
+        ClientInfoMetrics {
+            app_build: self.app_build.unwrap_or(unknown.app_build),
+            app_display_version: self
+                .app_display_version
+                .unwrap_or(unknown.app_display_version),
+            locale: self.locale,
         }
     }
 }
@@ -32,6 +89,7 @@ pub struct InternalMetrics {
     pub architecture: StringMetric,
     pub device_manufacturer: StringMetric,
     pub device_model: StringMetric,
+    pub locale: StringMetric,
 }
 
 impl InternalMetrics {
@@ -93,6 +151,44 @@ impl InternalMetrics {
                 disabled: false,
                 dynamic_label: None,
             }),
+            locale: StringMetric::new(CommonMetricData {
+                name: "locale".into(),
+                category: "".into(),
+                send_in_pings: vec!["glean_client_info".into()],
+                lifetime: Lifetime::Application,
+                disabled: false,
+                dynamic_label: None,
+            }),
+        }
+    }
+}
+
+/// Metrics about tasks dropped by the dispatcher.
+#[derive(Debug)]
+pub struct DispatcherMetrics {
+    pub preinit_tasks_overflow: CounterMetric,
+    pub dispatch_queue_overflow: CounterMetric,
+}
+
+impl DispatcherMetrics {
+    pub fn new() -> Self {
+        Self {
+            preinit_tasks_overflow: CounterMetric::new(CommonMetricData {
+                name: "preinit_tasks_overflow".into(),
+                category: "glean.error".into(),
+                send_in_pings: vec!["metrics".into()],
+                lifetime: Lifetime::Ping,
+                disabled: false,
+                dynamic_label: None,
+            }),
+            dispatch_queue_overflow: CounterMetric::new(CommonMetricData {
+                name: "dispatch_queue_overflow".into(),
+                category: "glean.error".into(),
+                send_in_pings: vec!["metrics".into()],
+                lifetime: Lifetime::Ping,
+                disabled: false,
+                dynamic_label: None,
+            }),
         }
     }
 }
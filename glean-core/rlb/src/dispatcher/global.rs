@@ -2,22 +2,30 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use once_cell::sync::{Lazy, OnceCell};
-use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
-use super::{DispatchError, DispatchGuard, Dispatcher};
+use super::{DispatchError, DispatchGuard, Dispatcher, Overflow, Priority, TimeoutError};
 
-const GLOBAL_DISPATCHER_LIMIT: usize = 100;
+/// The size of the pre-init buffer of the global dispatcher.
+pub(crate) const GLOBAL_DISPATCHER_LIMIT: usize = 100;
 static GLOBAL_DISPATCHER: Lazy<RwLock<Option<Dispatcher>>> =
     Lazy::new(|| RwLock::new(Some(Dispatcher::new(GLOBAL_DISPATCHER_LIMIT))));
 
-fn guard() -> &'static DispatchGuard {
-    static GLOBAL_GUARD: OnceCell<DispatchGuard> = OnceCell::new();
-
-    GLOBAL_GUARD.get_or_init(|| {
-        let lock = GLOBAL_DISPATCHER.read().unwrap();
-        lock.as_ref().unwrap().guard()
-    })
+/// Get a guard for the current global dispatcher.
+///
+/// The guard is not cached, as the global dispatcher can be replaced by [`reset_dispatcher`].
+///
+/// [`reset_dispatcher`]: fn.reset_dispatcher.html
+fn guard() -> DispatchGuard {
+    GLOBAL_DISPATCHER
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|dispatcher| dispatcher.guard())
+        .unwrap()
 }
 
 /// Launches a new task on the global dispatch queue.
@@ -26,15 +34,45 @@ fn guard() -> &'static DispatchGuard {
 /// If the pre-init queue was already flushed,
 /// the background thread will process tasks in the queue (see [`flush_init`]).
 ///
-/// This will not block.
+/// This will not block, unless a high-water mark is set and exceeded (see [`set_high_water_mark`]).
+/// If the queue is full, the task is discarded (see [`set_max_queue_size`]).
 ///
 /// [`flush_init`]: fn.flush_init.html
+/// [`set_high_water_mark`]: fn.set_high_water_mark.html
+/// [`set_max_queue_size`]: fn.set_max_queue_size.html
 pub fn launch(task: impl FnOnce() + Send + 'static) {
-    match guard().launch(task) {
+    launch_with_priority(task, Priority::Normal)
+}
+
+/// Launches a new task on the global dispatch queue with the given priority.
+///
+/// High-priority tasks are processed before any queued task of normal priority.
+/// Ordering is only preserved within a priority level.
+/// They are not limited by the pre-init buffer size and never block.
+/// Tasks of normal priority behave exactly like tasks launched with [`launch`].
+///
+/// [`launch`]: fn.launch.html
+pub fn launch_with_priority(task: impl FnOnce() + Send + 'static, priority: Priority) {
+    let guard = guard();
+    match guard.launch_with_priority(task, priority) {
         Ok(_) => {}
         Err(DispatchError::QueueFull) => {
-            log::info!("Exceeded maximum queue size, discarding task");
-            // TODO: Record this as an error.
+            // Only the first dropped task is logged and schedules the recording,
+            // until the dropped tasks were recorded.
+            if !guard
+                .overflow
+                .recording_scheduled
+                .swap(true, Ordering::SeqCst)
+            {
+                log::error!("Exceeded maximum queue size, discarding tasks");
+                // High-priority tasks are not limited, so this is not dropped.
+                let overflow = Arc::clone(&guard.overflow);
+                let _ =
+                    guard.launch_with_priority(move || record_overflow(&overflow), Priority::High);
+            }
+        }
+        Err(DispatchError::ShutDown) => {
+            log::error!("The dispatcher was shut down, rejecting task");
         }
         Err(_) => {
             log::info!("Failed to launch a task on the queue. Discarding task.");
@@ -42,18 +80,78 @@ pub fn launch(task: impl FnOnce() + Send + 'static) {
     }
 }
 
+/// Records the number of tasks dropped since the last recording.
+///
+/// Tasks dropped from the pre-init buffer are recorded as the number of tasks
+/// that were launched before the buffer was flushed, like in the other Glean SDKs.
+fn record_overflow(overflow: &Overflow) {
+    overflow.recording_scheduled.store(false, Ordering::SeqCst);
+    let preinit = overflow.preinit.swap(0, Ordering::SeqCst);
+    let queue = overflow.queue.swap(0, Ordering::SeqCst);
+
+    let preinit = if preinit > 0 {
+        preinit + GLOBAL_DISPATCHER_LIMIT
+    } else {
+        0
+    };
+    crate::record_dispatcher_overflow(preinit, queue);
+}
+
+/// Sets the maximum number of queued tasks of normal priority, after the pre-init buffer was flushed.
+///
+/// Tasks launched on a full queue are discarded.
+/// `None` removes the limit. Defaults to [`DEFAULT_MAX_QUEUE_SIZE`].
+/// The pre-init buffer always holds 100 tasks.
+///
+/// [`DEFAULT_MAX_QUEUE_SIZE`]: constant.DEFAULT_MAX_QUEUE_SIZE.html
+pub fn set_max_queue_size(max_queue_size: Option<usize>) {
+    if let Some(dispatcher) = GLOBAL_DISPATCHER.read().unwrap().as_ref() {
+        dispatcher.set_max_queue_size(max_queue_size);
+    }
+}
+
+/// Gets the number of queued tasks, including tasks in the pre-init buffer.
+///
+/// This is meant for diagnostics: the queue might change right after this returns.
+pub fn queue_len() -> usize {
+    guard().queue_len()
+}
+
+/// Sets the queue length above which [`launch`] blocks the caller briefly.
+///
+/// This provides back-pressure when the queue backs up, e.g. on a slow disk,
+/// instead of letting it grow without bounds.
+/// `None` disables back-pressure, which is the default.
+/// Launching tasks is never blocked before [`flush_init`] is called.
+///
+/// [`launch`]: fn.launch.html
+/// [`flush_init`]: fn.flush_init.html
+pub fn set_high_water_mark(high_water_mark: Option<usize>) {
+    if let Some(dispatcher) = GLOBAL_DISPATCHER.read().unwrap().as_ref() {
+        dispatcher.set_high_water_mark(high_water_mark);
+    }
+}
+
 /// Block until all tasks prior to this call are processed.
+///
+/// Before the pre-init buffer is flushed, this waits for a pending initialization of Glean first.
+/// If Glean is not initialized, or failed to initialize, it returns right away:
+/// the buffered tasks are not processed until Glean is initialized.
 pub fn block_on_queue() {
-    let guard = {
-        GLOBAL_DISPATCHER
-            .write()
-            .unwrap()
-            .as_ref()
-            .map(|dispatcher| dispatcher.guard())
-            .unwrap()
-    };
+    if guard().queue_preinit.load(Ordering::SeqCst) {
+        crate::wait_for_pending_init();
+    }
+    guard().block_on_queue();
+}
 
-    guard.block_on_queue();
+/// Block until all tasks prior to this call are processed, or the timeout expires.
+///
+/// # Errors
+///
+/// If the tasks were not processed within `duration`.
+/// They are still processed eventually.
+pub fn flush_with_timeout(duration: Duration) -> Result<(), TimeoutError> {
+    guard().block_on_queue_timeout(duration)
 }
 
 /// Starts processing queued tasks in the global dispatch queue.
@@ -61,15 +159,7 @@ pub fn block_on_queue() {
 /// This function blocks until queued tasks prior to this call are finished.
 /// Once the initial queue is empty the dispatcher will wait for new tasks to be launched.
 pub fn flush_init() -> Result<(), DispatchError> {
-    let mut guard = {
-        GLOBAL_DISPATCHER
-            .write()
-            .unwrap()
-            .as_ref()
-            .map(|dispatcher| dispatcher.guard())
-            .unwrap()
-    };
-    guard.flush_init()
+    guard().flush_init()
 }
 
 /// Shuts down the dispatch queue.
@@ -81,6 +171,71 @@ pub fn try_shutdown() -> Result<(), DispatchError> {
     guard().shutdown()
 }
 
+/// Shuts down the global dispatch queue, waiting for the queued tasks for at most `timeout`.
+///
+/// From this call on, new tasks are rejected: [`launch`] logs an error and discards them.
+/// Tasks launched before are processed first, in order.
+/// If the pre-init buffer was not flushed, its tasks are discarded.
+///
+/// # Errors
+///
+/// * [`DispatchError::ShutdownTimeout`] if the queued tasks were not processed within `timeout`.
+///   The remaining tasks are discarded, the task currently running is finished.
+/// * [`DispatchError::ShutDown`] if the dispatch queue was already shut down.
+///
+/// [`launch`]: fn.launch.html
+/// [`DispatchError::ShutdownTimeout`]: enum.DispatchError.html#variant.ShutdownTimeout
+/// [`DispatchError::ShutDown`]: enum.DispatchError.html#variant.ShutDown
+pub fn shutdown(timeout: Duration) -> Result<(), DispatchError> {
+    guard().shutdown_with_timeout(timeout)
+}
+
+/// Shuts down the global dispatcher and replaces it with a new one.
+///
+/// Tasks launched before this call are processed before the old worker thread exits.
+/// If the old dispatcher was never flushed, its queued tasks are discarded.
+/// The new dispatcher starts out in pre-init mode and needs to be flushed again (see [`flush_init`]).
+/// The high-water mark and maximum queue size carry over to the new dispatcher.
+///
+/// This blocks until the old worker thread finished.
+///
+/// [`flush_init`]: fn.flush_init.html
+pub(crate) fn reset_dispatcher() {
+    if let Some(old_dispatcher) = replace_dispatcher() {
+        if let Err(err) = old_dispatcher.shutdown() {
+            log::error!("Failed to shut down the old dispatcher: {}", err);
+        }
+    }
+}
+
+/// Shuts down the global dispatcher and replaces it with a new one,
+/// without waiting for the old worker thread.
+///
+/// Like [`reset_dispatcher`], but the old worker thread processes
+/// the remaining tasks in the background.
+///
+/// [`reset_dispatcher`]: fn.reset_dispatcher.html
+pub(crate) fn reset_dispatcher_detached() {
+    if let Some(old_dispatcher) = replace_dispatcher() {
+        if let Err(err) = old_dispatcher.shutdown_detached() {
+            log::error!("Failed to shut down the old dispatcher: {}", err);
+        }
+    }
+}
+
+/// Replaces the global dispatcher with a new one, returning the old one.
+///
+/// The high-water mark and maximum queue size carry over to the new dispatcher.
+fn replace_dispatcher() -> Option<Dispatcher> {
+    let new_dispatcher = Dispatcher::new(GLOBAL_DISPATCHER_LIMIT);
+    let mut global = GLOBAL_DISPATCHER.write().unwrap();
+    if let Some(old_dispatcher) = global.as_ref() {
+        new_dispatcher.set_high_water_mark(old_dispatcher.high_water_mark());
+        new_dispatcher.set_max_queue_size(old_dispatcher.max_queue_size());
+    }
+    global.replace(new_dispatcher)
+}
+
 #[cfg(test)]
 mod test {
     use std::sync::{Arc, Mutex};
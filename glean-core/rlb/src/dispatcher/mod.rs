@@ -21,27 +21,128 @@
 //!     println!("A second task that's executed sequentially, but off the main thread.");
 //! });
 //! ```
+//!
+//! # Queue limits
+//!
+//! The pre-init buffer holds a fixed number of tasks.
+//! After it was flushed, the queue holds at most [`DEFAULT_MAX_QUEUE_SIZE`] tasks of normal priority,
+//! unless configured otherwise (see [`set_max_queue_size`]).
+//! Tasks launched on a full queue are dropped: the newest task is the one discarded.
+//! The number of dropped tasks is recorded in `glean.error.preinit_tasks_overflow`
+//! and `glean.error.dispatch_queue_overflow`.
+//!
+//! [`DEFAULT_MAX_QUEUE_SIZE`]: constant.DEFAULT_MAX_QUEUE_SIZE.html
+//! [`set_max_queue_size`]: fn.set_max_queue_size.html
+//!
+//! # Back-pressure
+//!
+//! If a high-water mark is set (see [`set_high_water_mark`]), launching a task on a queue
+//! holding more tasks than that blocks the caller briefly, giving the worker thread a chance to catch up.
+//! This never happens in pre-init mode, nor for tasks launched from the worker thread itself.
+//!
+//! [`set_high_water_mark`]: fn.set_high_water_mark.html
+//!
+//! # Priorities
+//!
+//! Tasks launched with [`Priority::High`] are processed before any queued task of normal priority,
+//! in the order they were launched. A task already running is not interrupted.
+//! Glean uses this for ping submissions that should not wait for queued metric recordings,
+//! like the `baseline` ping on shutdown, and for disabling upload
+//! unless pending pings are uploaded before upload is disabled.
+//!
+//! Ordering is only preserved within a priority level: a high-priority task may run before
+//! tasks of normal priority that were launched earlier.
+//!
+//! [`Priority::High`]: enum.Priority.html#variant.High
+//!
+//! # Shutdown
+//!
+//! [`shutdown`] stops accepting new tasks and waits a bounded time for the queued tasks.
+//! Tasks launched afterwards are rejected with [`DispatchError::ShutDown`].
+//!
+//! [`shutdown`]: fn.shutdown.html
+//! [`DispatchError::ShutDown`]: enum.DispatchError.html#variant.ShutDown
 
 // TODO: remove this once bug 1672440 is merged and the code below
 // will actually be used somewhere.
 #![allow(dead_code)]
 
 use std::{
+    any::Any,
     mem,
+    panic::{self, AssertUnwindSafe},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
-use crossbeam_channel::{bounded, unbounded, SendError, Sender, TrySendError};
+use crossbeam_channel::{
+    bounded, unbounded, Receiver, RecvError, Select, SendError, Sender, TryRecvError, TrySendError,
+};
 use thiserror::Error;
 
 pub use global::*;
 
 mod global;
 
+/// The name of the worker thread processing the tasks.
+const WORKER_THREAD_NAME: &str = "glean.dispatcher";
+
+/// The longest a caller is blocked when launching a task on a backed up queue.
+const MAX_BACKPRESSURE_WAIT: Duration = Duration::from_millis(100);
+
+/// How often a blocked caller checks whether the queue drained below the high-water mark.
+const BACKPRESSURE_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The default maximum number of queued tasks of normal priority, after the pre-init buffer was flushed.
+pub const DEFAULT_MAX_QUEUE_SIZE: usize = 1000;
+
+/// The number of tasks that panicked, across all dispatchers.
+static PANIC_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// Gets the number of launched tasks that panicked.
+///
+/// A panicking task doesn't stop the worker thread, the next task is processed as usual.
+pub fn panic_count() -> u64 {
+    PANIC_COUNT.load(Ordering::SeqCst)
+}
+
+/// Runs a task, catching and logging a panic.
+fn run_task(task: Box<dyn FnOnce() + Send>) {
+    if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(task)) {
+        PANIC_COUNT.fetch_add(1, Ordering::SeqCst);
+        log::error!("A dispatched task panicked: {}", panic_message(&*panic));
+    }
+}
+
+/// Extracts the message from a panic payload, if it has one.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "<unknown>"
+    }
+}
+
+/// Whether the current thread is a dispatcher worker thread.
+fn is_worker_thread() -> bool {
+    thread::current().name() == Some(WORKER_THREAD_NAME)
+}
+
+/// The priority of a task.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Priority {
+    /// Processed before any queued task of normal priority.
+    High,
+    /// Processed in the order tasks are launched.
+    Normal,
+}
+
 /// The command a worker should execute.
 enum Command {
     /// A task is a user-defined function to run.
@@ -76,8 +177,21 @@ pub enum DispatchError {
     /// Failed to receive from channel
     #[error("Failed to receive from channel")]
     RecvError(#[from] crossbeam_channel::RecvError),
+
+    /// The dispatcher was shut down and doesn't accept new tasks
+    #[error("The dispatcher was shut down and doesn't accept new tasks")]
+    ShutDown,
+
+    /// Queued tasks were not processed before the shutdown timed out
+    #[error("Queued tasks were not processed before the shutdown timed out")]
+    ShutdownTimeout,
 }
 
+/// The error returned when the dispatch queue did not drain in time.
+#[derive(Error, Debug, PartialEq)]
+#[error("The dispatch queue did not drain within the timeout")]
+pub struct TimeoutError;
+
 impl From<TrySendError<Command>> for DispatchError {
     fn from(err: TrySendError<Command>) -> Self {
         match err {
@@ -93,6 +207,17 @@ impl<T> From<SendError<T>> for DispatchError {
     }
 }
 
+/// The number of tasks dropped because the queue was full.
+#[derive(Debug, Default)]
+struct Overflow {
+    /// Tasks dropped because the pre-init buffer was full.
+    preinit: AtomicUsize,
+    /// Tasks dropped because the queue was full, after the pre-init buffer was flushed.
+    queue: AtomicUsize,
+    /// Whether a task recording the dropped tasks is already queued.
+    recording_scheduled: AtomicBool,
+}
+
 /// A clonable guard for a dispatch queue.
 #[derive(Clone)]
 struct DispatchGuard {
@@ -107,18 +232,127 @@ struct DispatchGuard {
 
     /// Sender for the unbounded queue.
     sender: Sender<Command>,
+
+    /// Sender for the unbounded queue of high-priority tasks.
+    high_priority_sender: Sender<Command>,
+
+    /// The queue length above which callers are blocked briefly.
+    /// `0` disables back-pressure.
+    high_water_mark: Arc<AtomicUsize>,
+
+    /// Whether new tasks are rejected, after a shutdown with a timeout.
+    closed: Arc<AtomicBool>,
+
+    /// Whether the worker thread drops the remaining tasks, after a shutdown timed out.
+    discard: Arc<AtomicBool>,
+
+    /// The maximum number of queued tasks of normal priority, after the pre-init buffer was flushed.
+    /// `0` disables the limit.
+    max_queue_size: Arc<AtomicUsize>,
+
+    /// The number of tasks dropped because the queue was full.
+    overflow: Arc<Overflow>,
 }
 
 impl DispatchGuard {
     pub fn launch(&self, task: impl FnOnce() + Send + 'static) -> Result<(), DispatchError> {
+        self.launch_with_priority(task, Priority::Normal)
+    }
+
+    pub fn launch_with_priority(
+        &self,
+        task: impl FnOnce() + Send + 'static,
+        priority: Priority,
+    ) -> Result<(), DispatchError> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(DispatchError::ShutDown);
+        }
+
         let task = Command::Task(Box::new(task));
-        self.send(task)
+        match priority {
+            Priority::High => {
+                // High-priority tasks are few, so they're neither limited nor throttled.
+                self.high_priority_sender.send(task)?;
+                Ok(())
+            }
+            Priority::Normal => {
+                let preinit = self.queue_preinit.load(Ordering::SeqCst);
+                let result = if !preinit && self.queue_is_full() {
+                    Err(DispatchError::QueueFull)
+                } else {
+                    self.send(task)
+                };
+
+                if result == Err(DispatchError::QueueFull) {
+                    let count = if preinit {
+                        &self.overflow.preinit
+                    } else {
+                        &self.overflow.queue
+                    };
+                    count.fetch_add(1, Ordering::SeqCst);
+                }
+                result
+            }
+        }
+    }
+
+    /// Whether the queue reached its maximum size, after the pre-init buffer was flushed.
+    fn queue_is_full(&self) -> bool {
+        let max_queue_size = self.max_queue_size.load(Ordering::SeqCst);
+        max_queue_size != 0 && self.sender.len() >= max_queue_size
+    }
+
+    /// Gets the number of queued tasks, of any priority.
+    fn queue_len(&self) -> usize {
+        let normal = if self.queue_preinit.load(Ordering::SeqCst) {
+            self.preinit_sender.len()
+        } else {
+            self.sender.len()
+        };
+        normal + self.high_priority_sender.len()
     }
 
     pub fn shutdown(&self) -> Result<(), DispatchError> {
         self.send(Command::Shutdown)
     }
 
+    /// Stops accepting new tasks and waits for the queued tasks to be processed,
+    /// for at most `timeout`.
+    ///
+    /// If they are not processed in time, the remaining tasks are discarded
+    /// and the worker thread exits after the task it is currently running.
+    /// If the pre-init buffer was not flushed, its tasks are discarded right away.
+    fn shutdown_with_timeout(&self, timeout: Duration) -> Result<(), DispatchError> {
+        if self.closed.swap(true, Ordering::SeqCst) {
+            return Err(DispatchError::ShutDown);
+        }
+
+        // The worker thread was never unblocked, so it won't process anything.
+        if self.queue_preinit.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        // A task running on the worker thread can't wait for the queue:
+        // the remaining tasks only run after it.
+        if is_worker_thread() {
+            return self.send(Command::Shutdown);
+        }
+
+        // The channel has room for the message, so the worker doesn't block
+        // if we stopped waiting already.
+        let (tx, rx) = bounded(1);
+        self.send(Command::Task(Box::new(move || {
+            let _ = tx.send(());
+        })))?;
+        self.send(Command::Shutdown)?;
+
+        if rx.recv_timeout(timeout).is_err() {
+            self.discard.store(true, Ordering::SeqCst);
+            return Err(DispatchError::ShutdownTimeout);
+        }
+        Ok(())
+    }
+
     fn send(&self, task: Command) -> Result<(), DispatchError> {
         if self.queue_preinit.load(Ordering::SeqCst) {
             match self.preinit_sender.try_send(task) {
@@ -128,21 +362,73 @@ impl DispatchGuard {
             }
         } else {
             self.sender.send(task)?;
+            self.apply_backpressure();
             Ok(())
         }
     }
 
+    /// Blocks the caller while the queue exceeds the high-water mark,
+    /// for at most [`MAX_BACKPRESSURE_WAIT`](constant.MAX_BACKPRESSURE_WAIT.html).
+    fn apply_backpressure(&self) {
+        let high_water_mark = self.high_water_mark.load(Ordering::SeqCst);
+        // The worker thread would only wait for itself.
+        if high_water_mark == 0 || is_worker_thread() {
+            return;
+        }
+
+        let deadline = Instant::now() + MAX_BACKPRESSURE_WAIT;
+        while self.sender.len() > high_water_mark && Instant::now() < deadline {
+            thread::sleep(BACKPRESSURE_POLL_INTERVAL);
+        }
+    }
+
     fn block_on_queue(&self) {
+        // A task running on the worker thread can't wait for the queue:
+        // all tasks launched before it already ran.
+        // After a shutdown the queue was drained or discarded.
+        if is_worker_thread() || self.closed.load(Ordering::SeqCst) {
+            return;
+        }
+
+        // Nothing is processed before the pre-init buffer is flushed,
+        // which might never happen. The buffer might be full, too.
+        if self.queue_preinit.load(Ordering::SeqCst) {
+            log::error!("Can't wait for the dispatch queue before the pre-init buffer is flushed");
+            return;
+        }
+
+        // Sent directly, so waiting works even if the queue is full.
         let (tx, rx) = crossbeam_channel::bounded(0);
-        self.launch(move || {
+        self.send(Command::Task(Box::new(move || {
             tx.send(())
                 .expect("(worker) Can't send message on single-use channel")
-        })
+        })))
         .expect("Failed to launch the blocking task");
         rx.recv()
             .expect("Failed to receive message on single-use channel");
     }
 
+    fn block_on_queue_timeout(&self, timeout: Duration) -> Result<(), TimeoutError> {
+        // A task running on the worker thread can't wait for the queue:
+        // all tasks launched before it already ran.
+        // After a shutdown the queue was drained or discarded.
+        if is_worker_thread() || self.closed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        // The channel has room for the message, so the worker doesn't block
+        // if we stopped waiting already.
+        // Sent directly, so waiting works even if the queue is full.
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        if let Err(e) = self.send(Command::Task(Box::new(move || {
+            let _ = tx.send(());
+        }))) {
+            log::error!("Failed to launch the blocking task: {}", e);
+            return Err(TimeoutError);
+        }
+        rx.recv_timeout(timeout).map_err(|_| TimeoutError)
+    }
+
     fn flush_init(&mut self) -> Result<(), DispatchError> {
         // We immediately stop queueing in the pre-init buffer.
         let old_val = self.queue_preinit.swap(false, Ordering::SeqCst);
@@ -168,6 +454,33 @@ impl DispatchGuard {
     }
 }
 
+/// Receives the next command, preferring high-priority tasks.
+///
+/// This blocks until a command is available.
+/// Returns an error once the normal queue is disconnected.
+fn next_command(
+    high_priority_receiver: &Receiver<Command>,
+    receiver: &Receiver<Command>,
+) -> Result<Command, RecvError> {
+    loop {
+        if let Ok(command) = high_priority_receiver.try_recv() {
+            return Ok(command);
+        }
+
+        match receiver.try_recv() {
+            Ok(command) => return Ok(command),
+            Err(TryRecvError::Disconnected) => return Err(RecvError),
+            Err(TryRecvError::Empty) => {}
+        }
+
+        // Wait until either queue has something to offer, then check them in order of priority.
+        let mut select = Select::new();
+        select.recv(high_priority_receiver);
+        select.recv(receiver);
+        select.ready();
+    }
+}
+
 /// A dispatcher.
 ///
 /// Run expensive processing tasks sequentially off the main thread.
@@ -193,61 +506,80 @@ impl Dispatcher {
         let (block_sender, block_receiver) = bounded(0);
         let (preinit_sender, preinit_receiver) = bounded(max_queue_size);
         let (sender, mut unbounded_receiver) = unbounded();
+        let (high_priority_sender, high_priority_receiver) = unbounded();
 
         let queue_preinit = Arc::new(AtomicBool::new(true));
+        let discard = Arc::new(AtomicBool::new(false));
+        let worker_discard = Arc::clone(&discard);
+
+        let worker = thread::Builder::new()
+            .name(WORKER_THREAD_NAME.to_string())
+            .spawn(move || {
+                if block_receiver.recv().is_err() {
+                    // The other side was disconnected.
+                    // There's nothing the worker thread can do.
+                    log::error!("The task producer was disconnected. Worker thread will exit.");
+                    return;
+                }
 
-        let worker = thread::spawn(move || {
-            if block_receiver.recv().is_err() {
-                // The other side was disconnected.
-                // There's nothing the worker thread can do.
-                log::error!("The task producer was disconnected. Worker thread will exit.");
-                return;
-            }
-
-            let mut receiver = preinit_receiver;
-            loop {
-                use Command::*;
-
-                match receiver.recv() {
-                    Ok(Shutdown) => {
-                        break;
-                    }
-
-                    Ok(Task(f)) => {
-                        (f)();
-                    }
-
-                    Ok(Swap(swap_done)) => {
-                        // A swap should only occur exactly once.
-                        // This is upheld by `flush_init`, which errors out if the preinit buffer
-                        // was already flushed.
-
-                        // We swap the channels we listen on for new tasks.
-                        // The next iteration will continue with the unbounded queue.
-                        mem::swap(&mut receiver, &mut unbounded_receiver);
-
-                        // The swap command MUST be the last one received on the preinit buffer,
-                        // so by the time we run this we know all preinit tasks were processed.
-                        // We can notify the other side.
-                        swap_done
-                            .send(())
-                            .expect("The caller of `flush_init` has gone missing");
-                    }
-
-                    // Other side was disconnected.
-                    Err(_) => {
-                        log::error!("The task producer was disconnected. Worker thread will exit.");
-                        return;
+                let mut receiver = preinit_receiver;
+                loop {
+                    use Command::*;
+
+                    match next_command(&high_priority_receiver, &receiver) {
+                        Ok(Shutdown) => {
+                            break;
+                        }
+
+                        Ok(Task(_)) if worker_discard.load(Ordering::SeqCst) => {
+                            // The shutdown timed out, the remaining tasks are dropped.
+                            break;
+                        }
+
+                        Ok(Task(f)) => {
+                            run_task(f);
+                        }
+
+                        Ok(Swap(swap_done)) => {
+                            // A swap should only occur exactly once.
+                            // This is upheld by `flush_init`, which errors out if the preinit buffer
+                            // was already flushed.
+
+                            // We swap the channels we listen on for new tasks.
+                            // The next iteration will continue with the unbounded queue.
+                            mem::swap(&mut receiver, &mut unbounded_receiver);
+
+                            // The swap command MUST be the last one received on the preinit buffer,
+                            // so by the time we run this we know all preinit tasks were processed.
+                            // We can notify the other side.
+                            swap_done
+                                .send(())
+                                .expect("The caller of `flush_init` has gone missing");
+                        }
+
+                        // Other side was disconnected.
+                        Err(_) => {
+                            log::error!(
+                                "The task producer was disconnected. Worker thread will exit."
+                            );
+                            return;
+                        }
                     }
                 }
-            }
-        });
+            })
+            .expect("Failed to spawn the dispatcher worker thread");
 
         let guard = DispatchGuard {
             queue_preinit,
             block_sender,
             preinit_sender,
             sender,
+            high_priority_sender,
+            high_water_mark: Arc::new(AtomicUsize::new(0)),
+            closed: Arc::new(AtomicBool::new(false)),
+            discard,
+            max_queue_size: Arc::new(AtomicUsize::new(DEFAULT_MAX_QUEUE_SIZE)),
+            overflow: Arc::new(Overflow::default()),
         };
 
         Dispatcher {
@@ -264,6 +596,52 @@ impl Dispatcher {
         self.guard().block_on_queue()
     }
 
+    fn block_on_queue_timeout(&self, timeout: Duration) -> Result<(), TimeoutError> {
+        self.guard().block_on_queue_timeout(timeout)
+    }
+
+    /// Sets the queue length above which launching a task blocks the caller briefly.
+    ///
+    /// `None` disables back-pressure, which is the default.
+    fn set_high_water_mark(&self, high_water_mark: Option<usize>) {
+        self.guard
+            .high_water_mark
+            .store(high_water_mark.unwrap_or(0), Ordering::SeqCst);
+    }
+
+    /// Sets the maximum number of queued tasks of normal priority, after the pre-init buffer was flushed.
+    ///
+    /// `None` removes the limit.
+    fn set_max_queue_size(&self, max_queue_size: Option<usize>) {
+        self.guard
+            .max_queue_size
+            .store(max_queue_size.unwrap_or(0), Ordering::SeqCst);
+    }
+
+    /// Gets the currently configured maximum queue size, if any.
+    fn max_queue_size(&self) -> Option<usize> {
+        match self.guard.max_queue_size.load(Ordering::SeqCst) {
+            0 => None,
+            max_queue_size => Some(max_queue_size),
+        }
+    }
+
+    /// Gets the currently configured high-water mark, if any.
+    fn high_water_mark(&self) -> Option<usize> {
+        match self.guard.high_water_mark.load(Ordering::SeqCst) {
+            0 => None,
+            high_water_mark => Some(high_water_mark),
+        }
+    }
+
+    /// Whether the worker thread already stopped, or will stop, without another shutdown command.
+    ///
+    /// That's the case if it never started processing,
+    /// or if the dispatcher was shut down with a timeout.
+    fn is_shut_down(&self) -> bool {
+        self.guard.queue_preinit.load(Ordering::SeqCst) || self.guard.closed.load(Ordering::SeqCst)
+    }
+
     /// Waits for the worker thread to finish and finishes the dispatch queue.
     ///
     /// You need to call `try_shutdown` to initiate a shutdown of the queue.
@@ -274,6 +652,40 @@ impl Dispatcher {
         Ok(())
     }
 
+    /// Shuts down the worker thread and waits for it to finish.
+    ///
+    /// If the pre-init buffer was flushed, all tasks launched before this call are processed first.
+    /// Otherwise the worker thread never started processing and queued tasks are discarded.
+    fn shutdown(mut self) -> Result<(), DispatchError> {
+        if !self.is_shut_down() {
+            self.guard.shutdown()?;
+        }
+
+        let worker = self.worker.take();
+        // Dropping the guard disconnects the worker thread if it is still waiting to be unblocked.
+        drop(self);
+
+        if let Some(worker) = worker {
+            worker.join().map_err(|_| DispatchError::WorkerPanic)?;
+        }
+        Ok(())
+    }
+
+    /// Shuts down the worker thread without waiting for it to finish.
+    ///
+    /// If the pre-init buffer was flushed, the worker thread processes
+    /// all tasks launched before this call and then exits.
+    /// Otherwise the worker thread never started processing and queued tasks are discarded.
+    fn shutdown_detached(mut self) -> Result<(), DispatchError> {
+        if !self.is_shut_down() {
+            self.guard.shutdown()?;
+        }
+
+        // Dropping the handle detaches the worker thread.
+        self.worker.take();
+        Ok(())
+    }
+
     /// Flushes the pre-init buffer.
     ///
     /// This function blocks until tasks queued prior to this call are finished.
@@ -290,7 +702,10 @@ mod test {
     use super::*;
     use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
     use std::sync::{Arc, Mutex};
-    use std::{thread, time::Duration};
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
 
     fn enable_test_logging() {
         // When testing we want all logs to go to stdout/stderr by default,
@@ -427,6 +842,135 @@ mod test {
         assert!(result.lock().unwrap().is_empty());
     }
 
+    #[test]
+    fn shutdown_processes_queued_tasks() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(10);
+        dispatcher.flush_init().unwrap();
+
+        let result = Arc::new(Mutex::new(vec![]));
+        for i in 1..=3 {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    thread::sleep(Duration::from_millis(10));
+                    result.lock().unwrap().push(i);
+                })
+                .unwrap();
+        }
+
+        dispatcher.shutdown().unwrap();
+
+        assert_eq!(&*result.lock().unwrap(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn shutdown_discards_tasks_if_never_flushed() {
+        enable_test_logging();
+
+        let dispatcher = Dispatcher::new(10);
+
+        let result = Arc::new(Mutex::new(vec![]));
+        {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    result.lock().unwrap().push(0);
+                })
+                .unwrap();
+        }
+
+        dispatcher.shutdown().unwrap();
+
+        assert!(result.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn shutdown_with_timeout_drains_the_queue_and_rejects_new_tasks() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(10);
+        dispatcher.flush_init().unwrap();
+
+        let result = Arc::new(Mutex::new(vec![]));
+        for i in 1..=3 {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    thread::sleep(Duration::from_millis(10));
+                    result.lock().unwrap().push(i);
+                })
+                .unwrap();
+        }
+
+        assert_eq!(
+            Ok(()),
+            dispatcher
+                .guard()
+                .shutdown_with_timeout(Duration::from_secs(5))
+        );
+        assert_eq!(&*result.lock().unwrap(), &[1, 2, 3]);
+
+        assert_eq!(
+            Err(DispatchError::ShutDown),
+            dispatcher.guard().launch(|| {})
+        );
+        assert_eq!(
+            Err(DispatchError::ShutDown),
+            dispatcher
+                .guard()
+                .shutdown_with_timeout(Duration::from_secs(5))
+        );
+
+        // Waiting on a shut down queue returns right away.
+        dispatcher.block_on_queue();
+        dispatcher.shutdown().unwrap();
+    }
+
+    #[test]
+    fn shutdown_with_timeout_discards_the_remaining_tasks() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(10);
+        dispatcher.flush_init().unwrap();
+
+        // Block the worker thread, acting as a slow task.
+        let (unblock_sender, unblock_receiver) = crossbeam_channel::bounded::<()>(0);
+        dispatcher
+            .guard()
+            .launch(move || {
+                unblock_receiver.recv().unwrap();
+            })
+            .unwrap();
+
+        let result = Arc::new(Mutex::new(vec![]));
+        {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    result.lock().unwrap().push(1);
+                })
+                .unwrap();
+        }
+
+        assert_eq!(
+            Err(DispatchError::ShutdownTimeout),
+            dispatcher
+                .guard()
+                .shutdown_with_timeout(Duration::from_millis(50))
+        );
+
+        // The running task is finished, the queued one is dropped.
+        unblock_sender.send(()).unwrap();
+        dispatcher.shutdown().unwrap();
+        assert!(result.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn preinit_buffer_fills_up() {
         enable_test_logging();
@@ -514,4 +1058,288 @@ mod test {
         let expected = (1..=20).collect::<Vec<_>>();
         assert_eq!(&*result.lock().unwrap(), &expected);
     }
+
+    #[test]
+    fn full_queue_drops_the_newest_tasks() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+        dispatcher.set_max_queue_size(Some(2));
+        dispatcher.flush_init().unwrap();
+
+        // Block the worker thread until we're done queueing.
+        let (unblock_sender, unblock_receiver) = crossbeam_channel::bounded::<()>(0);
+        let (started_sender, started_receiver) = crossbeam_channel::bounded::<()>(0);
+        dispatcher
+            .guard()
+            .launch(move || {
+                started_sender.send(()).unwrap();
+                unblock_receiver.recv().unwrap();
+            })
+            .unwrap();
+        started_receiver.recv().unwrap();
+
+        let result = Arc::new(Mutex::new(vec![]));
+        for i in 1..=4 {
+            let result = Arc::clone(&result);
+            let launched = dispatcher.guard().launch(move || {
+                result.lock().unwrap().push(i);
+            });
+            if i <= 2 {
+                assert_eq!(Ok(()), launched);
+            } else {
+                assert_eq!(Err(DispatchError::QueueFull), launched);
+            }
+        }
+
+        // High-priority tasks are not limited.
+        dispatcher
+            .guard()
+            .launch_with_priority(|| {}, Priority::High)
+            .unwrap();
+        assert_eq!(3, dispatcher.guard().queue_len());
+        assert_eq!(2, dispatcher.guard.overflow.queue.load(Ordering::SeqCst));
+        assert_eq!(0, dispatcher.guard.overflow.preinit.load(Ordering::SeqCst));
+
+        unblock_sender.send(()).unwrap();
+        dispatcher.block_on_queue();
+        assert_eq!(&*result.lock().unwrap(), &[1, 2]);
+        assert_eq!(0, dispatcher.guard().queue_len());
+    }
+
+    #[test]
+    fn full_preinit_buffer_counts_dropped_tasks() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(2);
+        // Only applies after the pre-init buffer was flushed.
+        dispatcher.set_max_queue_size(Some(1));
+
+        for _ in 1..=2 {
+            dispatcher.guard().launch(|| {}).unwrap();
+        }
+        for _ in 1..=3 {
+            assert_eq!(
+                Err(DispatchError::QueueFull),
+                dispatcher.guard().launch(|| {})
+            );
+        }
+        assert_eq!(2, dispatcher.guard().queue_len());
+        assert_eq!(3, dispatcher.guard.overflow.preinit.load(Ordering::SeqCst));
+        assert_eq!(0, dispatcher.guard.overflow.queue.load(Ordering::SeqCst));
+
+        dispatcher.flush_init().unwrap();
+    }
+
+    #[test]
+    fn backed_up_queue_throttles_the_caller() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+        dispatcher.set_high_water_mark(Some(2));
+        dispatcher.flush_init().unwrap();
+
+        // Block the worker thread until we're done queueing.
+        let (unblock_sender, unblock_receiver) = crossbeam_channel::bounded::<()>(0);
+        dispatcher
+            .guard()
+            .launch(move || {
+                unblock_receiver.recv().unwrap();
+            })
+            .unwrap();
+
+        let result = Arc::new(Mutex::new(vec![]));
+        let now = Instant::now();
+        for i in 1..=3 {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    result.lock().unwrap().push(i);
+                })
+                .unwrap();
+        }
+
+        // The queue exceeded the high-water mark, so the caller was blocked.
+        assert!(now.elapsed() >= MAX_BACKPRESSURE_WAIT);
+
+        unblock_sender.send(()).unwrap();
+        dispatcher.block_on_queue();
+        assert_eq!(&*result.lock().unwrap(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn preinit_buffer_is_not_throttled() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+        dispatcher.set_high_water_mark(Some(1));
+
+        // Blocking here would keep the caller waiting for tasks that can't be processed yet.
+        let now = Instant::now();
+        for _ in 1..=5 {
+            dispatcher.guard().launch(|| {}).unwrap();
+        }
+        assert!(now.elapsed() < MAX_BACKPRESSURE_WAIT);
+
+        dispatcher.flush_init().unwrap();
+    }
+
+    #[test]
+    fn high_priority_tasks_run_first() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+        dispatcher.flush_init().unwrap();
+
+        // Block the worker thread until we're done queueing.
+        let (unblock_sender, unblock_receiver) = crossbeam_channel::bounded::<()>(0);
+        dispatcher
+            .guard()
+            .launch(move || {
+                unblock_receiver.recv().unwrap();
+            })
+            .unwrap();
+
+        let result = Arc::new(Mutex::new(vec![]));
+        {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    thread::sleep(Duration::from_millis(50));
+                    result.lock().unwrap().push("slow");
+                })
+                .unwrap();
+        }
+        for name in &["high1", "high2"] {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch_with_priority(
+                    move || {
+                        result.lock().unwrap().push(name);
+                    },
+                    Priority::High,
+                )
+                .unwrap();
+        }
+
+        unblock_sender.send(()).unwrap();
+        dispatcher.block_on_queue();
+
+        assert_eq!(&*result.lock().unwrap(), &["high1", "high2", "slow"]);
+    }
+
+    #[test]
+    fn high_priority_tasks_run_first_on_flush() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+
+        let result = Arc::new(Mutex::new(vec![]));
+        for (i, priority) in [Priority::Normal, Priority::High, Priority::Normal]
+            .iter()
+            .enumerate()
+        {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch_with_priority(
+                    move || {
+                        result.lock().unwrap().push(i);
+                    },
+                    *priority,
+                )
+                .unwrap();
+        }
+
+        dispatcher.flush_init().unwrap();
+
+        assert_eq!(&*result.lock().unwrap(), &[1, 0, 2]);
+    }
+
+    #[test]
+    fn waiting_for_the_queue_before_flush_returns_immediately() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+
+        let result = Arc::new(Mutex::new(vec![]));
+        for i in 1..=5 {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    result.lock().unwrap().push(i);
+                })
+                .unwrap();
+        }
+
+        // The pre-init buffer is full and never processed before the flush.
+        dispatcher.block_on_queue();
+        assert!(result.lock().unwrap().is_empty());
+
+        dispatcher.flush_init().unwrap();
+        dispatcher.block_on_queue();
+        assert_eq!(&*result.lock().unwrap(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn waiting_for_the_queue_times_out() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+        dispatcher.flush_init().unwrap();
+
+        // Block the worker thread, acting as a slow task.
+        let (unblock_sender, unblock_receiver) = crossbeam_channel::bounded::<()>(0);
+        dispatcher
+            .guard()
+            .launch(move || {
+                unblock_receiver.recv().unwrap();
+            })
+            .unwrap();
+
+        assert_eq!(
+            Err(TimeoutError),
+            dispatcher.block_on_queue_timeout(Duration::from_millis(50))
+        );
+
+        unblock_sender.send(()).unwrap();
+        assert_eq!(
+            Ok(()),
+            dispatcher.block_on_queue_timeout(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn panicking_tasks_dont_stop_the_worker() {
+        enable_test_logging();
+
+        let mut dispatcher = Dispatcher::new(5);
+        dispatcher.flush_init().unwrap();
+
+        let panics_before = panic_count();
+        let result = Arc::new(Mutex::new(vec![]));
+        dispatcher
+            .guard()
+            .launch(|| panic!("deliberately failing task"))
+            .unwrap();
+        {
+            let result = Arc::clone(&result);
+            dispatcher
+                .guard()
+                .launch(move || {
+                    result.lock().unwrap().push(1);
+                })
+                .unwrap();
+        }
+
+        dispatcher.block_on_queue();
+
+        assert_eq!(&*result.lock().unwrap(), &[1]);
+        // Other tests might panic concurrently.
+        assert!(panic_count() > panics_before);
+    }
 }
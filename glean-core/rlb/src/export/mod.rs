@@ -0,0 +1,9 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Exporting the stored values of metrics to other monitoring systems.
+//!
+//! Only available with the `prometheus-export` feature.
+
+pub mod prometheus;
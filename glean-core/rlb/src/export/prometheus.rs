@@ -0,0 +1,410 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Exporting metrics in the Prometheus text exposition format.
+//!
+//! This reads what is currently in storage: values recorded in tasks
+//! that were not processed yet are not included.
+//!
+//! Only counter, gauge, timing distribution and memory distribution metrics are exported,
+//! including their labeled variants:
+//!
+//! * Counters and gauges are exported as `counter` and `gauge`.
+//! * Timing distributions are exported as a `summary`, in nanoseconds.
+//!   The quantiles are estimated from the buckets, so they are the minimum of the bucket
+//!   the quantile falls into.
+//! * Memory distributions are exported as a `histogram`, in bytes.
+//!   The upper bound of each bucket is the largest value it holds.
+//!
+//! Metric names are sanitized to match the Prometheus naming rules,
+//! e.g. `category.name` becomes `category_name`.
+//! If the names of two metrics are the same after sanitizing, e.g. `category.name`
+//! and `category_name`, only the first one read from storage is exported.
+//! Every sample carries the name of the ping it is stored for in the `ping` label,
+//! and the label of labeled metrics in the `label` label.
+//! Metrics with a `Ping` lifetime are reset when their ping is submitted,
+//! so their samples are additionally labeled `lifetime="ping"`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use glean_core::metrics::Metric;
+use glean_core::Lifetime;
+
+/// The quantiles exported for summaries.
+const QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// All samples of one metric, across pings and labels.
+#[derive(Debug, Default)]
+struct Family {
+    /// The identifier of the metric, without the label of labeled metrics.
+    metric_id: String,
+    kind: &'static str,
+    samples: Vec<String>,
+}
+
+/// Exports the stored metrics in the Prometheus text exposition format.
+///
+/// Returns an empty string if Glean is not initialized.
+pub fn export_to_prometheus() -> String {
+    let glean = match crate::global_glean() {
+        Some(glean) => glean,
+        None => return String::new(),
+    };
+    let glean = glean.lock().unwrap();
+
+    let mut families: BTreeMap<String, Family> = BTreeMap::new();
+    for &lifetime in &[Lifetime::User, Lifetime::Application, Lifetime::Ping] {
+        glean
+            .storage()
+            .iter_lifetime(lifetime, |storage_name, metric_id, metric| {
+                record_metric(&mut families, lifetime, storage_name, metric_id, metric);
+            });
+    }
+
+    let mut output = String::new();
+    for (name, family) in families {
+        writeln!(output, "# TYPE {} {}", name, family.kind).unwrap();
+        for sample in family.samples {
+            output.push_str(&sample);
+        }
+    }
+    output
+}
+
+/// Formats the samples of a single stored metric and adds them to its family.
+///
+/// Metrics of types that are not exported are skipped.
+fn record_metric(
+    families: &mut BTreeMap<String, Family>,
+    lifetime: Lifetime,
+    storage_name: &str,
+    metric_id: &str,
+    metric: &Metric,
+) {
+    let kind = match metric {
+        Metric::Counter(_) => "counter",
+        Metric::Gauge(_) => "gauge",
+        Metric::TimingDistribution(_) => "summary",
+        Metric::MemoryDistribution(_) => "histogram",
+        _ => return,
+    };
+
+    let (base_id, label) = match metric_id.split_once('/') {
+        Some((base_id, label)) => (base_id, Some(label)),
+        None => (metric_id, None),
+    };
+    let name = sanitize_name(base_id);
+
+    let mut labels = vec![("ping", storage_name)];
+    if let Some(label) = label {
+        labels.push(("label", label));
+    }
+    if lifetime == Lifetime::Ping {
+        labels.push(("lifetime", "ping"));
+    }
+
+    let family = families.entry(name.clone()).or_insert_with(|| Family {
+        metric_id: base_id.to_string(),
+        kind,
+        samples: Vec::new(),
+    });
+    if family.metric_id != base_id {
+        log::warn!(
+            "Not exporting {} to Prometheus, its name is already used by {}",
+            metric_id,
+            family.metric_id
+        );
+        return;
+    }
+    if family.kind != kind {
+        log::warn!(
+            "Not exporting {} to Prometheus, its name is already used by a {}",
+            metric_id,
+            family.kind
+        );
+        return;
+    }
+
+    let mut samples = String::new();
+    match metric {
+        Metric::Counter(value) => write_sample(&mut samples, &name, &labels, *value),
+        Metric::Gauge(value) => write_sample(&mut samples, &name, &labels, *value),
+        Metric::TimingDistribution(hist) => write_summary(
+            &mut samples,
+            &name,
+            &labels,
+            hist.values(),
+            hist.sum(),
+            hist.count(),
+        ),
+        Metric::MemoryDistribution(hist) => write_histogram(
+            &mut samples,
+            &name,
+            &labels,
+            &hist.snapshot(),
+            hist.sum(),
+            hist.count(),
+        ),
+        _ => unreachable!("Only exported metric types are formatted"),
+    }
+    family.samples.push(samples);
+}
+
+/// Writes the quantiles, sum and count of a distribution as a summary.
+///
+/// `values` maps the minimum of each bucket to its count.
+fn write_summary(
+    output: &mut String,
+    name: &str,
+    labels: &[(&str, &str)],
+    values: &HashMap<u64, u64>,
+    sum: u64,
+    count: u64,
+) {
+    let mut buckets: Vec<_> = values.iter().map(|(&k, &v)| (k, v)).collect();
+    buckets.sort_unstable();
+
+    for &quantile in &QUANTILES {
+        let rank = (quantile * count as f64).ceil() as u64;
+        let mut cumulative = 0;
+        let value = buckets
+            .iter()
+            .find(|(_, count)| {
+                cumulative += count;
+                cumulative >= rank
+            })
+            .map_or(0, |&(bucket, _)| bucket);
+
+        let quantile = quantile.to_string();
+        let mut labels = labels.to_vec();
+        labels.push(("quantile", &quantile));
+        write_sample(output, name, &labels, value);
+    }
+
+    write_sample(output, &format!("{}_sum", name), labels, sum);
+    write_sample(output, &format!("{}_count", name), labels, count);
+}
+
+/// Writes the cumulative buckets, sum and count of a distribution as a histogram.
+///
+/// `snapshot` maps the minimum of each bucket to its count. It must include the empty bucket
+/// after the last filled one, so the upper bound of each bucket, the largest value it holds,
+/// is one less than the minimum of the next bucket.
+fn write_histogram(
+    output: &mut String,
+    name: &str,
+    labels: &[(&str, &str)],
+    snapshot: &HashMap<u64, u64>,
+    sum: u64,
+    count: u64,
+) {
+    let mut bucket_minimums: Vec<_> = snapshot.keys().copied().collect();
+    bucket_minimums.sort_unstable();
+
+    let bucket_name = format!("{}_bucket", name);
+    let mut cumulative = 0;
+    for pair in bucket_minimums.windows(2) {
+        cumulative += snapshot[&pair[0]];
+        let upper_bound = (pair[1] - 1).to_string();
+        let mut labels = labels.to_vec();
+        labels.push(("le", &upper_bound));
+        write_sample(output, &bucket_name, &labels, cumulative);
+    }
+
+    let mut labels_inf = labels.to_vec();
+    labels_inf.push(("le", "+Inf"));
+    write_sample(output, &bucket_name, &labels_inf, count);
+
+    write_sample(output, &format!("{}_sum", name), labels, sum);
+    write_sample(output, &format!("{}_count", name), labels, count);
+}
+
+/// Writes a single sample line.
+fn write_sample<V: std::fmt::Display>(
+    output: &mut String,
+    name: &str,
+    labels: &[(&str, &str)],
+    value: V,
+) {
+    let labels = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    writeln!(output, "{}{{{}}} {}", name, labels, value).unwrap();
+}
+
+/// Turns a Glean metric identifier into a valid Prometheus metric name.
+///
+/// Characters that are not allowed are replaced by `_`,
+/// and names that are empty or start with a digit are prefixed with `_`.
+fn sanitize_name(metric_id: &str) -> String {
+    let mut name: String = metric_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Escapes a label value, as required by the text exposition format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names_are_sanitized() {
+        assert_eq!("category_name", sanitize_name("category.name"));
+        assert_eq!(
+            "glean_error_invalid_value",
+            sanitize_name("glean.error.invalid-value")
+        );
+        assert_eq!("_1st_category_name", sanitize_name("1st_category.name"));
+        assert_eq!("_", sanitize_name(""));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(r#"a\"b\\c\nd"#, escape_label_value("a\"b\\c\nd"));
+    }
+
+    #[test]
+    fn samples_are_grouped_by_metric() {
+        let mut families = BTreeMap::new();
+        record_metric(
+            &mut families,
+            Lifetime::Ping,
+            "store1",
+            "category.counter",
+            &Metric::Counter(1),
+        );
+        record_metric(
+            &mut families,
+            Lifetime::Application,
+            "store2",
+            "category.counter/label",
+            &Metric::Counter(2),
+        );
+        // Not exported.
+        record_metric(
+            &mut families,
+            Lifetime::Ping,
+            "store1",
+            "category.string",
+            &Metric::String("value".into()),
+        );
+        // Clashes with the counter.
+        record_metric(
+            &mut families,
+            Lifetime::Ping,
+            "store1",
+            "category.counter",
+            &Metric::Gauge(3),
+        );
+
+        assert_eq!(1, families.len());
+        let family = &families["category_counter"];
+        assert_eq!("counter", family.kind);
+        assert_eq!(
+            vec![
+                "category_counter{ping=\"store1\",lifetime=\"ping\"} 1\n",
+                "category_counter{ping=\"store2\",label=\"label\"} 2\n",
+            ],
+            family.samples
+        );
+    }
+
+    #[test]
+    fn metrics_with_the_same_sanitized_name_are_exported_once() {
+        let mut families = BTreeMap::new();
+        record_metric(
+            &mut families,
+            Lifetime::Ping,
+            "store1",
+            "category.counter",
+            &Metric::Counter(1),
+        );
+        record_metric(
+            &mut families,
+            Lifetime::Ping,
+            "store1",
+            "category_counter/label",
+            &Metric::Counter(2),
+        );
+
+        assert_eq!(1, families.len());
+        let family = &families["category_counter"];
+        assert_eq!("category.counter", family.metric_id);
+        assert_eq!(
+            vec!["category_counter{ping=\"store1\",lifetime=\"ping\"} 1\n"],
+            family.samples
+        );
+    }
+
+    #[test]
+    fn timing_distributions_are_summaries() {
+        let values = [(1, 2), (2, 3), (5, 4), (10, 1)].iter().cloned().collect();
+
+        let mut output = String::new();
+        write_summary(
+            &mut output,
+            "timing",
+            &[("ping", "store1")],
+            &values,
+            40,
+            10,
+        );
+
+        let expected = "\
+timing{ping=\"store1\",quantile=\"0.5\"} 2
+timing{ping=\"store1\",quantile=\"0.9\"} 5
+timing{ping=\"store1\",quantile=\"0.99\"} 10
+timing_sum{ping=\"store1\"} 40
+timing_count{ping=\"store1\"} 10
+";
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn memory_distributions_are_histograms() {
+        let snapshot = [(1, 1), (2, 0), (4, 2), (8, 0)].iter().cloned().collect();
+
+        let mut output = String::new();
+        write_histogram(
+            &mut output,
+            "memory",
+            &[("ping", "store1")],
+            &snapshot,
+            12,
+            3,
+        );
+
+        let expected = "\
+memory_bucket{ping=\"store1\",le=\"1\"} 1
+memory_bucket{ping=\"store1\",le=\"3\"} 1
+memory_bucket{ping=\"store1\",le=\"7\"} 3
+memory_bucket{ping=\"store1\",le=\"+Inf\"} 3
+memory_sum{ping=\"store1\"} 12
+memory_count{ping=\"store1\"} 3
+";
+        assert_eq!(expected, output);
+    }
+}
@@ -18,15 +18,13 @@
 //!
 //! ```rust,no_run
 //! # use glean::{Configuration, ClientInfoMetrics, Error, private::*};
-//! let cfg = Configuration {
-//!     data_path: "/tmp/data".into(),
-//!     application_id: "org.mozilla.glean_core.example".into(),
-//!     upload_enabled: true,
-//!     max_events: None,
-//!     delay_ping_lifetime_io: false,
-//!     channel: None,
-//! };
-//! glean::initialize(cfg, ClientInfoMetrics::unknown());
+//! let cfg = Configuration::builder()
+//!     .data_path("/tmp/data")
+//!     .application_id("org.mozilla.glean_core.example")
+//!     .upload_enabled(true)
+//!     .build()
+//!     .unwrap();
+//! glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
 //!
 //! let prototype_ping = PingType::new("prototype", true, true, vec!());
 //!
@@ -35,22 +33,48 @@
 //! prototype_ping.submit(None);
 //! ```
 
-use once_cell::sync::OnceCell;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
+use once_cell::sync::{Lazy, OnceCell};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 
-pub use configuration::Configuration;
-pub use core_metrics::ClientInfoMetrics;
-pub use glean_core::{global_glean, setup_glean, CommonMetricData, Error, Glean, Lifetime, Result};
+use thiserror::Error;
+
+pub use configuration::{Configuration, ConfigurationBuilder, ConfigurationError, InitCallback};
+pub use core_metrics::{ClientInfoMetrics, ClientInfoMetricsBuilder};
+#[cfg(feature = "prometheus-export")]
+pub use export::prometheus::export_to_prometheus;
+pub use glean_core::metrics::{
+    Datetime, DistributionData, HistogramType, MemoryUnit, RecordedEvent, TimeUnit, TimerId,
+};
+pub use glean_core::{
+    global_glean, setup_glean, Clock, CommonMetricData, Error, ErrorKind, ErrorType, Glean,
+    Lifetime, RealClock, Result,
+};
 
 mod configuration;
 mod core_metrics;
 pub mod dispatcher;
+#[cfg(feature = "prometheus-export")]
+pub mod export;
 mod glean_metrics;
+pub mod net;
 pub mod private;
+#[cfg(feature = "expose-runtime-values")]
+pub mod query;
+mod scheduler;
 mod system;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test;
 
 const LANGUAGE_BINDING_NAME: &str = "Rust";
+/// The name of the ping glean-core submits when upload is disabled.
+const DELETION_REQUEST_PING_NAME: &str = "deletion-request";
 
 /// State to keep track for the Rust Language bindings.
 ///
@@ -63,13 +87,114 @@ struct RustBindingsState {
 
     /// Client info metrics set by the application.
     client_info: ClientInfoMetrics,
+
+    /// The upload manager, if an uploader was configured.
+    upload_manager: Option<net::UploadManager>,
+
+    /// How long `shutdown` waits for pending tasks, if limited.
+    dispatcher_flush_timeout: Option<Duration>,
+
+    /// The scheduler submitting the `events` ping periodically, if configured.
+    events_ping_scheduler: Option<scheduler::EventsPingScheduler>,
+
+    /// The scheduler submitting the `metrics` ping daily, while upload is enabled.
+    metrics_ping_scheduler: Option<scheduler::MetricsPingScheduler>,
+
+    /// Persists data with ping lifetime periodically, if it is held in memory.
+    ping_lifetime_persister: Option<scheduler::PingLifetimePersister>,
+
+    /// Whether the `metrics` ping is submitted daily,
+    /// i.e. both `enable_metrics_ping` and `use_core_mps` are set.
+    enable_metrics_ping: bool,
+
+    /// The value of the `X-Debug-ID` header added to all upload requests, if set.
+    debug_view_tag: Option<String>,
+
+    /// Whether the payload of pings is logged before they are uploaded.
+    log_pings: bool,
+
+    /// The tags of the `X-Source-Tags` header added to all upload requests, if set.
+    source_tags: Option<Vec<String>>,
+
+    /// Whether pending pings are uploaded before upload is disabled.
+    upload_pending_pings_before_disabling: bool,
+
+    /// The path Glean stores its data in, to get the storage size without the Glean object.
+    data_path: PathBuf,
+
+    /// The disk space Glean's data is expected to stay within, if configured.
+    max_storage_bytes: Option<u64>,
 }
 
-/// Set when `glean::initialize()` returns.
+/// Set when `glean::initialize()` returns, reset by `glean::shutdown()`.
 /// This allows to detect calls that happen before `glean::initialize()` was called.
 /// Note: The initialization might still be in progress, as it runs in a separate thread.
 static INITIALIZE_CALLED: AtomicBool = AtomicBool::new(false);
 
+/// Set at the end of the initialization thread, once the pre-init queue was flushed,
+/// reset by `glean::shutdown()`.
+static INIT_COMPLETE: AtomicBool = AtomicBool::new(false);
+
+/// The outcome of the initialization thread.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum InitOutcome {
+    /// Initialization is running or was not started yet.
+    Pending,
+    /// Glean is set up and the pre-init queue was flushed.
+    Succeeded,
+    /// Glean could not be set up.
+    Failed,
+}
+
+/// The outcome of the last initialization, with a condition variable notified when it changes.
+static INIT_OUTCOME: Lazy<(Mutex<InitOutcome>, Condvar)> =
+    Lazy::new(|| (Mutex::new(InitOutcome::Pending), Condvar::new()));
+
+/// The arguments of the last `initialize` call, to initialize again in `test::simulate_restart`.
+/// Also read by `set_upload_enabled`, which may be called before the initialization thread set up the state.
+static LAST_INIT_ARGS: Lazy<Mutex<Option<(Configuration, ClientInfoMetrics)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// The number of changes of the upload enabled state requested so far.
+static UPLOAD_ENABLED_REQUESTED: AtomicU64 = AtomicU64::new(0);
+
+/// The number of the last change of the upload enabled state applied.
+///
+/// Disabling upload may jump ahead of an earlier change that enabled it,
+/// which is then skipped, as a later change already took effect.
+static UPLOAD_ENABLED_APPLIED: AtomicU64 = AtomicU64::new(0);
+
+/// The source of the current time, with the monotonic time timestamps are relative to.
+///
+/// Timestamps are taken when the API is called, not when the dispatched task runs,
+/// so this is kept outside of the Glean object. See `test::set_clock`.
+static CLOCK: Lazy<RwLock<(Arc<dyn Clock>, Instant)>> =
+    Lazy::new(|| RwLock::new((Arc::new(RealClock), Instant::now())));
+
+/// Ping types registered while Glean is not initialized, in registration order.
+///
+/// The initialization takes them out and registers them in the same order,
+/// right after the builtin pings. Ping types registered afterwards are registered
+/// on the dispatcher, until `shutdown` starts collecting them here again.
+static PRE_INIT_PING_REGISTRATION: Lazy<Mutex<Option<Vec<private::PingType>>>> =
+    Lazy::new(|| Mutex::new(Some(Vec::new())));
+
+/// The error returned when Glean could not be initialized.
+#[derive(Error, Debug, PartialEq)]
+pub enum InitializeError {
+    /// Glean could not be set up, e.g. because the database could not be opened
+    #[error("Glean failed to initialize")]
+    Failed,
+
+    /// Initialization did not complete in time
+    #[error("Glean did not finish initializing within {0:?}")]
+    Timeout(Duration),
+
+    /// The data path can't be created or is not writable
+    #[error("The data path {0:?} is not usable: {1}")]
+    InvalidDataPath(PathBuf, String),
+}
+
 /// A global singleton storing additional state for Glean.
 ///
 /// Requires a Mutex, because in tests we can actual reset this.
@@ -94,19 +219,21 @@ fn setup_state(state: RustBindingsState) {
 
 fn with_glean<F, R>(f: F) -> R
 where
-    F: Fn(&Glean) -> R,
+    F: FnOnce(&Glean) -> R,
 {
     let glean = global_glean().expect("Global Glean object not initialized");
-    let lock = glean.lock().unwrap();
+    // A task that panicked while holding the lock doesn't stop later tasks from recording.
+    let lock = glean.lock().unwrap_or_else(PoisonError::into_inner);
     f(&lock)
 }
 
 fn with_glean_mut<F, R>(f: F) -> R
 where
-    F: Fn(&mut Glean) -> R,
+    F: FnOnce(&mut Glean) -> R,
 {
     let glean = global_glean().expect("Global Glean object not initialized");
-    let mut lock = glean.lock().unwrap();
+    // A task that panicked while holding the lock doesn't stop later tasks from recording.
+    let mut lock = glean.lock().unwrap_or_else(PoisonError::into_inner);
     f(&mut lock)
 }
 
@@ -117,13 +244,39 @@ where
 /// # Arguments
 ///
 /// * `cfg` - the `Configuration` options to initialize with.
+///   Values set in environment variables take precedence,
+///   see [`Configuration::apply_env_overrides`](struct.Configuration.html#method.apply_env_overrides).
 /// * `client_info` - the `ClientInfoMetrics` values used to set Glean
 ///   core metrics.
-pub fn initialize(cfg: Configuration, client_info: ClientInfoMetrics) {
-    if was_initialize_called() {
+///
+/// # Errors
+///
+/// * [`InitializeError::InvalidDataPath`](enum.InitializeError.html) if the data path
+///   can't be created or is not writable. Glean is not initialized then.
+///
+/// Other failures happen while setting up Glean in the background,
+/// see [`initialize_sync`](fn.initialize_sync.html) to wait for them.
+pub fn initialize(
+    mut cfg: Configuration,
+    client_info: ClientInfoMetrics,
+) -> std::result::Result<(), InitializeError> {
+    if is_initialized() {
         log::error!("Glean should not be initialized multiple times");
-        return;
+        return Ok(());
+    }
+
+    cfg.apply_env_overrides();
+    if let Err(e) = validate_data_path(&cfg.data_path) {
+        log::error!("Invalid data path {:?}: {}", cfg.data_path, e);
+        let reason = e.to_string();
+        if let Some(on_init) = &cfg.on_init {
+            on_init.call(Err(e.into()));
+        }
+        return Err(InitializeError::InvalidDataPath(cfg.data_path, reason));
     }
+    *LAST_INIT_ARGS.lock().unwrap() = Some((cfg.clone(), client_info.clone()));
+
+    set_init_outcome(InitOutcome::Pending);
 
     std::thread::spawn(move || {
         let core_cfg = glean_core::Configuration {
@@ -133,42 +286,102 @@ pub fn initialize(cfg: Configuration, client_info: ClientInfoMetrics) {
             language_binding_name: LANGUAGE_BINDING_NAME.into(),
             max_events: cfg.max_events,
             delay_ping_lifetime_io: cfg.delay_ping_lifetime_io,
+            compress_pings: cfg.compress_pings,
+            max_pings_per_minute: cfg.max_pings_per_minute,
+            extra_server_endpoints: net::validate_extra_server_endpoints(
+                &cfg.extra_server_endpoints,
+            ),
+            disable_internal_error_metrics: cfg.disable_internal_error_metrics,
+            rate_limit: cfg.rate_limit,
+            event_dedup_window: cfg.event_dedup_window,
+            enable_trace_context: cfg.enable_trace_context,
         };
 
-        let glean = match Glean::new(core_cfg) {
+        let mut glean = match Glean::new(core_cfg) {
             Ok(glean) => glean,
             // glean-core already takes care of logging errors: other bindings
             // simply do early returns, as we're doing.
-            Err(_) => return,
+            Err(e) => {
+                set_init_outcome(InitOutcome::Failed);
+                if let Some(on_init) = &cfg.on_init {
+                    on_init.call(Err(e));
+                }
+                return;
+            }
         };
 
+        glean.set_clock(clock());
+
+        if let Some(max_pending_pings) = cfg.max_pending_pings {
+            glean.set_max_pending_pings(max_pending_pings);
+        }
+
         // glean-core already takes care of logging errors: other bindings
         // simply do early returns, as we're doing.
-        if glean_core::setup_glean(glean).is_err() {
+        if let Err(e) = glean_core::setup_glean(glean) {
+            set_init_outcome(InitOutcome::Failed);
+            if let Some(on_init) = &cfg.on_init {
+                on_init.call(Err(e));
+            }
             return;
         }
 
         log::info!("Glean initialized");
 
         // Now make this the global object available to others.
+        let server_endpoint = net::validate_server_endpoint(cfg.server_endpoint.as_deref());
+        let ping_name_suffix = cfg.ping_name_suffix;
+        let upload_manager = cfg
+            .uploader
+            .map(|uploader| net::UploadManager::new(server_endpoint, ping_name_suffix, uploader));
+        // Debug options are kept for the lifetime of the process,
+        // even if Glean is initialized again after a shutdown.
+        let (debug_view_tag, log_pings, source_tags) = STATE
+            .get()
+            .map(|state| {
+                let state = state.lock().unwrap();
+                (
+                    state.debug_view_tag.clone(),
+                    state.log_pings,
+                    state.source_tags.clone(),
+                )
+            })
+            .unwrap_or((None, false, None));
         setup_state(RustBindingsState {
             channel: cfg.channel,
             client_info,
+            upload_manager,
+            dispatcher_flush_timeout: cfg.dispatcher_flush_timeout,
+            events_ping_scheduler: None,
+            metrics_ping_scheduler: None,
+            ping_lifetime_persister: None,
+            enable_metrics_ping: cfg.enable_metrics_ping && cfg.use_core_mps,
+            debug_view_tag,
+            log_pings,
+            source_tags,
+            upload_pending_pings_before_disabling: cfg.upload_pending_pings_before_disabling,
+            data_path: cfg.data_path.clone(),
+            max_storage_bytes: cfg.max_storage_bytes,
         });
 
         let upload_enabled = cfg.upload_enabled;
 
         with_glean_mut(|glean| {
-            let state = global_state().lock().unwrap();
+            let mut state = global_state().lock().unwrap();
 
             // Get the current value of the dirty flag so we know whether to
             // send a dirty startup baseline ping below.  Immediately set it to
             // `false` so that dirty startup pings won't be sent if Glean
             // initialization does not complete successfully.
-            // TODO Bug 1672956 will decide where to set this flag again.
+            // It is set again once initialization is done.
             let dirty_flag = glean.is_dirty_flag_set();
             glean.set_dirty_flag(false);
 
+            // Otherwise the option is left to the `GLEAN_LOG_PINGS` environment variable.
+            if state.log_pings {
+                glean.set_log_pings(true);
+            }
+
             // Register builtin pings.
             // Unfortunately we need to manually list them here to guarantee
             // they are registered synchronously before we need them.
@@ -178,15 +391,19 @@ pub fn initialize(cfg: Configuration, client_info: ClientInfoMetrics) {
             glean.register_ping_type(&glean_metrics::pings::metrics.ping_type);
             glean.register_ping_type(&glean_metrics::pings::events.ping_type);
 
-            // TODO: perform registration of pings that were attempted to be
-            // registered before init. See bug 1673850.
+            // Perform registration of pings that were attempted to be
+            // registered before init, in the order they were registered.
+            let pre_init_pings = PRE_INIT_PING_REGISTRATION.lock().unwrap().take();
+            for ping in pre_init_pings.unwrap_or_default() {
+                glean.register_ping_type(&ping.ping_type);
+            }
 
             // If this is the first time ever the Glean SDK runs, make sure to set
             // some initial core metrics in case we need to generate early pings.
             // The next times we start, we would have them around already.
             let is_first_run = glean.is_first_run();
             if is_first_run {
-                initialize_core_metrics(&glean, &state.client_info, state.channel.clone());
+                initialize_core_metrics(glean, &state.client_info, state.channel.clone());
             }
 
             // Deal with any pending events so we can start recording new ones
@@ -196,19 +413,26 @@ pub fn initialize(cfg: Configuration, client_info: ClientInfoMetrics) {
             // 1. Pings were submitted through Glean and it is ready to upload those pings;
             // 2. Upload is disabled, to upload a possible deletion-request ping.
             if pings_submitted || !upload_enabled {
-                // TODO: bug 1672958.
+                trigger_upload(&state);
             }
 
             // Set up information and scheduling for Glean owned pings. Ideally, the "metrics"
             // ping startup check should be performed before any other ping, since it relies
             // on being dispatched to the API context before any other metric.
-            // TODO: start the metrics ping scheduler, will happen in bug 1672951.
+            // Upload being disabled before initialization stops the scheduler again.
+            if glean.is_upload_enabled() {
+                start_metrics_ping_scheduler(glean, &mut state);
+            }
 
             // Check if the "dirty flag" is set. That means the product was probably
             // force-closed. If that's the case, submit a 'baseline' ping with the
             // reason "dirty_startup". We only do that from the second run.
-            if !is_first_run && dirty_flag {
-                // TODO: bug 1672958 - submit_ping_by_name_sync("baseline", "dirty_startup");
+            if !is_first_run
+                && dirty_flag
+                && submit_ping_by_name_sync(glean, "baseline", Some("dirty_startup"))
+                    .unwrap_or(false)
+            {
+                trigger_upload(&state);
             }
 
             // From the second time we run, after all startup pings are generated,
@@ -216,30 +440,459 @@ pub fn initialize(cfg: Configuration, client_info: ClientInfoMetrics) {
             // Any new value will be sent in newly generated pings after startup.
             if !is_first_run {
                 glean.clear_application_lifetime_metrics();
-                initialize_core_metrics(&glean, &state.client_info, state.channel.clone());
+                initialize_core_metrics(glean, &state.client_info, state.channel.clone());
             }
+
+            // Set the dirty flag for the duration of the run.
+            // A clean shutdown resets it, so if it is still set on the next startup,
+            // the application was not shut down cleanly.
+            glean.set_dirty_flag(true);
         });
 
+        dispatcher::set_max_queue_size(Some(
+            cfg.max_dispatch_queue
+                .unwrap_or(dispatcher::DEFAULT_MAX_QUEUE_SIZE),
+        ));
+
         // Signal Dispatcher that init is complete
         if let Err(err) = dispatcher::flush_init() {
             log::error!("Unable to flush the preinit queue: {}", err);
         }
+
+        // The interval isn't validated if the configuration wasn't built with the builder.
+        match cfg.events_ping_interval {
+            Some(interval) if interval < configuration::MIN_EVENTS_PING_INTERVAL => {
+                log::error!(
+                    "Not submitting the events ping periodically, the interval {:?} is too short",
+                    interval
+                );
+            }
+            Some(interval) => {
+                global_state().lock().unwrap().events_ping_scheduler =
+                    scheduler::EventsPingScheduler::start(interval);
+            }
+            None => {}
+        }
+
+        if cfg.delay_ping_lifetime_io {
+            global_state().lock().unwrap().ping_lifetime_persister =
+                scheduler::PingLifetimePersister::start(scheduler::PING_LIFETIME_PERSIST_INTERVAL);
+        }
+
+        INIT_COMPLETE.store(true, Ordering::SeqCst);
+        set_init_outcome(InitOutcome::Succeeded);
+        if let Some(on_init) = &cfg.on_init {
+            on_init.call(Ok(()));
+        }
     });
 
     // Mark the initialization as called: this needs to happen outside of the
     // dispatched block!
     INITIALIZE_CALLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Checks that the data path exists or can be created, and that files can be written to it.
+fn validate_data_path(data_path: &Path) -> io::Result<()> {
+    fs::create_dir_all(data_path)?;
+    let probe = data_path.join(".glean-write-check");
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+/// Creates and initializes a new Glean object and waits for the initialization to complete.
+///
+/// This is like [`initialize`](fn.initialize.html),
+/// but blocks until Glean is set up and all tasks queued before initialization were processed.
+///
+/// # Arguments
+///
+/// * `cfg` - the `Configuration` options to initialize with.
+/// * `client_info` - the `ClientInfoMetrics` values used to set Glean
+///   core metrics.
+/// * `timeout` - how long to wait for the initialization to complete.
+///
+/// # Errors
+///
+/// * [`InitializeError::InvalidDataPath`](enum.InitializeError.html) if the data path
+///   can't be created or is not writable.
+/// * [`InitializeError::Failed`](enum.InitializeError.html) if Glean could not be set up.
+/// * [`InitializeError::Timeout`](enum.InitializeError.html) if initialization did not complete in time.
+///   It might still complete later.
+pub fn initialize_sync(
+    cfg: Configuration,
+    client_info: ClientInfoMetrics,
+    timeout: Duration,
+) -> std::result::Result<(), InitializeError> {
+    initialize(cfg, client_info)?;
+    wait_for_init(timeout)
+}
+
+/// Blocks until the initialization thread finished, at most for the given duration.
+fn wait_for_init(timeout: Duration) -> std::result::Result<(), InitializeError> {
+    let (lock, cvar) = &*INIT_OUTCOME;
+    let (outcome, _) = cvar
+        .wait_timeout_while(lock.lock().unwrap(), timeout, |outcome| {
+            *outcome == InitOutcome::Pending
+        })
+        .unwrap();
+
+    match *outcome {
+        InitOutcome::Succeeded => Ok(()),
+        InitOutcome::Failed => Err(InitializeError::Failed),
+        InitOutcome::Pending => Err(InitializeError::Timeout(timeout)),
+    }
+}
+
+/// Blocks until the initialization thread finished, if `initialize` was called.
+///
+/// Returns right away if Glean is not being initialized.
+pub(crate) fn wait_for_pending_init() {
+    if !INITIALIZE_CALLED.load(Ordering::SeqCst) || INIT_COMPLETE.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let (lock, cvar) = &*INIT_OUTCOME;
+    let _outcome = cvar
+        .wait_while(lock.lock().unwrap(), |outcome| {
+            *outcome == InitOutcome::Pending
+        })
+        .unwrap();
+}
+
+/// Records the outcome of the initialization and wakes up anyone waiting for it.
+fn set_init_outcome(outcome: InitOutcome) {
+    let (lock, cvar) = &*INIT_OUTCOME;
+    *lock.lock().unwrap() = outcome;
+    cvar.notify_all();
+}
+
+/// Signals that the application became active, e.g. it was brought to the foreground.
+///
+/// This sets the dirty flag, so a `baseline` ping with the reason `dirty_startup`
+/// is submitted on the next start if the application is killed before it becomes inactive,
+/// and submits the `baseline` ping with the reason `active`.
+///
+/// Both happen on the dispatcher, after previously launched tasks.
+pub fn handle_client_active() {
+    dispatcher::launch(|| with_glean(|glean| glean.set_dirty_flag(true)));
+    submit_ping_by_name("baseline", Some("active"));
+}
+
+/// Signals that the application became inactive, e.g. it was moved to the background.
+///
+/// This submits the `baseline` ping with the reason `inactive`,
+/// ahead of previously launched tasks that are still queued,
+/// then persists data with ping lifetime that is still held in memory
+/// (see `Configuration.delay_ping_lifetime_io`) and clears the dirty flag.
+pub fn handle_client_inactive() {
+    submit_ping_by_name_with_priority("baseline", Some("inactive"), dispatcher::Priority::High);
+    dispatcher::launch(|| {
+        with_glean(|glean| {
+            if let Err(e) = glean.persist_ping_lifetime_data() {
+                log::error!("Failed to persist ping lifetime data: {}", e);
+            }
+            // The application might be killed while inactive, which is not a dirty shutdown.
+            glean.set_dirty_flag(false);
+        })
+    });
 }
 
-/// Checks if `glean::initialize` was ever called.
+/// Shuts down Glean in an orderly fashion.
+///
+/// This submits the `baseline` ping with the reason `app-shutdown`,
+/// ahead of previously launched tasks that are still queued,
+/// then waits for all those tasks to finish,
+/// persists data with ping lifetime that is still held in memory
+/// (see `Configuration.delay_ping_lifetime_io`)
+/// and finally stops the dispatcher.
+///
+/// Tasks launched after this call are queued until Glean is initialized again.
+/// `initialize` can be called again afterwards.
+///
+/// This blocks until the shutdown is complete.
+/// If `Configuration.dispatcher_flush_timeout` is set and pending tasks don't finish in time,
+/// the `baseline` ping is not submitted, data is not persisted
+/// and the pending tasks finish in the background.
+pub fn shutdown() {
+    if !is_initialized() {
+        log::error!("Shutdown called before Glean is initialized");
+        return;
+    }
+
+    // The initialization thread always finishes eventually.
+    // If it failed, the dispatcher never runs, so there's nothing to submit or persist.
+    let (lock, cvar) = &*INIT_OUTCOME;
+    let outcome = *cvar
+        .wait_while(lock.lock().unwrap(), |outcome| {
+            *outcome == InitOutcome::Pending
+        })
+        .unwrap();
+
+    let mut flushed = true;
+    if outcome == InitOutcome::Succeeded {
+        // Stop submitting the events and metrics pings.
+        {
+            let mut state = global_state().lock().unwrap();
+            state.events_ping_scheduler = None;
+            state.metrics_ping_scheduler = None;
+            state.ping_lifetime_persister = None;
+        }
+
+        let flush_timeout = global_state().lock().unwrap().dispatcher_flush_timeout;
+        if let Some(flush_timeout) = flush_timeout {
+            if let Err(e) = dispatcher::flush_with_timeout(flush_timeout) {
+                log::error!("{}. Not waiting for pending tasks on shutdown.", e);
+                flushed = false;
+            }
+        }
+    }
+
+    if outcome == InitOutcome::Succeeded && flushed {
+        // The ping jumps ahead of the queued tasks, so it is sent even if they take long.
+        // They are still processed before the worker thread stops.
+        submit_ping_by_name_with_priority(
+            "baseline",
+            Some("app-shutdown"),
+            dispatcher::Priority::High,
+        )
+        .wait();
+
+        dispatcher::launch(|| {
+            with_glean(|glean| {
+                if let Err(e) = glean.persist_ping_lifetime_data() {
+                    log::error!("Failed to persist ping lifetime data: {}", e);
+                }
+                // This is a clean shutdown: don't send a dirty startup ping on the next start.
+                glean.set_dirty_flag(false);
+            })
+        });
+    }
+
+    if flushed {
+        // This processes the task above before stopping the worker thread.
+        dispatcher::reset_dispatcher();
+    } else {
+        dispatcher::reset_dispatcher_detached();
+    }
+    private::ping::clear_test_callbacks();
+
+    set_init_outcome(InitOutcome::Pending);
+    INIT_COMPLETE.store(false, Ordering::SeqCst);
+    INITIALIZE_CALLED.store(false, Ordering::SeqCst);
+    // Ping types registered from now on are registered by the next initialization.
+    PRE_INIT_PING_REGISTRATION
+        .lock()
+        .unwrap()
+        .get_or_insert_with(Vec::new);
+    log::info!("Glean shut down");
+}
+
+/// Tears Glean down as if the application was killed, keeping all data on disk.
+///
+/// Previously launched tasks and running uploads are finished first.
+/// Unlike `shutdown`, no `baseline` ping is submitted, data with ping lifetime held in memory
+/// is lost and the dirty flag stays set.
+/// The database is closed, so that Glean can be initialized on the same data path again.
+///
+/// Returns the arguments of the last `initialize` call, if Glean was initialized.
+#[cfg(any(test, feature = "test-support"))]
+pub(crate) fn teardown_preserving_data() -> Option<(Configuration, ClientInfoMetrics)> {
+    if !is_initialized() {
+        log::error!("Teardown called before Glean is initialized");
+        return None;
+    }
+
+    let (lock, cvar) = &*INIT_OUTCOME;
+    let outcome = *cvar
+        .wait_while(lock.lock().unwrap(), |outcome| {
+            *outcome == InitOutcome::Pending
+        })
+        .unwrap();
+
+    if outcome == InitOutcome::Succeeded {
+        let mut state = global_state().lock().unwrap();
+        state.events_ping_scheduler = None;
+        state.metrics_ping_scheduler = None;
+        state.ping_lifetime_persister = None;
+    }
+
+    // This processes all queued tasks before stopping the worker thread.
+    dispatcher::reset_dispatcher();
+    private::ping::clear_test_callbacks();
+
+    if outcome == InitOutcome::Succeeded {
+        // Uploads record metrics, so they need to be done before the database is closed.
+        // The upload thread locks the global state, so it can't be held while waiting.
+        let upload_manager = global_state().lock().unwrap().upload_manager.clone();
+        if let Some(upload_manager) = upload_manager {
+            upload_manager.wait_until_stopped();
+        }
+        with_glean_mut(|glean| glean.destroy_db());
+    }
+
+    set_init_outcome(InitOutcome::Pending);
+    INIT_COMPLETE.store(false, Ordering::SeqCst);
+    INITIALIZE_CALLED.store(false, Ordering::SeqCst);
+    // Ping types registered from now on are registered by the next initialization.
+    PRE_INIT_PING_REGISTRATION
+        .lock()
+        .unwrap()
+        .get_or_insert_with(Vec::new);
+    log::info!("Glean torn down");
+
+    LAST_INIT_ARGS.lock().unwrap().clone()
+}
+
+/// **Test-only API.**
+///
+/// Resets Glean and initializes it again with the given configuration,
+/// so tests of instrumented code don't depend on each other.
+/// Call this at the start of every test using Glean.
+/// It is not suitable for production code, and only available with the `test-support` feature.
+///
+/// A running Glean is torn down first: previously launched tasks are finished,
+/// but no `baseline` ping is submitted, neither on the teardown nor on the initialization.
+/// The debug options set through `set_debug_view_tag`, `set_log_pings` and `set_source_tags`
+/// are reset as well.
+/// This blocks until the initialization completed.
+///
+/// # Arguments
+///
+/// * `cfg` - the configuration to initialize Glean with.
+/// * `client_info` - the client info to initialize Glean with.
+/// * `clear_stores` - whether to delete all data in `cfg.data_path` before initializing,
+///   including pending pings.
+///
+/// # Errors
+///
+/// * [`InitializeError::InvalidDataPath`](enum.InitializeError.html) if the data path
+///   can't be created or is not writable.
+/// * [`InitializeError::Failed`](enum.InitializeError.html) if Glean could not be set up.
+/// * [`InitializeError::Timeout`](enum.InitializeError.html) if initialization did not complete in time.
+#[cfg(any(test, feature = "test-support"))]
+pub fn test_reset_glean(
+    cfg: Configuration,
+    client_info: ClientInfoMetrics,
+    clear_stores: bool,
+) -> std::result::Result<(), InitializeError> {
+    if is_initialized() {
+        // Unlike a restart, a reset should not send a `dirty_startup` ping.
+        if wait_for_init(test::RESTART_TIMEOUT).is_ok() {
+            dispatcher::launch(|| with_glean(|glean| glean.set_dirty_flag(false)));
+        }
+        teardown_preserving_data();
+    }
+
+    if let Some(state) = STATE.get() {
+        let mut state = state.lock().unwrap();
+        state.debug_view_tag = None;
+        state.log_pings = false;
+        state.source_tags = None;
+    }
+
+    if clear_stores {
+        if let Err(e) = fs::remove_dir_all(&cfg.data_path) {
+            if e.kind() != io::ErrorKind::NotFound {
+                log::error!("Failed to clear the data path: {}", e);
+                return Err(InitializeError::Failed);
+            }
+        }
+    }
+
+    initialize_sync(cfg, client_info, test::RESTART_TIMEOUT)
+}
+
+/// Gets the source of the current time.
+fn clock() -> Arc<dyn Clock> {
+    Arc::clone(&CLOCK.read().unwrap().0)
+}
+
+/// Gets a monotonic timestamp in nanoseconds.
+///
+/// Timestamps are relative to when the clock was set, so only the difference
+/// between two timestamps is meaningful.
+fn precise_time_ns() -> u64 {
+    let (clock, start) = &*CLOCK.read().unwrap();
+    clock
+        .monotonic_now()
+        .saturating_duration_since(*start)
+        .as_nanos() as u64
+}
+
+/// Checks whether the `metrics` ping is overdue and starts submitting it daily, if enabled.
+fn start_metrics_ping_scheduler(glean: &Glean, state: &mut RustBindingsState) {
+    if !state.enable_metrics_ping {
+        return;
+    }
+
+    let (schedule, submitted) = scheduler::metrics_ping::check_on_startup(glean);
+    if submitted {
+        trigger_upload(state);
+    }
+    state.metrics_ping_scheduler = scheduler::MetricsPingScheduler::start(schedule);
+}
+
+/// Signals the upload manager, if any, to upload pending pings.
+fn trigger_upload(state: &RustBindingsState) {
+    if let Some(upload_manager) = &state.upload_manager {
+        upload_manager.trigger_upload();
+    }
+}
+
+/// Gets the number of dispatched tasks that panicked.
+///
+/// Panics in tasks, e.g. while recording metrics, are caught and logged,
+/// and later tasks are processed as usual.
+/// This is meant for diagnostics.
+pub fn get_dispatcher_panic_count() -> u64 {
+    dispatcher::panic_count()
+}
+
+/// Checks if `glean::initialize` was called since the last `glean::shutdown`.
+///
+/// Initialization might still be in progress,
+/// see [`is_init_complete`](fn.is_init_complete.html) to check whether Glean is ready.
 ///
 /// # Returns
 ///
 /// `true` if it was, `false` otherwise.
-fn was_initialize_called() -> bool {
+pub fn is_initialized() -> bool {
     INITIALIZE_CALLED.load(Ordering::SeqCst)
 }
 
+/// Checks if the initialization completed since the last `glean::shutdown`.
+///
+/// Once it did, Glean is set up and all tasks queued before initialization were processed.
+/// This stays `false` if initialization failed.
+///
+/// # Returns
+///
+/// `true` if it did, `false` otherwise.
+pub fn is_init_complete() -> bool {
+    INIT_COMPLETE.load(Ordering::SeqCst)
+}
+
+/// Records the number of tasks the dispatcher dropped because its queue was full.
+pub(crate) fn record_dispatcher_overflow(preinit: usize, queue: usize) {
+    let glean = match global_glean() {
+        Some(glean) => glean,
+        None => return,
+    };
+    let glean = glean.lock().unwrap_or_else(PoisonError::into_inner);
+    let metrics = core_metrics::DispatcherMetrics::new();
+    if preinit > 0 {
+        metrics
+            .preinit_tasks_overflow
+            .add(&glean, preinit.try_into().unwrap_or(i32::MAX));
+    }
+    if queue > 0 {
+        metrics
+            .dispatch_queue_overflow
+            .add(&glean, queue.try_into().unwrap_or(i32::MAX));
+    }
+}
+
 fn initialize_core_metrics(
     glean: &Glean,
     client_info: &ClientInfoMetrics,
@@ -256,21 +909,29 @@ fn initialize_core_metrics(
     if let Some(app_channel) = channel {
         core_metrics.app_channel.set(glean, app_channel);
     }
-    core_metrics.os_version.set(glean, "unknown".to_string());
+    core_metrics.os_version.set(glean, system::os_version());
     core_metrics
         .architecture
         .set(glean, system::ARCH.to_string());
     core_metrics
         .device_manufacturer
-        .set(glean, "unknown".to_string());
-    core_metrics.device_model.set(glean, "unknown".to_string());
+        .set(glean, system::device_manufacturer());
+    core_metrics.device_model.set(glean, system::device_model());
+    let locale = client_info.locale.clone().unwrap_or_else(system::locale);
+    core_metrics.locale.set(glean, locale);
 }
 
 /// Sets whether upload is enabled or not.
 ///
+/// When upload is disabled, the `deletion-request` ping is uploaded
+/// before any further tasks are processed.
+/// If `upload_pending_pings_before_disabling` is configured,
+/// all pending pings are uploaded before that, instead of being deleted.
+/// This includes the pings and metrics of tasks launched before upload is disabled.
+///
 /// See `glean_core::Glean.set_upload_enabled`.
 pub fn set_upload_enabled(enabled: bool) {
-    if !was_initialize_called() {
+    if !is_initialized() {
         let msg =
             "Changing upload enabled before Glean is initialized is not supported.\n \
             Pass the correct state into `Glean.initialize()`.\n \
@@ -280,58 +941,612 @@ pub fn set_upload_enabled(enabled: bool) {
     }
 
     // Changing upload enabled always happens asynchronous.
-    // That way it follows what a user expect when calling it inbetween other calls:
-    // it executes in the right order.
+    // Disabling upload jumps ahead of queued metric recordings, so it takes effect
+    // before any data still waiting in the queue is recorded.
+    // Enabling upload doesn't, so data recorded while upload was disabled is not collected.
+    // An earlier change enabling upload that is still queued is skipped,
+    // so the last change made is the one that takes effect.
+    //
+    // When pending pings are uploaded before disabling upload, the queued tasks run first instead,
+    // so pings submitted before upload was disabled are uploaded too.
     //
     // Because the dispatch queue is halted until Glean is fully initialized
     // we can safely enqueue here and it will execute after initialization.
-    dispatcher::launch(move || {
-        with_glean_mut(|glean| {
-            let state = global_state().lock().unwrap();
-            let old_enabled = glean.is_upload_enabled();
-            glean.set_upload_enabled(enabled);
+    let flush_first = LAST_INIT_ARGS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|(cfg, _)| cfg.upload_pending_pings_before_disabling);
+    let priority = if enabled || flush_first {
+        dispatcher::Priority::Normal
+    } else {
+        dispatcher::Priority::High
+    };
+    let change = UPLOAD_ENABLED_REQUESTED.fetch_add(1, Ordering::SeqCst) + 1;
+    dispatcher::launch_with_priority(
+        move || {
+            // Tasks run one after another, so nothing else changes this in between.
+            if change < UPLOAD_ENABLED_APPLIED.load(Ordering::SeqCst) {
+                log::info!("Skipping a change of upload enabled superseded by a later one.");
+                return;
+            }
+            UPLOAD_ENABLED_APPLIED.store(change, Ordering::SeqCst);
+
+            if !enabled {
+                upload_pending_pings_before_disabling();
+            }
+
+            let upload_disabled = with_glean_mut(|glean| {
+                let mut state = global_state().lock().unwrap();
+                let old_enabled = glean.is_upload_enabled();
+                glean.set_upload_enabled(enabled);
+
+                if old_enabled && !enabled {
+                    // Stop submitting the metrics ping.
+                    state.metrics_ping_scheduler = None;
+                }
+
+                if !old_enabled && enabled {
+                    // If uploading is being re-enabled, we have to restore the
+                    // application-lifetime metrics.
+                    initialize_core_metrics(glean, &state.client_info, state.channel.clone());
+
+                    // Disabling upload cleared when the metrics ping was last sent,
+                    // so this starts over like a first run.
+                    start_metrics_ping_scheduler(glean, &mut state);
+                }
 
-            // TODO: Cancel upload and any outstanding metrics ping scheduler
-            // task. Will happen on bug 1672951.
+                old_enabled && !enabled
+            });
 
-            if !old_enabled && enabled {
-                // If uploading is being re-enabled, we have to restore the
-                // application-lifetime metrics.
-                initialize_core_metrics(&glean, &state.client_info, state.channel.clone());
+            if upload_disabled {
+                // glean-core submitted the deletion-request ping and cleared all other pending pings.
+                // Send it right away, before handling any other tasks.
+                // Don't hold on to the state while waiting for the upload.
+                let upload_manager = global_state().lock().unwrap().upload_manager.clone();
+                if let Some(upload_manager) = upload_manager {
+                    upload_manager.trigger_upload_and_wait();
+                }
             }
+        },
+        priority,
+    );
+}
 
-            // TODO: trigger upload for the deletion-ping. Will happen in bug 1672952.
-        });
+/// Uploads all pending pings, if configured to do so before upload is disabled.
+///
+/// Does nothing if upload is already disabled or no uploader is configured.
+fn upload_pending_pings_before_disabling() {
+    // Don't hold on to the state while waiting for the upload.
+    let upload_manager = {
+        let state = global_state().lock().unwrap();
+        if !state.upload_pending_pings_before_disabling {
+            return;
+        }
+        state.upload_manager.clone()
+    };
+
+    if let Some(upload_manager) = upload_manager {
+        if with_glean(|glean| glean.is_upload_enabled()) {
+            upload_manager.trigger_upload_and_wait();
+        }
+    }
+}
+
+/// Sets the maximum number of pending pings.
+///
+/// Once exceeded, the oldest pending pings are deleted.
+/// Deletion-request pings are never deleted.
+///
+/// See `glean_core::Glean.set_max_pending_pings`.
+pub fn set_max_pending_pings(max_count: usize) {
+    dispatcher::launch(move || {
+        with_glean_mut(|glean| glean.set_max_pending_pings(max_count));
     });
 }
 
+/// Sets a tag to send pings to the ["Ping Debug Viewer"](https://mozilla.github.io/glean/book/dev/core/internal/debug-pings.html).
+///
+/// All upload requests made after the tag was set include a `X-Debug-ID` header with the tag.
+/// The tag is not persisted and needs to be set again on every run.
+///
+/// # Arguments
+///
+/// * `tag` - A valid HTTP header value. Must match the regex: "[a-zA-Z0-9-]{1,20}".
+///
+/// # Returns
+///
+/// `false` if the tag is invalid. No tag is set in that case.
+pub fn set_debug_view_tag(tag: &str) -> bool {
+    if !glean_core::is_valid_tag(tag) {
+        log::error!("Invalid debug view tag '{}'. Not setting it.", tag);
+        return false;
+    }
+
+    let tag = tag.to_string();
+    dispatcher::launch(move || {
+        global_state().lock().unwrap().debug_view_tag = Some(tag);
+    });
+    true
+}
+
+/// Sets source tags to classify pings in the pipeline.
+///
+/// All upload requests made after the tags were set include a `X-Source-Tags` header
+/// with the comma-separated tags.
+/// The tags are not persisted and need to be set again on every run.
+///
+/// # Arguments
+///
+/// * `tags` - 1 to 5 valid HTTP header values, each matching the regex: "[a-zA-Z0-9-]{1,20}".
+///   Tags starting with `glean` are reserved and not allowed.
+///
+/// # Returns
+///
+/// `false` if any tag is invalid. No tags are set in that case.
+pub fn set_source_tags(tags: Vec<String>) -> bool {
+    if !glean_core::is_valid_source_tags(&tags) {
+        log::error!("Invalid source tags {:?}. Not setting them.", tags);
+        return false;
+    }
+
+    dispatcher::launch(move || {
+        global_state().lock().unwrap().source_tags = Some(tags);
+    });
+    true
+}
+
+/// Sets whether the payload of pings is logged.
+///
+/// When enabled, the pretty-printed JSON of every ping, including the builtin pings,
+/// is logged at info level right before it is handed to the uploader.
+/// This is not persisted and needs to be set again on every run.
+///
+/// See `glean_core::Glean.set_log_pings`.
+pub fn set_log_pings(value: bool) {
+    dispatcher::launch(move || {
+        global_state().lock().unwrap().log_pings = value;
+        with_glean_mut(|glean| glean.set_log_pings(value));
+    });
+}
+
+/// Gets the number of pings waiting to be uploaded.
+///
+/// This includes pings from previous runs that were not uploaded yet.
+/// Pings submitted in tasks that didn't run yet are not counted.
+/// This can be called from any thread and returns `0` until Glean is fully initialized.
+pub fn pending_ping_count() -> usize {
+    if !is_init_complete() {
+        return 0;
+    }
+    with_glean(|glean| glean.pending_pings_count())
+}
+
+/// Gets the maximum number of pending pings.
+///
+/// This reflects [`set_max_pending_pings`](fn.set_max_pending_pings.html)
+/// calls in tasks that already ran.
+/// Returns `None` until Glean is fully initialized, or if the number is not limited.
+pub fn max_pending_pings() -> Option<usize> {
+    if !is_init_complete() {
+        return None;
+    }
+    with_glean(|glean| glean.max_pending_pings())
+}
+
+/// A handle to a registered ping type.
+///
+/// Pass it to [`deregister_ping_type`](fn.deregister_ping_type.html)
+/// to remove the ping type from the registry again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PingHandle(String);
+
 /// Register a new [`PingType`](metrics/struct.PingType.html).
-pub fn register_ping_type(ping: &private::PingType) {
-    let ping = ping.clone();
+///
+/// # Returns
+///
+/// A [`PingHandle`](struct.PingHandle.html) to deregister the ping type later.
+pub fn register_ping_type(ping: &private::PingType) -> PingHandle {
+    let handle = PingHandle(ping.name.clone());
+
+    // Deciding under the lock makes sure a ping type is either replayed
+    // by the initialization or registered on the dispatcher afterwards.
+    let mut pre_init_pings = PRE_INIT_PING_REGISTRATION.lock().unwrap();
+    match pre_init_pings.as_mut() {
+        Some(pings) => pings.push(ping.clone()),
+        None => {
+            let ping = ping.clone();
+            dispatcher::launch(move || {
+                with_glean_mut(|glean| {
+                    glean.register_ping_type(&ping.ping_type);
+                })
+            });
+        }
+    }
+    handle
+}
+
+/// Deregisters a [`PingType`](metrics/struct.PingType.html).
+///
+/// Submitting the ping afterwards logs a warning and doesn't queue anything.
+/// Pings of that type that are already pending are still uploaded.
+///
+/// See `glean_core::Glean.deregister_ping_type`.
+pub fn deregister_ping_type(handle: PingHandle) {
     dispatcher::launch(move || {
         with_glean_mut(|glean| {
-            glean.register_ping_type(&ping.ping_type);
+            glean.deregister_ping_type(&handle.0);
         })
     })
 }
 
+/// Gets the names of all known ping types, in the order they were registered.
+///
+/// This includes the builtin pings: `baseline`, `metrics`, `events` and `deletion-request`.
+/// Registration is asynchronous, so ping types whose registration is still queued are not included.
+///
+/// # Returns
+///
+/// The names of the ping types, or an empty list if the initialization did not complete.
+pub fn list_registered_pings() -> Vec<String> {
+    if !is_init_complete() {
+        return Vec::new();
+    }
+
+    with_glean(|glean| {
+        // The `deletion-request` ping is known to glean-core, but not in its registry,
+        // so it can't be submitted by name.
+        std::iter::once(DELETION_REQUEST_PING_NAME)
+            .chain(glean.registered_ping_names())
+            .map(String::from)
+            .collect()
+    })
+}
+
+/// **Test-only API.**
+///
+/// Checks whether a ping type with the given name is registered.
+///
+/// This includes the builtin pings.
+/// Registration is asynchronous, so this waits for previously launched tasks
+/// and ping types registered before this call are seen.
+pub fn test_has_ping_type(name: &str) -> bool {
+    dispatcher::block_on_queue();
+    with_glean(|glean| glean.get_ping_by_name(name).is_some())
+}
+
+/// **Test-only API.**
+///
+/// Gets the names of all registered ping types, in the order they were registered.
+///
+/// The builtin pings come first, followed by the ping types registered before initialization.
+/// Registration is asynchronous, so this waits for previously launched tasks
+/// and ping types registered before this call are included.
+pub fn test_get_registered_ping_names() -> Vec<String> {
+    dispatcher::block_on_queue();
+    with_glean(|glean| {
+        glean
+            .registered_ping_names()
+            .into_iter()
+            .map(String::from)
+            .collect()
+    })
+}
+
+/// Indicates that an experiment is running.
+///
+/// Glean will then add an experiment annotation to the environment
+/// which is sent with pings. This information is not persisted between runs.
+///
+/// See `glean_core::Glean.set_experiment_active`.
+pub fn set_experiment_active(
+    experiment_id: String,
+    branch: String,
+    extra: Option<HashMap<String, String>>,
+) {
+    dispatcher::launch(move || {
+        with_glean(|glean| glean.set_experiment_active(experiment_id, branch, extra))
+    });
+}
+
+/// Records that the user was exposed to an experiment.
+///
+/// This is distinct from [`set_experiment_active`](fn.set_experiment_active.html),
+/// which records the enrollment: call this when the user actually encounters the experiment.
+/// The exposure is recorded in the `nimbus.exposure` event, sent in the `events` and `baseline` pings.
+///
+/// If the experiment is not active when the exposure is recorded,
+/// an `experiment_id_not_active` error is logged and nothing is recorded.
+///
+/// See `glean_core::Glean.record_experiment_exposure`.
+pub fn record_experiment_exposure(experiment_id: impl Into<String>) {
+    let experiment_id = experiment_id.into();
+    let timestamp = precise_time_ns() / 1_000_000;
+    dispatcher::launch(move || {
+        with_glean(|glean| glean.record_experiment_exposure(experiment_id, timestamp))
+    });
+}
+
+/// **Test-only API.**
+///
+/// Checks if an experiment is currently active.
+///
+/// This waits for previously launched tasks,
+/// so experiments set active or inactive before this call are seen.
+///
+/// See `glean_core::Glean.test_is_experiment_active`.
+pub fn test_is_experiment_active(experiment_id: &str) -> bool {
+    dispatcher::block_on_queue();
+    with_glean(|glean| glean.test_is_experiment_active(experiment_id.to_string()))
+}
+
+/// Indicates that an experiment is no longer running.
+///
+/// See `glean_core::Glean.set_experiment_inactive`.
+pub fn set_experiment_inactive(experiment_id: String) {
+    dispatcher::launch(move || with_glean(|glean| glean.set_experiment_inactive(experiment_id)));
+}
+
+/// A handle to a ping submission running on the dispatcher.
+///
+/// Dropping the handle does not cancel the submission.
+#[derive(Debug)]
+pub struct PingSubmission(crossbeam_channel::Receiver<Result<bool>>);
+
+impl PingSubmission {
+    /// Blocks until the ping submission was processed.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the ping was assembled and queued for upload.
+    /// `false` if it was skipped, e.g. because it was empty and not configured
+    /// to be sent if empty, because upload is disabled, or because the ping is unknown.
+    pub fn wait(self) -> bool {
+        self.wait_for_result().unwrap_or(false)
+    }
+
+    /// Blocks until the ping submission was processed, reporting why it failed.
+    fn wait_for_result(self) -> Result<bool> {
+        // If the task was discarded by the dispatcher the sender is dropped,
+        // which means Glean was shut down before the ping could be submitted.
+        self.0
+            .recv()
+            .unwrap_or_else(|_| Err(Error::not_initialized()))
+    }
+}
+
 /// Collects and submits a ping for eventual uploading.
 ///
 /// See `glean_core::Glean.submit_ping`.
-pub fn submit_ping(ping: &private::PingType, reason: Option<&str>) {
+///
+/// # Returns
+///
+/// A [`PingSubmission`](struct.PingSubmission.html) handle to observe
+/// whether the ping was actually queued.
+pub fn submit_ping(ping: &private::PingType, reason: Option<&str>) -> PingSubmission {
     submit_ping_by_name(&ping.name, reason)
 }
 
+/// Collects and submits a ping, blocking until the ping was assembled.
+///
+/// The submission runs on the dispatcher like with [`submit_ping`](fn.submit_ping.html),
+/// this only waits for that task to finish. If Glean is not initialized yet,
+/// this waits for the initialization to finish first.
+///
+/// # Errors
+///
+/// * [`ErrorKind::PingNotFound`] if the ping is not registered.
+/// * [`ErrorKind::NotInitialized`] if Glean was shut down before the ping was submitted.
+/// * Any error collecting or writing the ping to disk.
+///
+/// Skipping the ping, e.g. because it is empty or upload is disabled, is not an error.
+///
+/// [`ErrorKind::PingNotFound`]: enum.ErrorKind.html#variant.PingNotFound
+/// [`ErrorKind::NotInitialized`]: enum.ErrorKind.html#variant.NotInitialized
+pub fn submit_ping_and_wait(ping: &private::PingType, reason: Option<&str>) -> Result<()> {
+    submit_ping(ping, reason).wait_for_result().map(|_| ())
+}
+
+/// Writes data with ping lifetime that is held in memory to disk right away.
+///
+/// Such data is only held in memory if `Configuration.delay_ping_lifetime_io` is set,
+/// otherwise there is nothing to persist and this returns `Ok` right away.
+/// With that flag set, this also happens automatically every 60 seconds
+/// and on `shutdown`, so data isn't lost if the process is killed between pings.
+///
+/// Previously launched tasks are finished first, so their data is included.
+/// If Glean is not initialized yet, this waits for the initialization to finish first.
+///
+/// # Errors
+///
+/// * [`ErrorKind::NotInitialized`] if Glean was shut down before the data was persisted.
+/// * Any error writing the data to disk.
+///
+/// [`ErrorKind::NotInitialized`]: enum.ErrorKind.html#variant.NotInitialized
+pub fn persist_ping_lifetime_data() -> Result<()> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    dispatcher::launch(move || {
+        let result = with_glean(|glean| glean.persist_ping_lifetime_data());
+        // The caller might not be interested in the result.
+        let _ = tx.send(result);
+    });
+    // If the task was discarded by the dispatcher the sender is dropped,
+    // which means Glean was shut down before the data could be persisted.
+    rx.recv().unwrap_or_else(|_| Err(Error::not_initialized()))
+}
+
+/// Compacts the database in the background, reclaiming the space left behind by removed metrics.
+///
+/// The data is copied to a new database file, which then replaces the current one.
+/// This is done on the dispatcher, so the caller doesn't wait for the disk I/O.
+/// Errors are logged, see [`compact_storage_sync`](fn.compact_storage_sync.html) to handle them.
+pub fn compact_storage() {
+    dispatcher::launch(|| match with_glean_mut(|glean| glean.compact_storage()) {
+        Ok(reclaimed) => log::info!("Compacting the storage reclaimed {} bytes", reclaimed),
+        Err(e) => log::error!("Failed to compact the storage: {}", e),
+    });
+}
+
+/// Compacts the database, blocking until it is done.
+///
+/// Previously launched tasks are finished first.
+/// If Glean is not initialized yet, this waits for the initialization to finish first.
+///
+/// # Returns
+///
+/// The number of bytes reclaimed on disk.
+///
+/// # Errors
+///
+/// * [`ErrorKind::NotInitialized`] if Glean was shut down before the storage was compacted.
+/// * Any error copying the data or replacing the database files.
+///   The database is kept as it was in that case.
+///
+/// [`ErrorKind::NotInitialized`]: enum.ErrorKind.html#variant.NotInitialized
+pub fn compact_storage_sync() -> Result<u64> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    dispatcher::launch(move || {
+        let result = with_glean_mut(|glean| glean.compact_storage());
+        // The caller might not be interested in the result.
+        let _ = tx.send(result);
+    });
+    // If the task was discarded by the dispatcher the sender is dropped,
+    // which means Glean was shut down before the storage was compacted.
+    rx.recv().unwrap_or_else(|_| Err(Error::not_initialized()))
+}
+
+/// Gets the disk space used by Glean's data, in bytes.
+///
+/// This is the size of the database file and of all pending pings,
+/// including `deletion-request` pings. It is computed on every call.
+///
+/// This can be called from any thread: it neither waits for dispatched tasks
+/// nor locks the Glean object.
+///
+/// # Returns
+///
+/// `0` if Glean was not initialized.
+pub fn storage_size_bytes() -> u64 {
+    let data_path = match STATE.get() {
+        Some(state) => state.lock().unwrap().data_path.clone(),
+        None => return 0,
+    };
+    glean_core::storage_size_bytes(&data_path)
+}
+
+/// Gets the disk space Glean's data is expected to stay within, in bytes.
+///
+/// Glean doesn't enforce this limit, it only reports `Configuration::max_storage_bytes`.
+///
+/// # Returns
+///
+/// `None` if no limit was configured or Glean was not initialized.
+pub fn max_storage_bytes() -> Option<u64> {
+    STATE.get()?.lock().unwrap().max_storage_bytes
+}
+
+/// Collects and submits a ping by name, bypassing the dispatcher.
+///
+/// This needs to be called with the Glean object from within a dispatched task
+/// or from the initialization thread, to ensure ordering with other tasks.
+///
+/// # Returns
+///
+/// `true` if the ping was assembled and queued for upload.
+///
+/// # Errors
+///
+/// If the ping is not registered, or collecting or writing the ping to disk failed.
+fn submit_ping_by_name_sync(glean: &Glean, ping: &str, reason: Option<&str>) -> Result<bool> {
+    if !glean.is_upload_enabled() {
+        log::info!("Glean disabled: not submitting any pings.");
+        return Ok(false);
+    }
+
+    if glean.get_ping_by_name(ping).is_none() {
+        log::warn!(
+            "Attempted to submit unknown ping '{}'. Was it deregistered?",
+            ping
+        );
+        return Err(ErrorKind::PingNotFound(ping.to_string()).into());
+    }
+
+    glean.submit_ping_by_name(ping, reason)
+}
+
 /// Collects and submits a ping for eventual uploading by name.
 ///
 /// See `glean_core::Glean.submit_ping_by_name`.
-pub fn submit_ping_by_name(ping: &str, reason: Option<&str>) {
+///
+/// # Returns
+///
+/// A [`PingSubmission`](struct.PingSubmission.html) handle to observe
+/// whether the ping was actually queued.
+pub fn submit_ping_by_name(ping: &str, reason: Option<&str>) -> PingSubmission {
+    submit_ping_by_name_with_priority(ping, reason, dispatcher::Priority::Normal)
+}
+
+/// Collects and submits a ping for eventual uploading by name, with the given priority.
+///
+/// A ping submitted with [`Priority::High`](dispatcher/enum.Priority.html#variant.High)
+/// is collected before any queued task of normal priority, e.g. when the application is about to go
+/// to the background. Metrics recorded before the submission that are still queued then end up
+/// in the next ping instead.
+///
+/// # Returns
+///
+/// A [`PingSubmission`](struct.PingSubmission.html) handle to observe
+/// whether the ping was actually queued.
+pub fn submit_ping_by_name_with_priority(
+    ping: &str,
+    reason: Option<&str>,
+    priority: dispatcher::Priority,
+) -> PingSubmission {
     let ping = ping.to_string();
     let reason = reason.map(|s| s.to_string());
-    dispatcher::launch(move || {
-        with_glean(|glean| glean.submit_ping_by_name(&ping, reason.as_deref()).ok());
-    })
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    // The ping is assembled on the dispatcher thread, in the span it was submitted in.
+    #[cfg(feature = "opentelemetry")]
+    let trace_context = opentelemetry::Context::current();
+    dispatcher::launch_with_priority(
+        move || {
+            #[cfg(feature = "opentelemetry")]
+            let _trace_context = trace_context.attach();
+            private::ping::run_test_callback(&ping, reason.as_deref());
+
+            let result =
+                with_glean(|glean| submit_ping_by_name_sync(glean, &ping, reason.as_deref()));
+            if let Ok(true) = result {
+                trigger_upload(&global_state().lock().unwrap());
+            }
+            // The caller might not be interested in the result.
+            let _ = tx.send(result);
+        },
+        priority,
+    );
+    PingSubmission(rx)
+}
+
+/// Gets the payload a ping would have if it was submitted now.
+///
+/// This is meant for inspecting a ping before it is submitted, e.g. for debugging.
+/// Nothing is cleared and the ping's sequence number and start time are not advanced,
+/// so this doesn't affect the next submission of the ping.
+///
+/// This can be called from any thread and doesn't wait for previously launched tasks,
+/// so data recorded right before this call might not be included yet.
+///
+/// # Returns
+///
+/// The ping payload as JSON, or `None` if Glean is not initialized yet,
+/// the ping is not registered or it would not be sent because it has no data.
+pub fn peek_ping_content(ping_name: &str) -> Option<serde_json::Value> {
+    if !INIT_COMPLETE.load(Ordering::SeqCst) {
+        return None;
+    }
+    with_glean(|glean| glean.assemble_ping_for_inspection(ping_name))
 }
 
 #[cfg(test)]
-mod test;
+#[path = "lib_unit_tests.rs"]
+mod tests;
@@ -0,0 +1,340 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::private::BooleanMetric;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use super::*;
+
+// Because glean_preview is a global-singleton, we need to run the tests one-by-one to avoid different tests stomping over each other.
+// This is only an issue because we're resetting Glean, this cannot happen in normal use of the
+// RLB.
+//
+// We use a global lock to force synchronization of all tests, even if run multi-threaded.
+// This allows us to run without `--test-threads 1`.`
+static GLOBAL_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.rlb.test";
+
+// Reset Glean to a new instance with a temporary directory, waiting for the initialization.
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+fn new_glean() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    test_reset_glean(
+        test_configuration(dir.path()),
+        ClientInfoMetrics::unknown(),
+        true,
+    )
+    .unwrap();
+    dir
+}
+
+fn test_configuration(data_path: &std::path::Path) -> Configuration {
+    Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .channel("testing")
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn disabling_upload_disables_metrics_recording() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let _t = new_glean();
+
+    let metric = BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Application,
+        disabled: false,
+        dynamic_label: None,
+    });
+
+    crate::set_upload_enabled(false);
+
+    assert!(metric.test_get_value("store1").is_none())
+}
+
+/// Blocks the worker thread until the returned sender is used or dropped.
+fn block_dispatcher() -> crossbeam_channel::Sender<()> {
+    let (unblock_sender, unblock_receiver) = crossbeam_channel::bounded::<()>(0);
+    let (started_sender, started_receiver) = crossbeam_channel::bounded::<()>(0);
+    crate::dispatcher::launch(move || {
+        started_sender.send(()).unwrap();
+        let _ = unblock_receiver.recv();
+    });
+    started_receiver.recv().unwrap();
+    unblock_sender
+}
+
+#[test]
+fn metrics_recorded_while_upload_is_disabled_are_dropped() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let _t = new_glean();
+
+    let metric = BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Application,
+        disabled: false,
+        dynamic_label: None,
+    });
+
+    // Keep all three tasks queued, so enabling upload could jump ahead of the recording.
+    let unblock = block_dispatcher();
+    crate::set_upload_enabled(false);
+    metric.set(true);
+    crate::set_upload_enabled(true);
+    drop(unblock);
+    crate::dispatcher::block_on_queue();
+
+    assert!(with_glean(|glean| glean.is_upload_enabled()));
+    assert!(metric.test_get_value("store1").is_none());
+}
+
+#[test]
+fn the_last_change_of_upload_enabled_takes_effect() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let _t = new_glean();
+
+    // Disabling upload again jumps ahead of the queued change enabling it.
+    let unblock = block_dispatcher();
+    crate::set_upload_enabled(false);
+    crate::set_upload_enabled(true);
+    crate::set_upload_enabled(false);
+    drop(unblock);
+    crate::dispatcher::block_on_queue();
+
+    assert!(!with_glean(|glean| glean.is_upload_enabled()));
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1672982.
+fn test_experiments_recording() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1672982.
+fn test_experiments_recording_before_glean_inits() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673645.
+fn test_sending_of_foreground_background_pings() {
+    todo!()
+}
+
+#[test]
+fn test_sending_of_startup_baseline_ping() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let dir = tempfile::tempdir().unwrap();
+
+    // Simulate a previous run that was not shut down cleanly.
+    {
+        let glean = Glean::new(glean_core::Configuration {
+            data_path: dir.path().into(),
+            application_id: GLOBAL_APPLICATION_ID.into(),
+            language_binding_name: LANGUAGE_BINDING_NAME.into(),
+            upload_enabled: true,
+            max_events: None,
+            delay_ping_lifetime_io: false,
+            compress_pings: true,
+            max_pings_per_minute: None,
+            extra_server_endpoints: Default::default(),
+            disable_internal_error_metrics: false,
+            rate_limit: None,
+            event_dedup_window: None,
+            enable_trace_context: false,
+        })
+        .unwrap();
+        glean.set_dirty_flag(true);
+    }
+
+    // Other tests leave Glean initialized, so it needs to be reset, keeping the data.
+    test_reset_glean(
+        test_configuration(dir.path()),
+        ClientInfoMetrics::unknown(),
+        false,
+    )
+    .unwrap();
+
+    // The ping is submitted during initialization, before any queued task runs.
+    let doc_ids = crate::test::pending_ping_doc_ids("baseline");
+    assert_eq!(1, doc_ids.len());
+    let content =
+        std::fs::read_to_string(dir.path().join("pending_pings").join(&doc_ids[0])).unwrap();
+    let payload: serde_json::Value = serde_json::from_str(content.lines().nth(1).unwrap()).unwrap();
+    assert_eq!("dirty_startup", payload["ping_info"]["reason"]);
+
+    shutdown();
+}
+
+#[test]
+fn initialize_must_not_crash_if_data_dir_is_messed_up() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let dir = tempfile::tempdir().unwrap();
+    // Create a file in the temporary dir and use that as the
+    // name of the Glean data dir.
+    let file_path = dir.path().join("notadir");
+    std::fs::write(&file_path, "test").expect("The test Glean dir file must be created");
+
+    let result = test_reset_glean(
+        test_configuration(&file_path),
+        ClientInfoMetrics::unknown(),
+        false,
+    );
+    assert!(matches!(result, Err(InitializeError::InvalidDataPath(..))));
+    assert!(!is_initialized());
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673667.
+fn queued_recorded_metrics_correctly_record_during_init() {
+    todo!()
+}
+
+#[test]
+fn initializing_twice_is_a_noop() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let dir = new_glean();
+    initialize(test_configuration(dir.path()), ClientInfoMetrics::unknown()).unwrap();
+    assert!(is_init_complete());
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673668.
+fn dont_handle_events_when_uninitialized() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn the_app_channel_must_be_correctly_set_if_requested() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn ping_collection_must_happen_after_concurrently_scheduled_metrics_recordings() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn basic_metrics_should_be_cleared_when_disabling_uploading() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn core_metrics_should_be_cleared_and_restored_when_disabling_and_enabling_uploading() {
+    todo!()
+}
+
+#[test]
+fn overflowing_the_task_queue_records_telemetry() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut cfg = test_configuration(dir.path());
+    cfg.max_dispatch_queue = Some(2);
+    test_reset_glean(cfg, ClientInfoMetrics::unknown(), true).unwrap();
+
+    // Block the worker thread until we're done queueing.
+    let unblock_sender = block_dispatcher();
+
+    for _ in 0..5 {
+        crate::dispatcher::launch(|| {});
+    }
+    // The 2 queued tasks and the task recording the dropped ones.
+    assert_eq!(3, crate::dispatcher::queue_len());
+
+    unblock_sender.send(()).unwrap();
+    crate::dispatcher::block_on_queue();
+
+    let metrics = core_metrics::DispatcherMetrics::new();
+    with_glean(|glean| {
+        assert_eq!(
+            Some(3),
+            metrics
+                .dispatch_queue_overflow
+                .test_get_value(glean, "metrics")
+        );
+        assert_eq!(
+            None,
+            metrics
+                .preinit_tasks_overflow
+                .test_get_value(glean, "metrics")
+        );
+    });
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn sending_deletion_ping_if_disabled_outside_of_run() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn no_sending_of_deletion_ping_if_unchanged_outside_of_run() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn test_sending_of_startup_baseline_ping_with_application_lifetime_metric() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn test_dirty_flag_is_reset_to_false() {
+    todo!()
+}
+
+#[test]
+#[ignore] // TODO: To be done in bug 1673672.
+fn flipping_upload_enabled_respects_order_of_events() {
+    todo!()
+}
+
+#[test]
+fn log_pings_is_kept_when_initializing_again() {
+    let _lock = GLOBAL_LOCK.lock().unwrap();
+    env_logger::try_init().ok();
+
+    let t = new_glean();
+    set_log_pings(true);
+    crate::dispatcher::block_on_queue();
+    assert!(global_state().lock().unwrap().log_pings);
+
+    // `test_reset_glean` resets the debug options, so this initializes again like an application would.
+    shutdown();
+    initialize(test_configuration(t.path()), ClientInfoMetrics::unknown()).unwrap();
+    crate::dispatcher::block_on_queue();
+    assert!(global_state().lock().unwrap().log_pings);
+
+    set_log_pings(false);
+    crate::dispatcher::block_on_queue();
+    assert!(!global_state().lock().unwrap().log_pings);
+}
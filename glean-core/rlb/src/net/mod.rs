@@ -0,0 +1,393 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Handling the Glean upload logic.
+//!
+//! This doesn't perform the actual upload but rather handles
+//! retries, upload limitations and error tracking.
+//! The actual upload is left to the configured [`PingUploader`](trait.PingUploader.html).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use glean_core::upload::PingUploadTask;
+pub use glean_core::upload::UploadResult;
+
+#[cfg(feature = "reqwest-uploader")]
+mod reqwest_uploader;
+#[cfg(feature = "reqwest-uploader")]
+pub use reqwest_uploader::ReqwestUploader;
+
+/// The default server pings are sent to.
+pub(crate) const DEFAULT_GLEAN_ENDPOINT: &str = "https://incoming.telemetry.mozilla.org";
+
+/// The name of the thread processing the uploads.
+const UPLOAD_THREAD_NAME: &str = "glean.upload";
+
+/// A description of a component used to upload pings.
+pub trait PingUploader: std::fmt::Debug + Send + Sync {
+    /// Uploads a ping to a server.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - the URL to upload the data to.
+    /// * `body` - the serialized data to send.
+    /// * `headers` - a map of headers to send with the request.
+    ///
+    /// # Returns
+    ///
+    /// The [`UploadResult`](enum.UploadResult.html) of the upload attempt.
+    fn upload(&self, url: String, body: Vec<u8>, headers: HashMap<String, String>) -> UploadResult;
+}
+
+/// An uploader that doesn't send anything.
+///
+/// Every upload is reported as successful, so pings are deleted as if they were sent.
+/// Useful for applications that must not send data, but shouldn't pile up pending pings either.
+#[derive(Clone, Debug, Default)]
+pub struct NoopUploader;
+
+impl PingUploader for NoopUploader {
+    fn upload(
+        &self,
+        _url: String,
+        _body: Vec<u8>,
+        _headers: HashMap<String, String>,
+    ) -> UploadResult {
+        UploadResult::HttpStatus(200)
+    }
+}
+
+/// Validates the configured server endpoint.
+///
+/// # Arguments
+///
+/// * `server_endpoint` - the configured server, if any.
+///
+/// # Returns
+///
+/// The configured server without a trailing slash, if it is a valid http or https URL.
+/// The default server otherwise. An error is logged if the configured server is invalid.
+pub(crate) fn validate_server_endpoint(server_endpoint: Option<&str>) -> String {
+    server_endpoint
+        .and_then(parse_server_endpoint)
+        .unwrap_or_else(|| DEFAULT_GLEAN_ENDPOINT.to_string())
+}
+
+/// Validates the configured server endpoints for pings not sent to the default server.
+///
+/// # Arguments
+///
+/// * `endpoints` - the configured servers, by ping name pattern.
+///
+/// # Returns
+///
+/// The valid http or https URLs without a trailing slash, by ping name pattern.
+/// Invalid servers are logged and left out, so matching pings are sent to the default server.
+pub(crate) fn validate_extra_server_endpoints(
+    endpoints: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    endpoints
+        .iter()
+        .filter_map(|(pattern, endpoint)| {
+            parse_server_endpoint(endpoint).map(|endpoint| (pattern.clone(), endpoint))
+        })
+        .collect()
+}
+
+/// Parses a server endpoint, logging an error if it is not a valid http or https URL.
+fn parse_server_endpoint(server_endpoint: &str) -> Option<String> {
+    match url::Url::parse(server_endpoint) {
+        Ok(url) if (url.scheme() == "http" || url.scheme() == "https") && url.has_host() => {
+            Some(server_endpoint.trim_end_matches('/').to_string())
+        }
+        Ok(_) => {
+            log::error!(
+                "Invalid server endpoint {:?}: not a http(s) URL. Using the default server.",
+                server_endpoint
+            );
+            None
+        }
+        Err(e) => {
+            log::error!(
+                "Invalid server endpoint {:?}: {}. Using the default server.",
+                server_endpoint,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Appends the suffix to the ping name in an upload path.
+///
+/// # Arguments
+///
+/// * `path` - the upload path, of the form
+///   `/submit/<application_id>/<ping_name>/<schema_version>/<document_id>`.
+/// * `suffix` - the configured suffix, if any.
+///
+/// # Returns
+///
+/// The path with `<ping_name>` replaced by `<ping_name>-<suffix>`.
+/// The path is returned unchanged if there's no suffix or the path is malformed.
+fn apply_ping_name_suffix(path: &str, suffix: Option<&str>) -> String {
+    let suffix = match suffix {
+        Some(suffix) => suffix,
+        None => return path.to_string(),
+    };
+
+    let mut segments: Vec<&str> = path.split('/').collect();
+    if segments.len() != 6 {
+        log::warn!(
+            "Unexpected upload path {:?}, not applying the ping name suffix",
+            path
+        );
+        return path.to_string();
+    }
+
+    let ping_name = format!("{}-{}", segments[3], suffix);
+    segments[3] = &ping_name;
+    segments.join("/")
+}
+
+/// The logic for uploading pings: this leaves the actual upload
+/// to the configured uploader.
+///
+/// Clones share the same upload thread.
+#[derive(Clone, Debug)]
+pub(crate) struct UploadManager {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    server_endpoint: String,
+    ping_name_suffix: Option<String>,
+    uploader: Arc<dyn PingUploader>,
+    state: Mutex<UploadThreadState>,
+    /// The document id and result of the last processed upload.
+    last_result: Mutex<Option<(String, UploadResult)>>,
+    /// Notified when the upload thread stops.
+    stopped: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct UploadThreadState {
+    /// Whether the upload thread is running.
+    running: bool,
+    /// Whether an upload was triggered while the upload thread was running.
+    /// The thread then asks for new upload tasks once more before stopping,
+    /// so that pings enqueued in the meantime are not missed.
+    triggered: bool,
+}
+
+impl UploadManager {
+    /// Creates a new upload manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_endpoint` - the server pings are sent to.
+    /// * `ping_name_suffix` - the suffix appended to ping names in the upload path, if any.
+    /// * `uploader` - the component doing the actual upload.
+    pub(crate) fn new(
+        server_endpoint: String,
+        ping_name_suffix: Option<String>,
+        uploader: Arc<dyn PingUploader>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                server_endpoint,
+                ping_name_suffix,
+                uploader,
+                state: Mutex::new(UploadThreadState::default()),
+                last_result: Mutex::new(None),
+                stopped: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Signals Glean to upload pings at the next best opportunity.
+    ///
+    /// This spawns a thread processing all pending upload tasks.
+    /// If that thread is already running, it processes newly enqueued pings before stopping.
+    pub(crate) fn trigger_upload(&self) {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.running {
+                state.triggered = true;
+                return;
+            }
+            state.running = true;
+        }
+
+        let inner = Arc::clone(&self.inner);
+
+        let spawned = thread::Builder::new()
+            .name(UPLOAD_THREAD_NAME.to_string())
+            .spawn(move || {
+                // Limits are enforced by glean-core to avoid an inifinite loop here.
+                // Whenever a limit is reached, we receive `PingUploadTask::Done` and step out.
+                loop {
+                    let (incoming_task, extra_server_endpoint) = crate::with_glean(|glean| {
+                        let task = glean.get_upload_task();
+                        let endpoint = match &task {
+                            PingUploadTask::Upload(request) => {
+                                glean.server_endpoint_for(request).map(str::to_string)
+                            }
+                            _ => None,
+                        };
+                        (task, endpoint)
+                    });
+
+                    match incoming_task {
+                        PingUploadTask::Upload(request) => {
+                            let document_id = request.document_id.clone();
+                            let path = apply_ping_name_suffix(
+                                &request.path,
+                                inner.ping_name_suffix.as_deref(),
+                            );
+                            let server_endpoint = extra_server_endpoint
+                                .as_deref()
+                                .unwrap_or(&inner.server_endpoint);
+                            let upload_url = format!("{}{}", server_endpoint, path);
+                            let mut headers = request.headers;
+                            let (debug_view_tag, source_tags) = {
+                                let state = crate::global_state().lock().unwrap();
+                                (state.debug_view_tag.clone(), state.source_tags.clone())
+                            };
+                            if let Some(tag) = debug_view_tag {
+                                headers.insert("X-Debug-ID".to_string(), tag);
+                            }
+                            if let Some(tags) = source_tags {
+                                headers.insert("X-Source-Tags".to_string(), tags.join(","));
+                            }
+                            let result = inner.uploader.upload(upload_url, request.body, headers);
+                            // Process the upload response.
+                            crate::with_glean(|glean| {
+                                glean.process_ping_upload_response(&document_id, result.clone())
+                            });
+                            *inner.last_result.lock().unwrap() = Some((document_id, result));
+                        }
+                        PingUploadTask::Wait(time) => {
+                            // glean-core suggests how long to wait,
+                            // e.g. until the current rate limiting interval ends.
+                            thread::sleep(Duration::from_millis(time));
+                        }
+                        PingUploadTask::Done => {
+                            let mut state = inner.state.lock().unwrap();
+                            if state.triggered {
+                                state.triggered = false;
+                                continue;
+                            }
+                            state.running = false;
+                            inner.stopped.notify_all();
+                            return;
+                        }
+                    }
+                }
+            });
+
+        if let Err(e) = spawned {
+            log::error!("Failed to spawn the upload thread: {}", e);
+            let mut state = self.inner.state.lock().unwrap();
+            state.running = false;
+            self.inner.stopped.notify_all();
+        }
+    }
+
+    /// Gets the document id and result of the last processed upload, if any.
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn last_result(&self) -> Option<(String, UploadResult)> {
+        self.inner.last_result.lock().unwrap().clone()
+    }
+
+    /// Signals Glean to upload pings and blocks until there's nothing left to upload.
+    ///
+    /// Note that glean-core limits how many uploads are attempted in a row,
+    /// so pings might still be pending afterwards, e.g. after repeated recoverable failures.
+    pub(crate) fn trigger_upload_and_wait(&self) {
+        self.trigger_upload();
+        self.wait_until_stopped();
+    }
+
+    /// Blocks until the upload thread stopped, if it is running.
+    pub(crate) fn wait_until_stopped(&self) {
+        let mut state = self.inner.state.lock().unwrap();
+        while state.running {
+            state = self.inner.stopped.wait(state).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn server_endpoint_defaults_if_not_set() {
+        assert_eq!(DEFAULT_GLEAN_ENDPOINT, validate_server_endpoint(None));
+    }
+
+    #[test]
+    fn valid_server_endpoints_are_used() {
+        assert_eq!(
+            "http://localhost:8080",
+            validate_server_endpoint(Some("http://localhost:8080"))
+        );
+        assert_eq!(
+            "https://example.com/ingestion",
+            validate_server_endpoint(Some("https://example.com/ingestion/"))
+        );
+    }
+
+    #[test]
+    fn ping_name_suffix_is_applied_to_the_ping_name() {
+        let path = "/submit/org-mozilla-test/baseline/1/8fc3d1a1-0c92-4b7b-9df1-b6d6e6ff53d5";
+        assert_eq!(path, apply_ping_name_suffix(path, None));
+        assert_eq!(
+            "/submit/org-mozilla-test/baseline-staging/1/8fc3d1a1-0c92-4b7b-9df1-b6d6e6ff53d5",
+            apply_ping_name_suffix(path, Some("staging"))
+        );
+        assert_eq!(
+            "/not/an/upload/path",
+            apply_ping_name_suffix("/not/an/upload/path", Some("staging"))
+        );
+    }
+
+    #[test]
+    fn invalid_extra_server_endpoints_are_left_out() {
+        let endpoints = [
+            ("crash", "https://crash.example.com/"),
+            ("custom-*", "ftp://custom.example.com"),
+        ]
+        .iter()
+        .map(|(pattern, endpoint)| (pattern.to_string(), endpoint.to_string()))
+        .collect();
+
+        let valid = validate_extra_server_endpoints(&endpoints);
+        assert_eq!(1, valid.len());
+        assert_eq!("https://crash.example.com", valid["crash"]);
+    }
+
+    #[test]
+    fn invalid_server_endpoints_fall_back_to_default() {
+        for invalid in &[
+            "",
+            "localhost:8080",
+            "ftp://example.com",
+            "http://",
+            "not a url",
+        ] {
+            assert_eq!(
+                DEFAULT_GLEAN_ENDPOINT,
+                validate_server_endpoint(Some(invalid)),
+                "{} should be invalid",
+                invalid
+            );
+        }
+    }
+}
@@ -0,0 +1,143 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+use flate2::{write::GzEncoder, Compression};
+
+use super::{PingUploader, UploadResult};
+
+/// How long an upload may take before it is given up as a recoverable failure.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An uploader sending pings over HTTP, using a blocking [reqwest](https://docs.rs/reqwest) client.
+///
+/// Request bodies are sent gzip compressed,
+/// with `Content-Type: application/json; charset=utf-8` and `Content-Encoding: gzip` headers.
+/// The HTTP status of the response is handed to Glean, which treats 2xx as success
+/// and 4xx as unrecoverable failure. Other statuses, timeouts and connection errors
+/// are recoverable failures, so the ping is retried.
+/// A `Retry-After` header in the response is handed to Glean as well, which pauses uploads accordingly.
+///
+/// Only available with the `reqwest-uploader` feature.
+#[derive(Debug)]
+pub struct ReqwestUploader {
+    client: reqwest::blocking::Client,
+}
+
+impl ReqwestUploader {
+    /// Creates a new uploader, timing out uploads after 10 seconds.
+    ///
+    /// # Panics
+    ///
+    /// If the HTTP client can't be set up, e.g. because the TLS backend fails to initialize.
+    pub fn new() -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(UPLOAD_TIMEOUT)
+            .build()
+            .expect("Failed to create the HTTP client");
+        Self { client }
+    }
+}
+
+impl Default for ReqwestUploader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gzip compresses the body, unless Glean already did.
+fn compress_body(body: Vec<u8>, headers: &mut HashMap<String, String>) -> Vec<u8> {
+    if headers.get("Content-Encoding").map(String::as_str) == Some("gzip") {
+        return body;
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    match encoder.write_all(&body).and_then(|_| encoder.finish()) {
+        Ok(gzipped) => {
+            headers.insert("Content-Encoding".to_string(), "gzip".to_string());
+            headers.insert("Content-Length".to_string(), gzipped.len().to_string());
+            gzipped
+        }
+        Err(e) => {
+            log::warn!(
+                "Failed to gzip the ping body, sending it uncompressed: {}",
+                e
+            );
+            body
+        }
+    }
+}
+
+impl PingUploader for ReqwestUploader {
+    fn upload(
+        &self,
+        url: String,
+        body: Vec<u8>,
+        mut headers: HashMap<String, String>,
+    ) -> UploadResult {
+        let body = compress_body(body, &mut headers);
+        headers
+            .entry("Content-Type".to_string())
+            .or_insert_with(|| "application/json; charset=utf-8".to_string());
+
+        let mut request = self.client.post(&url).body(body);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        match request.send() {
+            Ok(response) => {
+                let status = u32::from(response.status().as_u16());
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok());
+                match retry_after {
+                    Some(value) => {
+                        UploadResult::HttpStatusWithRetryAfter(status, value.to_string())
+                    }
+                    None => UploadResult::HttpStatus(status),
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to upload a ping to {}: {}", url, e);
+                UploadResult::RecoverableFailure
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    #[test]
+    fn uncompressed_bodies_are_gzipped() {
+        let mut headers = HashMap::new();
+        let body = compress_body(b"{}".to_vec(), &mut headers);
+
+        let mut decoded = String::new();
+        GzDecoder::new(&body[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!("{}", decoded);
+        assert_eq!(Some(&"gzip".to_string()), headers.get("Content-Encoding"));
+        assert_eq!(Some(&body.len().to_string()), headers.get("Content-Length"));
+    }
+
+    #[test]
+    fn compressed_bodies_are_kept() {
+        let mut headers = HashMap::new();
+        headers.insert("Content-Encoding".to_string(), "gzip".to_string());
+        let body = compress_body(vec![1, 2, 3], &mut headers);
+        assert_eq!(vec![1, 2, 3], body);
+    }
+}
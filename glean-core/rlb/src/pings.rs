@@ -18,9 +18,11 @@ pub mod pings {
             true,
             true,
             vec![
+                "active".to_string(),
                 "background".to_string(),
                 "dirty_startup".to_string(),
-                "foreground".to_string()
+                "foreground".to_string(),
+                "inactive".to_string()
             ]
         )
     });
@@ -44,12 +46,13 @@ pub mod pings {
     #[allow(non_upper_case_globals)]
     pub static events: Lazy<PingType> = Lazy::new(|| {
         PingType::new(
-            "metrics",
+            "events",
             true,
             false,
             vec![
                 "background".to_string(),
                 "max_capacity".to_string(),
+                "periodic".to_string(),
                 "startup".to_string()
             ]
         )
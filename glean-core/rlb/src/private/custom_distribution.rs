@@ -0,0 +1,117 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use inherent::inherent;
+use std::sync::Arc;
+
+use glean_core::metrics::{DistributionData, HistogramType, MetricType};
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+
+// We need to wrap the glean-core type: otherwise if we try to implement
+// the trait for the metric in `glean_core::metrics` we hit error[E0117]:
+// only traits defined in the current crate can be implemented for arbitrary
+// types.
+
+/// This implements the developer facing API for recording custom distribution metrics.
+///
+/// Instances of this class type are automatically generated by the parsers
+/// at build time, allowing developers to record values that were previously
+/// registered in the metrics.yaml file.
+#[derive(Clone)]
+pub struct CustomDistributionMetric(pub(crate) Arc<glean_core::metrics::CustomDistributionMetric>);
+
+impl CustomDistributionMetric {
+    /// The public constructor used by automatically generated metrics.
+    ///
+    /// The bucket ranges are the ones declared in the metrics.yaml file.
+    /// Samples outside of them are recorded in the first or last bucket.
+    pub fn new(
+        meta: glean_core::CommonMetricData,
+        range_min: u64,
+        range_max: u64,
+        bucket_count: u64,
+        histogram_type: HistogramType,
+    ) -> Self {
+        Self(Arc::new(
+            glean_core::metrics::CustomDistributionMetric::new(
+                meta,
+                range_min,
+                range_max,
+                bucket_count,
+                histogram_type,
+            ),
+        ))
+    }
+}
+
+#[inherent(pub)]
+impl glean_core::traits::CustomDistribution for CustomDistributionMetric {
+    /// Accumulates the provided signed samples in the metric.
+    ///
+    /// This is required so that the platform-specific code can provide us with
+    /// 64 bit signed integers if no `u64` comparable type is available. This
+    /// will take care of filtering and reporting errors for any provided negative
+    /// sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The vector holding the samples to be recorded by the metric.
+    ///
+    /// ## Notes
+    ///
+    /// Discards any negative value in `samples` and report an `ErrorType::InvalidValue`
+    /// for each of them.
+    fn accumulate_samples_signed(&self, samples: Vec<i64>) {
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || {
+            crate::with_glean(|glean| metric.accumulate_samples_signed(glean, samples))
+        });
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value, the sum and the count of each bucket.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<DistributionData> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value(glean, storage_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently-stored histogram as a JSON String of the serialized value.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value_as_json_string(&self, storage_name: &str) -> Option<String> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value_as_json_string(glean, storage_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            glean_core::test_get_num_recorded_errors(glean, self.0.meta(), error, ping_name)
+                .unwrap_or(0)
+        })
+    }
+}
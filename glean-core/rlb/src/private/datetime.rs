@@ -0,0 +1,142 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#![allow(clippy::too_many_arguments)]
+
+use inherent::inherent;
+use std::sync::Arc;
+
+use glean_core::metrics::{Datetime, MetricType, TimeUnit};
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+
+// We need to wrap the glean-core type: otherwise if we try to implement
+// the trait for the metric in `glean_core::metrics` we hit error[E0117]:
+// only traits defined in the current crate can be implemented for arbitrary
+// types.
+
+/// This implements the developer facing API for recording datetime metrics.
+///
+/// Instances of this class type are automatically generated by the parsers
+/// at build time, allowing developers to record values that were previously
+/// registered in the metrics.yaml file.
+#[derive(Clone)]
+pub struct DatetimeMetric(pub(crate) Arc<glean_core::metrics::DatetimeMetric>);
+
+impl DatetimeMetric {
+    /// The public constructor used by automatically generated metrics.
+    pub fn new(meta: glean_core::CommonMetricData, time_unit: TimeUnit) -> Self {
+        Self(Arc::new(glean_core::metrics::DatetimeMetric::new(
+            meta, time_unit,
+        )))
+    }
+}
+
+#[inherent(pub)]
+impl glean_core::traits::Datetime for DatetimeMetric {
+    /// Sets the metric to a date/time including the timezone offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - the year to set the metric to.
+    /// * `month` - the month to set the metric to (1-12).
+    /// * `day` - the day to set the metric to (1-based).
+    /// * `hour` - the hour to set the metric to.
+    /// * `minute` - the minute to set the metric to.
+    /// * `second` - the second to set the metric to.
+    /// * `nano` - the nanosecond fraction to the last whole second.
+    /// * `offset_seconds` - the timezone difference, in seconds, for the Eastern
+    ///   Hemisphere. Negative seconds mean Western Hemisphere.
+    fn set_with_details(
+        &self,
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        nano: u32,
+        offset_seconds: i32,
+    ) {
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || {
+            crate::with_glean(|glean| {
+                metric.set_with_details(
+                    glean,
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minute,
+                    second,
+                    nano,
+                    offset_seconds,
+                )
+            })
+        });
+    }
+
+    /// Sets the metric to a date/time which including the timezone offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Some date/time value, with offset, to set the metric to.
+    ///             If none, the current local time is used.
+    fn set(&self, value: Option<Datetime>) {
+        // The current time is taken now, not when the task is run by the dispatcher.
+        let value = value.unwrap_or_else(|| crate::clock().now_with_offset());
+
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || crate::with_glean(|glean| metric.set(glean, Some(value))));
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value as a String.
+    ///
+    /// The precision of this value is truncated to the `time_unit` precision.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value_as_string(&self, storage_name: &str) -> Option<String> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value_as_string(glean, storage_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value, with its timezone offset.
+    ///
+    /// The precision of this value is truncated to the `time_unit` precision.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<Datetime> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value(glean, storage_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            glean_core::test_get_num_recorded_errors(glean, self.0.meta(), error, ping_name)
+                .unwrap_or(0)
+        })
+    }
+}
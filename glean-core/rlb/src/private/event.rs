@@ -0,0 +1,122 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use inherent::inherent;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use glean_core::metrics::{MetricType, RecordedEvent};
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+
+// We need to wrap the glean-core type: otherwise if we try to implement
+// the trait for the metric in `glean_core::metrics` we hit error[E0117]:
+// only traits defined in the current crate can be implemented for arbitrary
+// types.
+
+/// This implements the developer facing API for recording events.
+///
+/// Instances of this class type are automatically generated by the parsers
+/// at build time, allowing developers to record events that were previously
+/// registered in the metrics.yaml file.
+#[derive(Clone)]
+pub struct EventMetric(pub(crate) Arc<glean_core::metrics::EventMetric>);
+
+impl EventMetric {
+    /// The public constructor used by automatically generated metrics.
+    pub fn new(meta: glean_core::CommonMetricData, allowed_extra_keys: Vec<String>) -> Self {
+        Self(Arc::new(glean_core::metrics::EventMetric::new(
+            meta,
+            allowed_extra_keys,
+        )))
+    }
+}
+
+#[inherent(pub)]
+impl glean_core::traits::Event for EventMetric {
+    /// Records an event.
+    ///
+    /// The event is timestamped when this is called,
+    /// even if Glean is not initialized yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `extra` - A HashMap of (key, value) pairs. Each key must be one of the
+    ///   metric's `allowed_extra_keys`. If any key is not allowed, an
+    ///   `ErrorType::InvalidValue` error is recorded and no event is recorded.
+    ///
+    /// ## Notes
+    ///
+    /// Values longer than 100 bytes are truncated
+    /// and an `ErrorType::InvalidOverflow` error is recorded.
+    /// Once the number of stored events reaches the configured `max_events`,
+    /// the pings containing them are submitted with the reason `max_capacity`.
+    fn record(&self, extra: Option<HashMap<String, String>>) {
+        let timestamp = crate::precise_time_ns() / 1_000_000;
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || {
+            let submitted =
+                crate::with_glean(|glean| metric.record_with_string_keys(glean, timestamp, extra));
+            if submitted {
+                crate::trigger_upload(&crate::global_state().lock().unwrap());
+            }
+        });
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Tests whether there are currently stored events for this event metric.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_has_value(&self, store_name: &str) -> bool {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_has_value(glean, store_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Get the vector of currently stored events for this event metric.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, store_name: &str) -> Option<Vec<RecordedEvent>> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value(glean, store_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored events for this event metric as a JSON-encoded string.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value_as_json_string(&self, store_name: &str) -> String {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value_as_json_string(glean, store_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            glean_core::test_get_num_recorded_errors(glean, self.0.meta(), error, ping_name)
+                .unwrap_or(0)
+        })
+    }
+}
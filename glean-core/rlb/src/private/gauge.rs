@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use inherent::inherent;
+use std::sync::Arc;
+
+use glean_core::metrics::MetricType;
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+
+// We need to wrap the glean-core type: otherwise if we try to implement
+// the trait for the metric in `glean_core::metrics` we hit error[E0117]:
+// only traits defined in the current crate can be implemented for arbitrary
+// types.
+
+/// This implements the developer facing API for recording gauge metrics.
+///
+/// Instances of this class type are automatically generated by the parsers
+/// at build time, allowing developers to record values that were previously
+/// registered in the metrics.yaml file.
+#[derive(Clone)]
+pub struct GaugeMetric(pub(crate) Arc<glean_core::metrics::GaugeMetric>);
+
+impl GaugeMetric {
+    /// The public constructor used by automatically generated metrics.
+    pub fn new(meta: glean_core::CommonMetricData) -> Self {
+        Self(Arc::new(glean_core::metrics::GaugeMetric::new(meta)))
+    }
+}
+
+#[inherent(pub)]
+impl glean_core::traits::Gauge for GaugeMetric {
+    /// Sets the gauge to `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to set the gauge to. Can be negative.
+    fn set(&self, value: i64) {
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || crate::with_glean(|glean| metric.set(glean, value)));
+    }
+
+    /// Changes the gauge by `delta`.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The amount to change the gauge by. Negative values decrease it.
+    ///
+    /// ## Notes
+    ///
+    /// If the gauge would exceed `i64::MIN` or `i64::MAX`, it saturates at that value
+    /// and an error is logged.
+    fn add(&self, delta: i64) {
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || crate::with_glean(|glean| metric.add(glean, delta)));
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value as an integer.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<i64> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value(glean, storage_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            glean_core::test_get_num_recorded_errors(glean, self.0.meta(), error, ping_name)
+                .unwrap_or(0)
+        })
+    }
+}
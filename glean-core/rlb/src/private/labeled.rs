@@ -0,0 +1,197 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use inherent::inherent;
+use std::sync::Arc;
+
+use glean_core::metrics::{MemoryUnit, MetricType};
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+use crate::private::MemoryDistributionMetric;
+
+/// Sealed traits protect against downstream implementations.
+///
+/// We wrap it in a private module that is inaccessible outside of this module.
+mod private {
+    use std::sync::Arc;
+
+    use crate::private::{BooleanMetric, CounterMetric, MemoryDistributionMetric};
+
+    /// The sealed labeled trait.
+    ///
+    /// This also allows us to hide methods, that are only used internally
+    /// and should not be visible to users of the object implementing the
+    /// `Labeled<T>` trait.
+    pub trait Sealed {
+        /// The `glean_core` metric type representing the labeled metric.
+        type Inner: glean_core::metrics::MetricType + Clone;
+
+        /// Creates a new `glean_core` metric with the given metadata.
+        fn new_inner(meta: glean_core::CommonMetricData) -> Self::Inner;
+
+        /// Creates a new object wrapping the given `glean_core` metric.
+        fn from_inner(metric: Self::Inner) -> Self;
+    }
+
+    impl Sealed for CounterMetric {
+        type Inner = glean_core::metrics::CounterMetric;
+
+        fn new_inner(meta: glean_core::CommonMetricData) -> Self::Inner {
+            glean_core::metrics::CounterMetric::new(meta)
+        }
+
+        fn from_inner(metric: Self::Inner) -> Self {
+            CounterMetric(Arc::new(metric))
+        }
+    }
+
+    impl Sealed for BooleanMetric {
+        type Inner = glean_core::metrics::BooleanMetric;
+
+        fn new_inner(meta: glean_core::CommonMetricData) -> Self::Inner {
+            glean_core::metrics::BooleanMetric::new(meta)
+        }
+
+        fn from_inner(metric: Self::Inner) -> Self {
+            BooleanMetric(Arc::new(metric))
+        }
+    }
+
+    impl Sealed for MemoryDistributionMetric {
+        type Inner = glean_core::metrics::MemoryDistributionMetric;
+
+        /// Uses bytes, the default memory unit.
+        /// See [`LabeledMetric::with_memory_unit`](../struct.LabeledMetric.html#method.with_memory_unit)
+        /// for other units.
+        fn new_inner(meta: glean_core::CommonMetricData) -> Self::Inner {
+            glean_core::metrics::MemoryDistributionMetric::new(
+                meta,
+                glean_core::metrics::MemoryUnit::Byte,
+            )
+        }
+
+        fn from_inner(metric: Self::Inner) -> Self {
+            MemoryDistributionMetric(Arc::new(metric))
+        }
+    }
+}
+
+/// Marker trait for metrics that can be nested inside a labeled metric.
+///
+/// This trait is sealed and cannot be implemented for types outside this crate.
+pub trait AllowLabeled: private::Sealed {}
+
+// Implement the trait for everything we marked as allowed.
+impl<T> AllowLabeled for T where T: private::Sealed {}
+
+/// This implements the specific facing API for recording labeled metrics.
+///
+/// Instances of this type are automatically generated by the parser
+/// at build time, allowing developers to record values that were previously
+/// registered in the metrics.yaml file.
+/// Unlike most metric types, `LabeledMetric` does not have its own corresponding
+/// storage, but records metrics for the underlying metric type `T` in the storage
+/// for that type.
+#[derive(Clone)]
+pub struct LabeledMetric<T: AllowLabeled> {
+    core: Arc<glean_core::metrics::LabeledMetric<T::Inner>>,
+}
+
+impl<T> LabeledMetric<T>
+where
+    T: AllowLabeled,
+{
+    /// The public constructor used by automatically generated metrics.
+    ///
+    /// # Arguments
+    ///
+    /// * `meta` - The metadata of the underlying metric.
+    /// * `labels` - The static labels, if any. Labels are dynamic if `None`.
+    pub fn new(meta: glean_core::CommonMetricData, labels: Option<Vec<String>>) -> Self {
+        let submetric = T::new_inner(meta);
+        let core = glean_core::metrics::LabeledMetric::new(submetric, labels);
+        Self {
+            core: Arc::new(core),
+        }
+    }
+
+    /// The metadata of the underlying metric, without a label.
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn submetric_meta(&self) -> &glean_core::CommonMetricData {
+        self.core.get_submetric().meta()
+    }
+}
+
+impl LabeledMetric<MemoryDistributionMetric> {
+    /// The public constructor used by automatically generated labeled memory distribution metrics.
+    ///
+    /// [`new`](#method.new) records the samples in bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `meta` - The metadata of the underlying metric.
+    /// * `memory_unit` - The unit the samples are recorded in.
+    /// * `labels` - The static labels, if any. Labels are dynamic if `None`.
+    pub fn with_memory_unit(
+        meta: glean_core::CommonMetricData,
+        memory_unit: MemoryUnit,
+        labels: Option<Vec<String>>,
+    ) -> Self {
+        let submetric = glean_core::metrics::MemoryDistributionMetric::new(meta, memory_unit);
+        let core = glean_core::metrics::LabeledMetric::new(submetric, labels);
+        Self {
+            core: Arc::new(core),
+        }
+    }
+}
+
+#[inherent(pub)]
+impl<T> glean_core::traits::Labeled<T> for LabeledMetric<T>
+where
+    T: AllowLabeled + Clone,
+{
+    /// Gets a specific metric for a given label.
+    ///
+    /// If a set of acceptable labels were specified in the `metrics.yaml` file,
+    /// and the given label is not in the set, it will be recorded under the special `OTHER_LABEL` label.
+    ///
+    /// If a set of acceptable labels was not specified in the `metrics.yaml` file,
+    /// only the first 16 unique labels will be used.
+    /// After that, any additional labels will be recorded under the special `OTHER_LABEL` label.
+    ///
+    /// Labels must be `snake_case` and less than 30 characters.
+    /// If an invalid label is used, the metric will be recorded in the special `OTHER_LABEL` label
+    /// and an `ErrorType::InvalidLabel` error is recorded.
+    fn get(&self, label: &str) -> T {
+        T::from_inner(self.core.get(label))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            glean_core::test_get_num_recorded_errors(
+                glean,
+                self.core.get_submetric().meta(),
+                error,
+                ping_name,
+            )
+            .unwrap_or(0)
+        })
+    }
+}
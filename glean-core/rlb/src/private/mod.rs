@@ -5,7 +5,39 @@
 //! The different metric types supported by the Glean SDK to handle data.
 
 mod boolean;
-mod ping;
+mod counter;
+mod custom_distribution;
+mod datetime;
+mod event;
+mod gauge;
+mod labeled;
+mod memory_distribution;
+mod object;
+pub(crate) mod ping;
+mod quantity;
+mod rate;
+mod string_list;
+mod text;
+mod timespan;
+mod timing_distribution;
+mod url;
+mod uuid;
 
+pub use self::uuid::UuidMetric;
 pub use boolean::BooleanMetric;
+pub use counter::CounterMetric;
+pub use custom_distribution::CustomDistributionMetric;
+pub use datetime::DatetimeMetric;
+pub use event::EventMetric;
+pub use gauge::GaugeMetric;
+pub use labeled::{AllowLabeled, LabeledMetric};
+pub use memory_distribution::MemoryDistributionMetric;
+pub use object::ObjectMetric;
 pub use ping::PingType;
+pub use quantity::QuantityMetric;
+pub use rate::RateMetric;
+pub use string_list::StringListMetric;
+pub use text::TextMetric;
+pub use timespan::TimespanMetric;
+pub use timing_distribution::TimingDistributionMetric;
+pub use url::UrlMetric;
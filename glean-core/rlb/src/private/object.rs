@@ -0,0 +1,137 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use inherent::inherent;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use glean_core::metrics::MetricType;
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+
+// We need to wrap the glean-core type: otherwise if we try to implement
+// the trait for the metric in `glean_core::metrics` we hit error[E0117]:
+// only traits defined in the current crate can be implemented for arbitrary
+// types.
+
+/// This implements the developer facing API for recording object metrics.
+///
+/// Instances of this class type are automatically generated by the parsers
+/// at build time, allowing developers to record values that were previously
+/// registered in the metrics.yaml file.
+///
+/// The recorded values are of type `T`. Its JSON schema is derived with `schemars`,
+/// and every value is validated against it before it is recorded.
+pub struct ObjectMetric<T> {
+    inner: Arc<glean_core::metrics::ObjectMetric>,
+    schema: Arc<JsonValue>,
+    value_type: PhantomData<fn(T)>,
+}
+
+// Derived `Clone` would require `T: Clone`.
+impl<T> Clone for ObjectMetric<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            schema: Arc::clone(&self.schema),
+            value_type: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize + JsonSchema> ObjectMetric<T> {
+    /// The public constructor used by automatically generated metrics.
+    pub fn new(meta: glean_core::CommonMetricData) -> Self {
+        let schema = serde_json::to_value(schemars::schema_for!(T))
+            .expect("IMPOSSIBLE: Serializing a JSON schema failed");
+        Self {
+            inner: Arc::new(glean_core::metrics::ObjectMetric::new(meta)),
+            schema: Arc::new(schema),
+            value_type: PhantomData,
+        }
+    }
+}
+
+/// Validates a value against a JSON schema.
+///
+/// # Returns
+///
+/// The validation errors, separated by newlines, if the value doesn't match the schema.
+fn validate(schema: &JsonValue, value: &JsonValue) -> Result<(), String> {
+    let cfg = jsonschema_valid::Config::from_schema(schema, None).map_err(|e| e.to_string())?;
+    cfg.validate(value)
+        .map_err(|errors| errors.map(|e| e.msg).collect::<Vec<_>>().join("\n"))
+}
+
+#[inherent(pub)]
+impl<T: Serialize + JsonSchema> glean_core::traits::Object<T> for ObjectMetric<T> {
+    /// Sets to the specified structured value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// Records an `ErrorType::InvalidValue` error if the value can't be serialized,
+    /// doesn't match the JSON schema of `T`, or is not serialized as an object or an array.
+    fn set(&self, value: T) {
+        let metric = Arc::clone(&self.inner);
+        let schema = Arc::clone(&self.schema);
+        // `T` might not be `Send`, so it is serialized before the task is dispatched.
+        let value = serde_json::to_value(&value);
+        dispatcher::launch(move || {
+            crate::with_glean(|glean| match value {
+                Ok(value) => match validate(&schema, &value) {
+                    Ok(()) => metric.set(glean, value),
+                    Err(e) => metric.record_schema_error(
+                        glean,
+                        format!("Value doesn't match the schema: {}", e),
+                    ),
+                },
+                Err(e) => {
+                    metric.record_schema_error(glean, format!("Failed to serialize value: {}", e))
+                }
+            })
+        });
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value as JSON.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<JsonValue> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.inner.test_get_value(glean, storage_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            glean_core::test_get_num_recorded_errors(glean, self.inner.meta(), error, ping_name)
+                .unwrap_or(0)
+        })
+    }
+}
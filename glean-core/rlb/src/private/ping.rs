@@ -2,6 +2,18 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+/// A callback run right before a ping is collected.
+type BeforeSubmitCallback = Box<dyn FnOnce(Option<&str>) + Send + 'static>;
+
+/// Callbacks to run before the next submission of a ping, by ping name.
+static TEST_CALLBACKS: Lazy<Mutex<HashMap<String, BeforeSubmitCallback>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// Stores information about a ping.
 ///
 /// This is required so that given metric data queued on disk we can send
@@ -38,7 +50,60 @@ impl PingType {
     }
 
     /// Submits the ping.
-    pub fn submit(&self, reason: Option<&str>) {
+    ///
+    /// # Returns
+    ///
+    /// A [`PingSubmission`](../struct.PingSubmission.html) handle to observe
+    /// whether the ping was actually queued.
+    pub fn submit(&self, reason: Option<&str>) -> crate::PingSubmission {
         crate::submit_ping(self, reason)
     }
+
+    /// Submits the ping with the given priority.
+    ///
+    /// See [`submit_ping_by_name_with_priority`](../fn.submit_ping_by_name_with_priority.html).
+    pub fn submit_with_priority(
+        &self,
+        reason: Option<&str>,
+        priority: crate::dispatcher::Priority,
+    ) -> crate::PingSubmission {
+        crate::submit_ping_by_name_with_priority(&self.name, reason, priority)
+    }
+
+    /// **Test-only API**
+    ///
+    /// Registers a callback to run right before the next submission of this ping.
+    ///
+    /// The callback is called exactly once, on the dispatcher thread,
+    /// before the ping payload is assembled.
+    /// This allows to check metric values before they are cleared by the submission.
+    ///
+    /// Registering a new callback replaces any callback that did not run yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `cb` - The callback. It receives the reason the ping is submitted with.
+    pub fn test_before_next_submit(&self, cb: impl FnOnce(Option<&str>) + Send + 'static) {
+        TEST_CALLBACKS
+            .lock()
+            .unwrap()
+            .insert(self.name.clone(), Box::new(cb));
+    }
+}
+
+/// Runs the callback registered for the given ping, if any.
+///
+/// Must be called on the dispatcher thread, right before the ping is collected.
+pub(crate) fn run_test_callback(ping_name: &str, reason: Option<&str>) {
+    // Release the lock before running the callback,
+    // so the callback can register a new one.
+    let cb = TEST_CALLBACKS.lock().unwrap().remove(ping_name);
+    if let Some(cb) = cb {
+        cb(reason);
+    }
+}
+
+/// Removes all registered callbacks without running them.
+pub(crate) fn clear_test_callbacks() {
+    TEST_CALLBACKS.lock().unwrap().clear();
 }
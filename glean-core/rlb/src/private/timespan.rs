@@ -0,0 +1,128 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use inherent::inherent;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use glean_core::metrics::{MetricType, TimeUnit};
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+
+// We need to wrap the glean-core type: otherwise if we try to implement
+// the trait for the metric in `glean_core::metrics` we hit error[E0117]:
+// only traits defined in the current crate can be implemented for arbitrary
+// types.
+
+/// This implements the developer facing API for recording timespan metrics.
+///
+/// Instances of this class type are automatically generated by the parsers
+/// at build time, allowing developers to record values that were previously
+/// registered in the metrics.yaml file.
+#[derive(Clone)]
+pub struct TimespanMetric(pub(crate) Arc<RwLock<glean_core::metrics::TimespanMetric>>);
+
+impl TimespanMetric {
+    /// The public constructor used by automatically generated metrics.
+    pub fn new(meta: glean_core::CommonMetricData, time_unit: TimeUnit) -> Self {
+        Self(Arc::new(RwLock::new(
+            glean_core::metrics::TimespanMetric::new(meta, time_unit),
+        )))
+    }
+}
+
+#[inherent(pub)]
+impl glean_core::traits::Timespan for TimespanMetric {
+    /// Starts tracking time for the provided metric.
+    ///
+    /// This records an error if it's already tracking time (i.e. start was already
+    /// called with no corresponding `stop`): in that case the original
+    /// start time will be preserved.
+    fn start(&self) {
+        // The time is taken now, not when the task runs.
+        let start_time = crate::precise_time_ns();
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || {
+            crate::with_glean(|glean| metric.write().unwrap().set_start(glean, start_time))
+        });
+    }
+
+    /// Stops tracking time for the provided metric. Sets the metric to the elapsed time.
+    ///
+    /// This will record an error if no `start` was called.
+    fn stop(&self) {
+        let stop_time = crate::precise_time_ns();
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || {
+            crate::with_glean(|glean| metric.write().unwrap().set_stop(glean, stop_time))
+        });
+    }
+
+    /// Aborts a previous `start` call. No error is recorded if no `start` was called.
+    fn cancel(&self) {
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || metric.write().unwrap().cancel());
+    }
+
+    /// Explicitly sets the timespan value.
+    ///
+    /// This API should only be used if your library or application requires recording
+    /// times in a way that can not make use of `start`/`stop`/`cancel`.
+    ///
+    /// Care should be taken using this if the ping lifetime might contain more than one
+    /// timespan measurement. To be safe, `set_raw` should generally be followed by
+    /// sending a custom ping containing the timespan.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - The elapsed time to record.
+    /// * `overwrite` - Whether or not to overwrite existing data.
+    fn set_raw(&self, elapsed: Duration, overwrite: bool) {
+        let metric = Arc::clone(&self.0);
+        dispatcher::launch(move || {
+            crate::with_glean(|glean| metric.read().unwrap().set_raw(glean, elapsed, overwrite))
+        });
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value as an integer, in the metric's time unit.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<i64> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            self.0
+                .read()
+                .unwrap()
+                .test_get_value(glean, storage_name)
+                .map(|value| value as i64)
+        })
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            let metric = self.0.read().unwrap();
+            glean_core::test_get_num_recorded_errors(glean, metric.meta(), error, ping_name)
+                .unwrap_or(0)
+        })
+    }
+}
@@ -0,0 +1,93 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use inherent::inherent;
+use std::sync::Arc;
+
+use glean_core::metrics::MetricType;
+use glean_core::ErrorType;
+
+use crate::dispatcher;
+
+// We need to wrap the glean-core type: otherwise if we try to implement
+// the trait for the metric in `glean_core::metrics` we hit error[E0117]:
+// only traits defined in the current crate can be implemented for arbitrary
+// types.
+
+/// This implements the developer facing API for recording URL metrics.
+///
+/// Instances of this class type are automatically generated by the parsers
+/// at build time, allowing developers to record values that were previously
+/// registered in the metrics.yaml file.
+#[derive(Clone)]
+pub struct UrlMetric(pub(crate) Arc<glean_core::metrics::UrlMetric>);
+
+impl UrlMetric {
+    /// The public constructor used by automatically generated metrics.
+    pub fn new(meta: glean_core::CommonMetricData) -> Self {
+        Self(Arc::new(glean_core::metrics::UrlMetric::new(meta)))
+    }
+
+    /// The public constructor used by automatically generated metrics
+    /// recording only the scheme, host and path of URLs.
+    ///
+    /// The query string and fragment are stripped before the URL is recorded.
+    pub fn with_redacted_query(meta: glean_core::CommonMetricData) -> Self {
+        Self(Arc::new(
+            glean_core::metrics::UrlMetric::with_redacted_query(meta),
+        ))
+    }
+}
+
+#[inherent(pub)]
+impl glean_core::traits::Url for UrlMetric {
+    /// Sets to the specified URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The URL to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// Only `http` and `https` URLs of at most `MAX_URL_LENGTH` bytes are recorded.
+    /// Other values are dropped and an error is logged.
+    fn set<S: Into<String>>(&self, value: S) {
+        let metric = Arc::clone(&self.0);
+        let value = value.into();
+        dispatcher::launch(move || crate::with_glean(|glean| metric.set(glean, value)));
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value as a string.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<String> {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| self.0.test_get_value(glean, storage_name))
+    }
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32 {
+        dispatcher::block_on_queue();
+
+        crate::with_glean(|glean| {
+            glean_core::test_get_num_recorded_errors(glean, self.0.meta(), error, ping_name)
+                .unwrap_or(0)
+        })
+    }
+}
@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Reading the stored values of metrics at runtime.
+//!
+//! Only available with the `expose-runtime-values` feature.
+//!
+//! Values are read from the first ping the metric is sent in.
+//! This reads what is currently in storage: values recorded in tasks
+//! that were not processed yet are not included.
+//! All functions return `None` if Glean is not initialized.
+
+use glean_core::metrics::{DistributionData, MetricType};
+use glean_core::CommonMetricData;
+
+use crate::private::{
+    BooleanMetric, CounterMetric, MemoryDistributionMetric, TimespanMetric,
+    TimingDistributionMetric,
+};
+
+/// Reads a value with the global Glean object, from the first ping the metric is sent in.
+fn query<F, R>(meta: &CommonMetricData, f: F) -> Option<R>
+where
+    F: FnOnce(&glean_core::Glean, &str) -> Option<R>,
+{
+    let glean = crate::global_glean()?;
+    let storage_name = meta.send_in_pings.first()?;
+    let glean = glean.lock().unwrap();
+    f(&glean, storage_name)
+}
+
+/// Gets the currently stored value of a boolean metric.
+pub fn query_boolean(metric: &BooleanMetric) -> Option<bool> {
+    query(metric.0.meta(), |glean, storage_name| {
+        metric.0.test_get_value(glean, storage_name)
+    })
+}
+
+/// Gets the currently stored value of a counter metric.
+pub fn query_counter(metric: &CounterMetric) -> Option<i32> {
+    query(metric.0.meta(), |glean, storage_name| {
+        metric.0.test_get_value(glean, storage_name)
+    })
+}
+
+/// Gets the currently stored value of a timespan metric, in the metric's time unit.
+pub fn query_timespan(metric: &TimespanMetric) -> Option<i64> {
+    let metric = metric.0.read().unwrap();
+    query(metric.meta(), |glean, storage_name| {
+        metric
+            .test_get_value(glean, storage_name)
+            .map(|value| value as i64)
+    })
+}
+
+/// Gets the currently stored value of a timing distribution metric.
+pub fn query_timing_distribution(metric: &TimingDistributionMetric) -> Option<DistributionData> {
+    let metric = metric.0.read().unwrap();
+    query(metric.meta(), |glean, storage_name| {
+        metric.test_get_value(glean, storage_name)
+    })
+}
+
+/// Gets the currently stored value of a memory distribution metric.
+pub fn query_memory_distribution(metric: &MemoryDistributionMetric) -> Option<DistributionData> {
+    query(metric.0.meta(), |glean, storage_name| {
+        metric.0.test_get_value(glean, storage_name)
+    })
+}
@@ -0,0 +1,361 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Scheduling of the `metrics` ping.
+//!
+//! The `metrics` ping is submitted once per calendar day, at 04:00 local time.
+//! If the application isn't running then, it is submitted as `overdue` on the next startup.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset};
+use crossbeam_channel::{bounded, RecvTimeoutError, Sender};
+use glean_core::{Clock, Glean};
+
+/// The name of the thread submitting the `metrics` ping.
+const METRICS_PING_SCHEDULER_THREAD_NAME: &str = "glean.metrics_ping_scheduler";
+
+/// The local hour the `metrics` ping is submitted at.
+const SCHEDULED_HOUR: u32 = 4;
+
+/// How often the scheduler checks the clock while waiting for the next ping.
+///
+/// Waiting doesn't notice when the clock jumps, e.g. after the system was suspended
+/// or its time was changed.
+const CLOCK_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// When the next `metrics` ping is due, and the reason it is submitted with.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Schedule {
+    due: DateTime<FixedOffset>,
+    reason: &'static str,
+}
+
+impl Schedule {
+    /// Schedules the ping for the scheduled hour of the calendar day after `now`.
+    fn tomorrow(now: DateTime<FixedOffset>, reason: &'static str) -> Self {
+        Self {
+            due: now.date().succ().and_hms(SCHEDULED_HOUR, 0, 0),
+            reason,
+        }
+    }
+}
+
+/// What to do about the `metrics` ping at startup.
+#[derive(Debug, PartialEq)]
+enum StartupAction {
+    /// The ping was never sent: record the current time as if it was, without submitting it.
+    RecordFirstRun,
+    /// The ping wasn't sent today and the scheduled hour has passed: submit it right away.
+    SubmitOverdue,
+    /// Wait for the next scheduled submission.
+    Wait,
+}
+
+/// Decides what to do about the `metrics` ping at startup.
+///
+/// # Arguments
+///
+/// * `last_sent` - When the ping was last sent, if ever.
+/// * `now` - The current local time.
+///
+/// # Returns
+///
+/// The action to take now, and when to submit the next ping after it.
+fn startup_action(
+    last_sent: Option<DateTime<FixedOffset>>,
+    now: DateTime<FixedOffset>,
+) -> (StartupAction, Schedule) {
+    let last_sent = match last_sent {
+        Some(last_sent) => last_sent.with_timezone(now.offset()),
+        None => {
+            return (
+                StartupAction::RecordFirstRun,
+                Schedule::tomorrow(now, "tomorrow"),
+            )
+        }
+    };
+
+    let scheduled_today = now.date().and_hms(SCHEDULED_HOUR, 0, 0);
+    if last_sent.naive_local().date() == now.naive_local().date() {
+        (StartupAction::Wait, Schedule::tomorrow(now, "tomorrow"))
+    } else if now >= scheduled_today {
+        (
+            StartupAction::SubmitOverdue,
+            Schedule::tomorrow(now, "reschedule"),
+        )
+    } else {
+        (
+            StartupAction::Wait,
+            Schedule {
+                due: scheduled_today,
+                reason: "today",
+            },
+        )
+    }
+}
+
+/// Submits the `metrics` ping and records when it was sent.
+///
+/// # Returns
+///
+/// Whether the ping was submitted.
+fn submit(glean: &Glean, reason: &str, now: DateTime<FixedOffset>) -> bool {
+    log::info!("Submitting the metrics ping with reason '{}'", reason);
+    glean.set_metrics_ping_last_sent(now);
+    crate::submit_ping_by_name_sync(glean, "metrics", Some(reason)).unwrap_or(false)
+}
+
+/// Checks the `metrics` ping at startup.
+///
+/// The ping is submitted right away if it is overdue.
+/// On the first run, the current time is recorded instead, without submitting the ping.
+///
+/// # Returns
+///
+/// When to submit the next ping, and whether the ping was submitted.
+pub(crate) fn check_on_startup(glean: &Glean) -> (Schedule, bool) {
+    let now = crate::clock().now_with_offset();
+    let (action, schedule) = startup_action(glean.metrics_ping_last_sent(), now);
+    let submitted = match action {
+        StartupAction::RecordFirstRun => {
+            glean.set_metrics_ping_last_sent(now);
+            false
+        }
+        StartupAction::SubmitOverdue => submit(glean, "overdue", now),
+        StartupAction::Wait => false,
+    };
+    (schedule, submitted)
+}
+
+/// Submits the `metrics` ping once per calendar day, at the scheduled hour.
+///
+/// Dropping the scheduler stops it.
+#[derive(Debug)]
+pub(crate) struct MetricsPingScheduler {
+    /// Dropping the sender disconnects the scheduler thread, which then exits.
+    _stop: Sender<()>,
+}
+
+impl MetricsPingScheduler {
+    /// Starts submitting the `metrics` ping, the first time as given by `schedule`.
+    ///
+    /// # Returns
+    ///
+    /// The running scheduler, or `None` if the scheduler thread could not be spawned.
+    pub(crate) fn start(schedule: Schedule) -> Option<Self> {
+        Self::start_with(schedule, crate::clock(), CLOCK_CHECK_INTERVAL, |reason| {
+            crate::dispatcher::launch(move || {
+                let now = crate::clock().now_with_offset();
+                let submitted = crate::with_glean(|glean| submit(glean, reason, now));
+                if submitted {
+                    crate::trigger_upload(&crate::global_state().lock().unwrap());
+                }
+            });
+        })
+    }
+
+    /// Starts calling `submit` with the reason of each scheduled `metrics` ping when it is due.
+    ///
+    /// The time is taken from `clock`, which is checked at least every `check_interval`.
+    /// If the clock jumped past the due time, e.g. after the system was suspended
+    /// or its time was changed, the ping is submitted right away.
+    fn start_with<S>(
+        schedule: Schedule,
+        clock: Arc<dyn Clock>,
+        check_interval: Duration,
+        submit: S,
+    ) -> Option<Self>
+    where
+        S: Fn(&'static str) + Send + 'static,
+    {
+        let (stop, stopped) = bounded::<()>(0);
+
+        let spawned = thread::Builder::new()
+            .name(METRICS_PING_SCHEDULER_THREAD_NAME.to_string())
+            .spawn(move || {
+                let mut schedule = schedule;
+                log::debug!(
+                    "Scheduling the metrics ping for {} with reason '{}'",
+                    schedule.due,
+                    schedule.reason
+                );
+                loop {
+                    let now = clock.now_with_offset();
+                    if now >= schedule.due {
+                        submit(schedule.reason);
+                        schedule = Schedule::tomorrow(clock.now_with_offset(), "reschedule");
+                        log::debug!(
+                            "Scheduling the metrics ping for {} with reason '{}'",
+                            schedule.due,
+                            schedule.reason
+                        );
+                        continue;
+                    }
+
+                    let wait = (schedule.due - now).to_std().unwrap_or_default();
+                    match stopped.recv_timeout(wait.min(check_interval)) {
+                        Err(RecvTimeoutError::Timeout) => {}
+                        // The scheduler was dropped.
+                        _ => return,
+                    }
+                }
+            });
+
+        match spawned {
+            Ok(_) => Some(Self { _stop: stop }),
+            Err(e) => {
+                log::error!("Failed to spawn the metrics ping scheduler: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+    use crossbeam_channel::unbounded;
+    use glean_core::MockClock;
+
+    fn at(day: u32, hour: u32, min: u32) -> DateTime<FixedOffset> {
+        FixedOffset::east(3600)
+            .ymd(2020, 11, day)
+            .and_hms(hour, min, 0)
+    }
+
+    #[test]
+    fn first_run_records_without_submitting() {
+        let (action, schedule) = startup_action(None, at(4, 10, 0));
+        assert_eq!(StartupAction::RecordFirstRun, action);
+        assert_eq!(at(5, 4, 0), schedule.due);
+        assert_eq!("tomorrow", schedule.reason);
+    }
+
+    #[test]
+    fn ping_sent_today_is_scheduled_for_tomorrow() {
+        let (action, schedule) = startup_action(Some(at(4, 4, 0)), at(4, 23, 59));
+        assert_eq!(StartupAction::Wait, action);
+        assert_eq!(at(5, 4, 0), schedule.due);
+        assert_eq!("tomorrow", schedule.reason);
+    }
+
+    #[test]
+    fn ping_not_sent_today_is_overdue_after_the_scheduled_hour() {
+        let (action, schedule) = startup_action(Some(at(3, 4, 0)), at(4, 4, 0));
+        assert_eq!(StartupAction::SubmitOverdue, action);
+        assert_eq!(at(5, 4, 0), schedule.due);
+        assert_eq!("reschedule", schedule.reason);
+    }
+
+    #[test]
+    fn ping_not_sent_today_is_scheduled_for_today_before_the_scheduled_hour() {
+        let (action, schedule) = startup_action(Some(at(2, 4, 0)), at(4, 3, 59));
+        assert_eq!(StartupAction::Wait, action);
+        assert_eq!(at(4, 4, 0), schedule.due);
+        assert_eq!("today", schedule.reason);
+    }
+
+    fn clock_at(now: DateTime<FixedOffset>) -> Arc<MockClock> {
+        Arc::new(MockClock::at_millis(now.timestamp_millis()))
+    }
+
+    #[test]
+    fn pings_are_submitted_when_the_clock_passes_the_due_time() {
+        let (submitted, submissions) = unbounded();
+        let schedule = Schedule {
+            due: at(4, 4, 0),
+            reason: "today",
+        };
+        let clock = clock_at(at(4, 3, 0));
+        let check_interval = Duration::from_millis(10);
+        let _scheduler = MetricsPingScheduler::start_with(
+            schedule,
+            clock.clone(),
+            check_interval,
+            move |reason| {
+                submitted.send(reason).unwrap();
+            },
+        )
+        .unwrap();
+
+        let timeout = Duration::from_secs(5);
+        clock.advance(Duration::from_secs(60 * 60));
+        assert_eq!(Ok("today"), submissions.recv_timeout(timeout));
+
+        // The clock jumps past the next due time, e.g. after the system was suspended.
+        clock.advance(Duration::from_secs(2 * 24 * 60 * 60));
+        assert_eq!(Ok("reschedule"), submissions.recv_timeout(timeout));
+        assert!(submissions
+            .recv_timeout(Duration::from_millis(100))
+            .is_err());
+    }
+
+    #[test]
+    fn overdue_pings_are_submitted_right_away() {
+        let (submitted, submissions) = unbounded();
+        let schedule = Schedule {
+            due: at(4, 4, 0),
+            reason: "today",
+        };
+        // Without checking the clock in between, only the due time is waited for.
+        let check_interval = Duration::from_secs(60 * 60);
+        let _scheduler = MetricsPingScheduler::start_with(
+            schedule,
+            clock_at(at(4, 5, 0)),
+            check_interval,
+            move |reason| {
+                submitted.send(reason).unwrap();
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            Ok("today"),
+            submissions.recv_timeout(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn pings_are_not_submitted_before_they_are_due() {
+        let (submitted, submissions) = unbounded();
+        let schedule = Schedule {
+            due: at(4, 4, 0),
+            reason: "today",
+        };
+        let check_interval = Duration::from_millis(10);
+        let scheduler = MetricsPingScheduler::start_with(
+            schedule,
+            clock_at(at(4, 3, 0)),
+            check_interval,
+            move |reason| {
+                submitted.send(reason).unwrap();
+            },
+        )
+        .unwrap();
+
+        assert!(submissions
+            .recv_timeout(Duration::from_millis(100))
+            .is_err());
+
+        // Stopping the scheduler ends the thread, dropping the sender.
+        drop(scheduler);
+        assert_eq!(
+            Err(RecvTimeoutError::Disconnected),
+            submissions.recv_timeout(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn last_sent_is_compared_in_local_time() {
+        // 2020-11-03 23:30 UTC is already 2020-11-04 in UTC+1.
+        let last_sent = FixedOffset::east(0).ymd(2020, 11, 3).and_hms(23, 30, 0);
+        let (action, schedule) = startup_action(Some(last_sent), at(4, 10, 0));
+        assert_eq!(StartupAction::Wait, action);
+        assert_eq!(at(5, 4, 0), schedule.due);
+    }
+}
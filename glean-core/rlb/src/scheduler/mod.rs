@@ -0,0 +1,195 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Scheduling of Glean-owned pings.
+
+pub(crate) mod metrics_ping;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, RecvTimeoutError, Sender};
+use glean_core::Clock;
+
+pub(crate) use metrics_ping::MetricsPingScheduler;
+
+/// The name of the thread submitting the `events` ping periodically.
+const EVENTS_PING_SCHEDULER_THREAD_NAME: &str = "glean.events_ping_scheduler";
+
+/// The name of the thread persisting data with ping lifetime periodically.
+const PING_LIFETIME_PERSISTER_THREAD_NAME: &str = "glean.ping_lifetime_persister";
+
+/// How often data with ping lifetime is persisted, if it is held in memory.
+pub(crate) const PING_LIFETIME_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Spawns a thread running `task` every `interval`, until the returned sender is dropped.
+///
+/// The time is taken from the monotonic time of `clock`,
+/// which is checked at least every `check_interval`.
+///
+/// # Returns
+///
+/// The sender stopping the thread, or `None` if the thread could not be spawned.
+fn start_periodic<F>(
+    thread_name: &str,
+    interval: Duration,
+    clock: Arc<dyn Clock>,
+    check_interval: Duration,
+    task: F,
+) -> Option<Sender<()>>
+where
+    F: Fn() + Send + 'static,
+{
+    let (stop, stopped) = bounded::<()>(0);
+
+    let spawned = thread::Builder::new()
+        .name(thread_name.to_string())
+        .spawn(move || {
+            let mut due = clock.monotonic_now() + interval;
+            loop {
+                let now = clock.monotonic_now();
+                if now >= due {
+                    task();
+                    due = now + interval;
+                    continue;
+                }
+
+                match stopped.recv_timeout((due - now).min(check_interval)) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // The scheduler was dropped.
+                    _ => return,
+                }
+            }
+        });
+
+    match spawned {
+        Ok(_) => Some(stop),
+        Err(e) => {
+            log::error!("Failed to spawn the {} thread: {}", thread_name, e);
+            None
+        }
+    }
+}
+
+/// Submits the `events` ping with the reason `periodic` on a fixed interval.
+///
+/// The ping is only sent if it contains events.
+/// Dropping the scheduler stops it.
+#[derive(Debug)]
+pub(crate) struct EventsPingScheduler {
+    /// Dropping the sender disconnects the scheduler thread, which then exits.
+    _stop: Sender<()>,
+}
+
+impl EventsPingScheduler {
+    /// Starts submitting the `events` ping every `interval`.
+    ///
+    /// # Returns
+    ///
+    /// The running scheduler, or `None` if the scheduler thread could not be spawned.
+    ///
+    /// The time is taken from the Glean clock, see `glean::test::set_clock`,
+    /// which is checked every `interval`.
+    pub(crate) fn start(interval: Duration) -> Option<Self> {
+        let stop = start_periodic(
+            EVENTS_PING_SCHEDULER_THREAD_NAME,
+            interval,
+            crate::clock(),
+            interval,
+            || {
+                log::debug!("Submitting the periodic events ping");
+                crate::submit_ping_by_name("events", Some("periodic"));
+            },
+        )?;
+        Some(Self { _stop: stop })
+    }
+}
+
+/// Persists data with ping lifetime held in memory on a fixed interval,
+/// so it isn't lost if the process is killed between pings.
+///
+/// Only needed if `Configuration.delay_ping_lifetime_io` is set.
+/// Dropping the persister stops it.
+#[derive(Debug)]
+pub(crate) struct PingLifetimePersister {
+    /// Dropping the sender disconnects the persister thread, which then exits.
+    _stop: Sender<()>,
+}
+
+impl PingLifetimePersister {
+    /// Starts persisting data with ping lifetime every `interval`.
+    ///
+    /// # Returns
+    ///
+    /// The running persister, or `None` if its thread could not be spawned.
+    pub(crate) fn start(interval: Duration) -> Option<Self> {
+        let stop = start_periodic(
+            PING_LIFETIME_PERSISTER_THREAD_NAME,
+            interval,
+            crate::clock(),
+            interval,
+            || {
+                crate::dispatcher::launch(|| {
+                    crate::with_glean(|glean| {
+                        log::debug!("Persisting data with ping lifetime");
+                        if let Err(e) = glean.persist_ping_lifetime_data() {
+                            log::error!("Failed to persist ping lifetime data: {}", e);
+                        }
+                    })
+                });
+            },
+        )?;
+        Some(Self { _stop: stop })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use glean_core::MockClock;
+
+    #[test]
+    fn periodic_tasks_run_when_the_clock_passes_the_interval() {
+        let (ran, runs) = unbounded();
+        let clock = Arc::new(MockClock::new());
+        let interval = Duration::from_secs(60);
+        let _stop = start_periodic(
+            "glean.test_periodic",
+            interval,
+            clock.clone(),
+            Duration::from_millis(10),
+            move || ran.send(()).unwrap(),
+        )
+        .unwrap();
+
+        let timeout = Duration::from_secs(5);
+        for _ in 0..2 {
+            assert!(runs.recv_timeout(Duration::from_millis(100)).is_err());
+            clock.advance(interval);
+            assert_eq!(Ok(()), runs.recv_timeout(timeout));
+        }
+    }
+
+    #[test]
+    fn periodic_tasks_stop_when_the_sender_is_dropped() {
+        let (ran, runs) = unbounded::<()>();
+        let stop = start_periodic(
+            "glean.test_periodic",
+            Duration::from_secs(60),
+            Arc::new(MockClock::new()),
+            Duration::from_millis(10),
+            move || ran.send(()).unwrap(),
+        )
+        .unwrap();
+
+        // Stopping ends the thread, dropping the task and its sender.
+        drop(stop);
+        assert_eq!(
+            Err(RecvTimeoutError::Disconnected),
+            runs.recv_timeout(Duration::from_secs(5))
+        );
+    }
+}
@@ -27,7 +27,8 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-//! Detect and expose `target_arch` as a constant
+//! Detect and expose `target_arch` as a constant,
+//! and details of the system Glean runs on.
 
 #[cfg(target_arch = "aarch64")]
 /// `target_arch` when building this crate: `aarch64`
@@ -53,3 +54,170 @@ pub const ARCH: &str = "x86_64";
 )))]
 /// `target_arch` when building this crate: unknown!
 pub const ARCH: &str = "unknown";
+
+/// The value reported for system details that could not be detected.
+const UNKNOWN: &str = "unknown";
+
+/// Runs a command and gets its output, without surrounding whitespace.
+///
+/// Returns `None` if the command failed or didn't output anything.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let output = String::from_utf8(output.stdout).ok()?;
+    let output = output.trim();
+    if output.is_empty() {
+        None
+    } else {
+        Some(output.to_string())
+    }
+}
+
+/// Reads a file, without surrounding whitespace.
+///
+/// Returns `None` if the file could not be read or is empty.
+#[cfg(target_os = "linux")]
+fn read_trimmed(path: &str) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let content = content.trim();
+    if content.is_empty() {
+        None
+    } else {
+        Some(content.to_string())
+    }
+}
+
+/// Detects the version of the operating system, e.g. the kernel release on Linux.
+///
+/// Returns `"unknown"` if it can't be detected.
+pub fn os_version() -> String {
+    #[cfg(target_os = "linux")]
+    let version = read_trimmed("/proc/sys/kernel/osrelease");
+
+    #[cfg(target_os = "macos")]
+    let version = command_output("sw_vers", &["-productVersion"]);
+
+    // `ver` prints e.g. "Microsoft Windows [Version 10.0.19041.630]".
+    #[cfg(target_os = "windows")]
+    let version = command_output("cmd", &["/C", "ver"]).and_then(|output| {
+        let start = output.find("Version ")? + "Version ".len();
+        let end = output[start..].find(']')? + start;
+        Some(output[start..end].to_string())
+    });
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let version: Option<String> = None;
+
+    version.unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+/// Detects the manufacturer of the device.
+///
+/// Returns `"unknown"` if it can't be detected.
+pub fn device_manufacturer() -> String {
+    #[cfg(target_os = "linux")]
+    let manufacturer = read_trimmed("/sys/class/dmi/id/sys_vendor");
+
+    #[cfg(target_os = "macos")]
+    let manufacturer = Some("Apple".to_string());
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let manufacturer: Option<String> = None;
+
+    manufacturer.unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+/// Detects the model of the device.
+///
+/// Returns `"unknown"` if it can't be detected.
+pub fn device_model() -> String {
+    #[cfg(target_os = "linux")]
+    let model = read_trimmed("/sys/class/dmi/id/product_name");
+
+    #[cfg(target_os = "macos")]
+    let model = command_output("sysctl", &["-n", "hw.model"]);
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let model: Option<String> = None;
+
+    model.unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+/// The value reported for a locale that could not be detected, "undetermined".
+const UNDETERMINED_LOCALE: &str = "und";
+
+/// Converts a POSIX locale (e.g. "es_ES.UTF-8") into a language tag (e.g. "es-ES").
+///
+/// Returns `None` for the "C" and "POSIX" locales, which don't name a language.
+#[cfg(any(unix, test))]
+fn posix_locale_to_tag(locale: &str) -> Option<String> {
+    // Drop the codeset and modifier, e.g. ".UTF-8" and "@euro".
+    let locale = locale.split(['.', '@']).next()?;
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return None;
+    }
+    Some(locale.replace('_', "-"))
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetUserDefaultLocaleName(locale_name: *mut u16, locale_name_len: i32) -> i32;
+}
+
+/// Detects the locale of the user, e.g. "es-ES".
+///
+/// On Unix it is read from the `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables,
+/// in that order.
+///
+/// Returns `"und"` if it can't be detected.
+pub fn locale() -> String {
+    #[cfg(unix)]
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| posix_locale_to_tag(&value));
+
+    #[cfg(target_os = "windows")]
+    let locale = {
+        // `LOCALE_NAME_MAX_LENGTH`, including the terminating null character.
+        let mut buffer = [0u16; 85];
+        // SAFETY: The buffer is valid for writes of its full length.
+        let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+        if len > 1 {
+            String::from_utf16(&buffer[..len as usize - 1]).ok()
+        } else {
+            None
+        }
+    };
+
+    #[cfg(not(any(unix, target_os = "windows")))]
+    let locale: Option<String> = None;
+
+    locale.unwrap_or_else(|| UNDETERMINED_LOCALE.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn posix_locales_are_converted_to_language_tags() {
+        assert_eq!(
+            Some("es-ES".to_string()),
+            posix_locale_to_tag("es_ES.UTF-8")
+        );
+        assert_eq!(Some("de-DE".to_string()), posix_locale_to_tag("de_DE@euro"));
+        assert_eq!(Some("en".to_string()), posix_locale_to_tag("en"));
+        assert_eq!(None, posix_locale_to_tag("C.UTF-8"));
+        assert_eq!(None, posix_locale_to_tag("POSIX"));
+        assert_eq!(None, posix_locale_to_tag(""));
+    }
+}
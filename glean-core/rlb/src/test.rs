@@ -2,200 +2,511 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::private::BooleanMetric;
-use once_cell::sync::Lazy;
-use std::path::PathBuf;
-use std::sync::Mutex;
-
-use super::*;
-
-// Because glean_preview is a global-singleton, we need to run the tests one-by-one to avoid different tests stomping over each other.
-// This is only an issue because we're resetting Glean, this cannot happen in normal use of the
-// RLB.
-//
-// We use a global lock to force synchronization of all tests, even if run multi-threaded.
-// This allows us to run without `--test-threads 1`.`
-static GLOBAL_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
-const GLOBAL_APPLICATION_ID: &str = "org.mozilla.rlb.test";
-
-// Create a new instance of Glean with a temporary directory.
-// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
-fn new_glean() -> tempfile::TempDir {
-    let dir = tempfile::tempdir().unwrap();
-    let tmpname = dir.path().display().to_string();
-
-    let cfg = Configuration {
-        data_path: tmpname,
-        application_id: GLOBAL_APPLICATION_ID.into(),
-        upload_enabled: true,
-        max_events: None,
-        delay_ping_lifetime_io: false,
-        channel: Some("testing".into()),
-    };
-
-    initialize(cfg, ClientInfoMetrics::unknown());
-    dir
+//! Utilities for testing code instrumented with Glean.
+//!
+//! Only available with the `test-support` feature.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use glean_core::metrics::{combine_base_identifier_and_label, Metric};
+use glean_core::storage::StorageManager;
+use glean_core::{Clock, Lifetime};
+
+use crate::dispatcher;
+use crate::net::{PingUploader, UploadResult};
+use crate::private::{CounterMetric, LabeledMetric};
+use crate::{ClientInfoMetrics, Configuration, InitializeError};
+
+/// How long `simulate_restart` and `test_reset_glean` wait for Glean to be initialized again.
+pub(crate) const RESTART_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Makes the document ids of pings submitted from now on deterministic.
+///
+/// Document ids count up from `00000000-0000-0000-0000-000000000001`,
+/// so tests can assert exactly which pings were queued.
+pub fn use_deterministic_doc_ids() {
+    dispatcher::launch(|| crate::with_glean_mut(|glean| glean.test_set_deterministic_doc_ids()));
 }
 
-#[test]
-fn disabling_upload_disables_metrics_recording() {
-    let _lock = GLOBAL_LOCK.lock().unwrap();
-    env_logger::try_init().ok();
-
-    let _t = new_glean();
-    crate::dispatcher::block_on_queue();
+/// Checks that a metric is included in the ping with the given name.
+///
+/// This looks at the metrics the ping would currently contain,
+/// without collecting the ping or clearing any data.
+/// The metric can be of any type.
+///
+/// # Arguments
+///
+/// * `ping_name` - the name of the ping.
+/// * `category` - the category of the metric, might be empty.
+/// * `name` - the name of the metric.
+///
+/// # Errors
+///
+/// A description of the failure, listing the metrics the ping contains.
+pub fn assert_metric_in_ping(ping_name: &str, category: &str, name: &str) -> Result<(), String> {
+    let identifier = metric_identifier(category, name);
 
-    let metric = BooleanMetric::new(CommonMetricData {
-        name: "bool_metric".into(),
-        category: "test".into(),
-        send_in_pings: vec!["store1".into()],
-        lifetime: Lifetime::Application,
-        disabled: false,
-        dynamic_label: None,
+    dispatcher::block_on_queue();
+    let snapshot = crate::with_glean(|glean| {
+        StorageManager.snapshot_as_json(glean.storage(), ping_name, false)
     });
 
-    crate::set_upload_enabled(false);
+    // The snapshot maps metric types to the metrics of that type.
+    let sections = match snapshot.as_ref().and_then(|snapshot| snapshot.as_object()) {
+        Some(sections) => sections,
+        None => {
+            return Err(format!(
+                "Expected metric {:?} in ping {:?}, but the ping contains no metrics",
+                identifier, ping_name
+            ))
+        }
+    };
 
-    assert!(metric.test_get_value("store1").is_none())
+    if sections
+        .values()
+        .any(|section| section.get(&identifier).is_some())
+    {
+        return Ok(());
+    }
+
+    let recorded: Vec<String> = sections
+        .iter()
+        .flat_map(|(metric_type, section)| {
+            section
+                .as_object()
+                .into_iter()
+                .flat_map(|metrics| metrics.keys())
+                .map(move |metric| format!("{} ({})", metric, metric_type))
+        })
+        .collect();
+    Err(format!(
+        "Expected metric {:?} in ping {:?}, but the ping only contains: {}",
+        identifier,
+        ping_name,
+        recorded.join(", ")
+    ))
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1672982.
-fn test_experiments_recording() {
-    todo!()
-}
+/// Removes the stored value of a single metric from the given ping.
+///
+/// This waits for previously launched tasks, so values recorded before this call are removed.
+/// The metric can be of any type, except events and labeled metrics.
+/// Other metrics are not affected.
+///
+/// # Arguments
+///
+/// * `category` - the category of the metric, might be empty.
+/// * `name` - the name of the metric.
+/// * `ping_name` - the name of the ping to remove the value from.
+pub fn clear_metric(category: &str, name: &str, ping_name: &str) {
+    let identifier = metric_identifier(category, name);
 
-#[test]
-#[ignore] // TODO: To be done in bug 1672982.
-fn test_experiments_recording_before_glean_inits() {
-    todo!()
+    dispatcher::block_on_queue();
+    crate::with_glean(|glean| {
+        for lifetime in &[Lifetime::Ping, Lifetime::Application, Lifetime::User] {
+            // The metric is only stored with one lifetime, removing it from the others fails.
+            let _ = glean
+                .storage()
+                .remove_single_metric(*lifetime, ping_name, &identifier);
+        }
+    });
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673645.
-fn test_sending_of_foreground_background_pings() {
-    todo!()
-}
+/// Gets the value of a labeled counter for a single label.
+///
+/// This waits for previously launched tasks, so values recorded before this call are included.
+/// Unlike `metric.get(label).test_get_value(ping_name)`, this reads the stored value directly:
+/// the label is not validated and doesn't count towards the limit of dynamic labels.
+///
+/// # Arguments
+///
+/// * `metric` - the labeled counter.
+/// * `label` - the label to get the value for, e.g. `__other__`.
+/// * `ping_name` - the name of the ping to get the value from.
+///
+/// # Returns
+///
+/// The value of the counter for the label, or `None` if nothing was recorded for it.
+pub fn get_labeled_counter(
+    metric: &LabeledMetric<CounterMetric>,
+    label: &str,
+    ping_name: &str,
+) -> Option<i32> {
+    let meta = metric.submetric_meta();
+    let identifier =
+        combine_base_identifier_and_label(&metric_identifier(&meta.category, &meta.name), label);
 
-#[test]
-#[ignore] // TODO: To be done in bug 1672958.
-fn test_sending_of_startup_baseline_ping() {
-    todo!()
+    dispatcher::block_on_queue();
+    crate::with_glean(|glean| {
+        match StorageManager.snapshot_metric(glean.storage(), ping_name, &identifier) {
+            Some(Metric::Counter(value)) => Some(value),
+            _ => None,
+        }
+    })
 }
 
-#[test]
-fn initialize_must_not_crash_if_data_dir_is_messed_up() {
-    let _lock = GLOBAL_LOCK.lock().unwrap();
-    env_logger::try_init().ok();
-
-    let dir = tempfile::tempdir().unwrap();
-    let tmpdirname = dir.path().display().to_string();
-    // Create a file in the temporary dir and use that as the
-    // name of the Glean data dir.
-    let file_path = PathBuf::from(tmpdirname).join("notadir");
-    std::fs::write(file_path.clone(), "test").expect("The test Glean dir file must be created");
+/// Builds the identifier a metric is stored under.
+fn metric_identifier(category: &str, name: &str) -> String {
+    if category.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", category, name)
+    }
+}
 
-    let cfg = Configuration {
-        data_path: file_path.to_string_lossy().to_string(),
-        application_id: GLOBAL_APPLICATION_ID.into(),
-        upload_enabled: true,
-        max_events: None,
-        delay_ping_lifetime_io: false,
-        channel: Some("testing".into()),
-    };
+/// The longest time to sleep between two checks in [`wait_until`](fn.wait_until.html).
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Waits until the predicate is true, checking it again with a small backoff.
+///
+/// Use this to wait for things happening on other threads, such as uploads,
+/// instead of sleeping for a fixed time.
+///
+/// # Arguments
+///
+/// * `predicate` - the condition to wait for.
+/// * `timeout` - how long to wait at most.
+///
+/// # Returns
+///
+/// Whether the predicate became true before the timeout.
+pub fn wait_until<P>(predicate: P, timeout: Duration) -> bool
+where
+    P: Fn() -> bool,
+{
+    let start = Instant::now();
+    let mut interval = Duration::from_millis(1);
+    loop {
+        if predicate() {
+            return true;
+        }
+
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return false;
+        }
+
+        thread::sleep(interval.min(timeout - elapsed));
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
 
-    initialize(cfg, ClientInfoMetrics::unknown());
+/// Gets the document ids of the pending pings with the given name, in queue order.
+///
+/// Only pings submitted since Glean was initialized are included,
+/// and pings are no longer pending once they were uploaded.
+pub fn pending_ping_doc_ids(ping_name: &str) -> Vec<String> {
+    dispatcher::block_on_queue();
+    crate::with_glean(|glean| glean.test_get_pending_ping_doc_ids(ping_name))
+}
 
-    // TODO: is this test working? is it waiting for init to finish?
+/// Gets the exact bytes that would be uploaded for the latest pending ping with the given name.
+///
+/// Unlike the JSON payload stored on disk, this is the request body,
+/// gzip compressed unless `Configuration::compress_pings` is unset.
+/// Only pings submitted since Glean was initialized are included,
+/// and pings are no longer pending once they were uploaded.
+pub fn ping_payload_bytes(ping_name: &str) -> Option<Vec<u8>> {
     dispatcher::block_on_queue();
+    crate::with_glean(|glean| glean.test_get_pending_ping_body(ping_name))
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673667.
-fn queued_recorded_metrics_correctly_record_during_init() {
-    todo!()
+/// Submits the `metrics` ping right away, regardless of its schedule.
+///
+/// This waits for previously launched tasks, so their data is included.
+/// The ping is submitted like any other, clearing its data and queueing it for upload.
+///
+/// # Arguments
+///
+/// * `reason` - the reason to submit the ping with.
+///
+/// # Returns
+///
+/// The JSON payload of the ping, or `None` if it was not submitted, e.g. because it was empty.
+pub fn force_metrics_ping(reason: &str) -> Option<String> {
+    let reason = reason.to_string();
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    dispatcher::launch(move || {
+        crate::private::ping::run_test_callback("metrics", Some(&reason));
+
+        // The payload is read while Glean is still locked, before an upload can take the ping.
+        let payload = crate::with_glean(|glean| {
+            match crate::submit_ping_by_name_sync(glean, "metrics", Some(&reason)) {
+                Ok(true) => glean.test_get_pending_ping_payload("metrics"),
+                _ => None,
+            }
+        });
+        if payload.is_some() {
+            crate::trigger_upload(&crate::global_state().lock().unwrap());
+        }
+        let _ = tx.send(payload);
+    });
+    rx.recv().ok().flatten()
 }
 
-#[test]
-fn initializing_twice_is_a_noop() {
-    let dir = tempfile::tempdir().unwrap();
-    let tmpname = dir.path().display().to_string();
+/// Gets the branch of an active experiment.
+///
+/// This waits for previously launched tasks, so experiments set active before this call are seen.
+///
+/// # Returns
+///
+/// The branch if the experiment is active, `None` otherwise.
+pub fn get_experiment_branch(experiment_id: &str) -> Option<String> {
+    dispatcher::block_on_queue();
+    crate::with_glean(|glean| glean.test_get_experiment_branch(experiment_id.to_string()))
+}
 
-    let cfg = Configuration {
-        data_path: tmpname,
-        application_id: GLOBAL_APPLICATION_ID.into(),
-        upload_enabled: true,
-        max_events: None,
-        delay_ping_lifetime_io: false,
-        channel: Some("testing".into()),
-    };
+/// Sets several experiments active at once.
+///
+/// This is equivalent to calling [`set_experiment_active`](../fn.set_experiment_active.html)
+/// for each experiment. Experiments that are already active and not in the snapshot stay active.
+///
+/// # Arguments
+///
+/// * `experiments` - the branch and optional extras, by experiment id.
+pub fn set_experiments_snapshot(
+    experiments: HashMap<String, (String, Option<HashMap<String, String>>)>,
+) {
+    dispatcher::launch(move || {
+        crate::with_glean(|glean| {
+            for (experiment_id, (branch, extra)) in experiments {
+                glean.set_experiment_active(experiment_id, branch, extra);
+            }
+        })
+    });
+}
 
-    initialize(cfg.clone(), ClientInfoMetrics::unknown());
-    initialize(cfg, ClientInfoMetrics::unknown());
+/// Simulates a restart of the application, initializing Glean again on its existing data.
+///
+/// Glean is torn down as if the application was killed: previously launched tasks and
+/// running uploads are finished, but no `baseline` ping is submitted and the dirty flag stays set,
+/// so a `baseline` ping with the reason `dirty_startup` is submitted on the restart.
+/// Glean is then initialized with the same configuration and client info,
+/// and this blocks until the initialization completed.
+///
+/// # Errors
+///
+/// * [`InitializeError::Failed`](../enum.InitializeError.html) if Glean was not initialized
+///   or could not be set up again.
+/// * [`InitializeError::Timeout`](../enum.InitializeError.html) if initialization did not complete in time.
+pub fn simulate_restart() -> Result<(), InitializeError> {
+    let (cfg, client_info) = crate::teardown_preserving_data().ok_or(InitializeError::Failed)?;
+    crate::initialize_sync(cfg, client_info, RESTART_TIMEOUT)
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673668.
-fn dont_handle_events_when_uninitialized() {
-    todo!()
+/// Gets the total size in bytes of all files in the Glean data directory.
+///
+/// This waits for previously launched tasks, so data recorded before this call is included.
+/// Data with ping lifetime that is held in memory (see `Configuration.delay_ping_lifetime_io`)
+/// is not on disk and therefore not counted.
+///
+/// # Errors
+///
+/// Any error reading the data directory.
+pub fn storage_size_bytes() -> io::Result<u64> {
+    dispatcher::block_on_queue();
+    let data_path = crate::with_glean(|glean| glean.get_data_path().to_path_buf());
+    dir_size(&data_path)
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn the_app_channel_must_be_correctly_set_if_requested() {
-    todo!()
+/// Sums up the sizes of all files below `path`, recursively.
+fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn ping_collection_must_happen_after_concurrently_scheduled_metrics_recordings() {
-    todo!()
+/// Tears down Glean and removes its temporary data directory when dropped.
+struct TempGlean {
+    data_path: PathBuf,
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn basic_metrics_should_be_cleared_when_disabling_uploading() {
-    todo!()
+impl Drop for TempGlean {
+    fn drop(&mut self) {
+        // This closes the database, so the files can be removed on Windows too.
+        crate::teardown_preserving_data();
+        if let Err(e) = fs::remove_dir_all(&self.data_path) {
+            log::warn!(
+                "Failed to remove the temporary data directory {}: {}",
+                self.data_path.display(),
+                e
+            );
+        }
+    }
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn core_metrics_should_be_cleared_and_restored_when_disabling_and_enabling_uploading() {
-    todo!()
+/// Runs `f` with Glean initialized in a new temporary data directory.
+///
+/// Glean is torn down and the directory removed afterwards, even if `f` panics.
+///
+/// # Panics
+///
+/// Panics if Glean is already initialized or could not be initialized.
+///
+/// ```no_run
+/// # use glean::{private::CounterMetric, CommonMetricData};
+/// glean::test::with_temp_glean(|| {
+///     let counter = CounterMetric::new(CommonMetricData {
+///         name: "counter".into(),
+///         category: "test".into(),
+///         send_in_pings: vec!["metrics".into()],
+///         ..Default::default()
+///     });
+///     counter.add(1);
+///     assert_eq!(Some(1), counter.test_get_value("metrics"));
+/// });
+/// ```
+pub fn with_temp_glean<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    assert!(
+        !crate::is_initialized(),
+        "Glean must not be initialized before `with_temp_glean`"
+    );
+
+    let data_path = std::env::temp_dir().join(format!("glean-test-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&data_path).expect("Failed to create the temporary data directory");
+
+    let cfg = Configuration::builder()
+        .data_path(&data_path)
+        .application_id("org.mozilla.glean.test")
+        .build()
+        .expect("The test configuration is valid");
+    let guard = TempGlean { data_path };
+    crate::initialize_sync(cfg, ClientInfoMetrics::unknown(), RESTART_TIMEOUT)
+        .expect("Failed to initialize Glean");
+
+    let result = f();
+    drop(guard);
+    result
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn overflowing_the_task_queue_records_telemetry() {
-    todo!()
+/// Gets the document id and result of the last upload processed by Glean, if any.
+///
+/// Uploads happen on a separate thread, so this only reflects uploads
+/// that finished before this call.
+/// Returns `None` if no uploader is configured.
+pub fn last_upload_result() -> Option<(String, UploadResult)> {
+    let upload_manager = crate::global_state().lock().unwrap().upload_manager.clone();
+    upload_manager.and_then(|upload_manager| upload_manager.last_result())
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn sending_deletion_ping_if_disabled_outside_of_run() {
-    todo!()
+/// Replaces the source of the current time, e.g. with a `glean_core::MockClock`.
+///
+/// The clock is used for timespans, timing distributions, events and datetime metrics
+/// recorded from now on, for the times in `ping_info` and for the upload backoff.
+/// The `metrics` ping scheduler takes the clock when Glean is initialized.
+/// Timespans and timers running while the clock is replaced are not measured correctly.
+/// The clock is kept when Glean is initialized again.
+pub fn set_clock(clock: Arc<dyn Clock>) {
+    let start = clock.monotonic_now();
+    *crate::CLOCK.write().unwrap() = (Arc::clone(&clock), start);
+
+    if crate::is_initialized() {
+        dispatcher::launch(move || crate::with_glean_mut(|glean| glean.set_clock(clock)));
+    }
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn no_sending_of_deletion_ping_if_unchanged_outside_of_run() {
-    todo!()
+/// An uploader that doesn't send anything, but records every upload attempt.
+///
+/// Each attempt is answered with the next scripted result,
+/// or with a HTTP status of 200 if no results are left.
+///
+/// Clones share the same state, so a clone can be passed to the
+/// [`Configuration`](../struct.Configuration.html) while the test keeps inspecting the original.
+///
+/// ## Example
+///
+/// ```rust
+/// # use glean::{net::UploadResult, test::MockUploader, Configuration};
+/// let uploader = MockUploader::new();
+/// uploader.push_result(UploadResult::RecoverableFailure);
+///
+/// let cfg = Configuration::builder()
+///     .data_path("/tmp/data")
+///     .application_id("org.mozilla.glean_core.example")
+///     .uploader(uploader.clone())
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockUploader {
+    state: Arc<Mutex<MockUploaderState>>,
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn test_sending_of_startup_baseline_ping_with_application_lifetime_metric() {
-    todo!()
+#[derive(Debug, Default)]
+struct MockUploaderState {
+    /// The results to answer the next upload attempts with.
+    results: VecDeque<UploadResult>,
+    /// The number of upload attempts.
+    call_count: usize,
+    /// The number of upload attempts, by ping name.
+    attempts: HashMap<String, usize>,
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn test_dirty_flag_is_reset_to_false() {
-    todo!()
+impl MockUploader {
+    /// Creates a new mock uploader without any scripted results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scripts the result for an upload attempt.
+    ///
+    /// Results are used in the order they were pushed.
+    pub fn push_result(&self, result: UploadResult) {
+        self.state.lock().unwrap().results.push_back(result);
+    }
+
+    /// Gets the number of upload attempts so far.
+    pub fn call_count(&self) -> usize {
+        self.state.lock().unwrap().call_count
+    }
+
+    /// Gets the number of upload attempts for pings with the given name.
+    pub fn attempts_for(&self, ping_name: &str) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .attempts
+            .get(ping_name)
+            .copied()
+            .unwrap_or(0)
+    }
 }
 
-#[test]
-#[ignore] // TODO: To be done in bug 1673672.
-fn flipping_upload_enabled_respects_order_of_events() {
-    todo!()
+impl PingUploader for MockUploader {
+    fn upload(
+        &self,
+        url: String,
+        _body: Vec<u8>,
+        _headers: HashMap<String, String>,
+    ) -> UploadResult {
+        let mut state = self.state.lock().unwrap();
+        state.call_count += 1;
+
+        // The upload path has the form
+        // `/submit/<application_id>/<ping_name>/<schema_version>/<document_id>`.
+        let ping_name = url.rsplit('/').nth(2).unwrap_or_default().to_string();
+        *state.attempts.entry(ping_name).or_insert(0) += 1;
+
+        state
+            .results
+            .pop_front()
+            .unwrap_or(UploadResult::HttpStatus(200))
+    }
 }
@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::{CounterMetric, PingType},
+    test::assert_metric_in_ping,
+    ClientInfoMetrics, CommonMetricData, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_counter(name: &str, ping: &str) -> CounterMetric {
+    CounterMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec![ping.into()],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn recorded_metrics_are_found() {
+    setup_glean();
+    let ping = PingType::new("custom", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+
+    let metric = new_counter("recorded", "custom");
+    metric.add(1);
+
+    assert_eq!(Ok(()), assert_metric_in_ping("custom", "test", "recorded"));
+    // Checking doesn't clear the data.
+    assert_eq!(Some(1), metric.test_get_value("custom"));
+}
+
+#[test]
+fn unrecorded_metrics_are_reported() {
+    setup_glean();
+    let ping = PingType::new("other", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+
+    let err = assert_metric_in_ping("other", "test", "unrecorded").unwrap_err();
+    assert!(err.contains("contains no metrics"), "{}", err);
+
+    new_counter("recorded", "other").add(1);
+    let err = assert_metric_in_ping("other", "test", "unrecorded").unwrap_err();
+    assert!(err.contains("\"test.unrecorded\""), "{}", err);
+    assert!(err.contains("test.recorded (counter)"), "{}", err);
+}
@@ -0,0 +1,53 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{
+    private::{BooleanMetric, CounterMetric},
+    ClientInfoMetrics, CommonMetricData, Configuration, Lifetime,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn clearing_a_metric_keeps_other_metrics() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    });
+    let boolean = BooleanMetric::new(CommonMetricData {
+        name: "boolean".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::User,
+        ..Default::default()
+    });
+
+    counter.add(3);
+    boolean.set(true);
+
+    glean::test::clear_metric("test", "counter", "store1");
+    assert_eq!(None, counter.test_get_value("store1"));
+    assert_eq!(Some(true), boolean.test_get_value("store1"));
+
+    glean::test::clear_metric("test", "boolean", "store1");
+    assert_eq!(None, boolean.test_get_value("store1"));
+
+    // The metric records from scratch afterwards.
+    counter.add(1);
+    assert_eq!(Some(1), counter.test_get_value("store1"));
+}
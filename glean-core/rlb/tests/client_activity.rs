@@ -0,0 +1,73 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use glean::private::CounterMetric;
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// Counts the pending `baseline` pings with the given reason.
+fn count_reason(data_path: &Path, reason: &str) -> usize {
+    fs::read_dir(data_path.join("pending_pings"))
+        .unwrap()
+        .filter(|entry| {
+            let content = fs::read_to_string(entry.as_ref().unwrap().path()).unwrap();
+            let mut lines = content.lines();
+            if !lines.next().unwrap().contains("/baseline/") {
+                return false;
+            }
+            let body: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+            body["ping_info"]["reason"] == reason
+        })
+        .count()
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is restarted.
+#[test]
+fn client_activity_submits_baseline_pings_and_tracks_the_dirty_flag() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .delay_ping_lifetime_io(true)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    });
+
+    glean::handle_client_active();
+    counter.add(2);
+    glean::handle_client_inactive();
+    glean::dispatcher::block_on_queue();
+    assert_eq!(1, count_reason(dir.path(), "active"));
+    assert_eq!(1, count_reason(dir.path(), "inactive"));
+
+    // Being killed while inactive is not a dirty shutdown,
+    // and data with ping lifetime held in memory was persisted.
+    glean::test::simulate_restart().unwrap();
+    assert_eq!(0, count_reason(dir.path(), "dirty_startup"));
+    assert_eq!(Some(2), counter.test_get_value("store1"));
+
+    // Being killed while active is.
+    glean::handle_client_inactive();
+    glean::handle_client_active();
+    glean::test::simulate_restart().unwrap();
+    assert_eq!(1, count_reason(dir.path(), "dirty_startup"));
+
+    glean::shutdown();
+}
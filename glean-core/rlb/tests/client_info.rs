@@ -0,0 +1,73 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::CounterMetric, ClientInfoMetrics, CommonMetricData, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn builder_defaults_to_unknown() {
+    let client_info = ClientInfoMetrics::builder().app_build("1234/A").build();
+    assert_eq!("1234/A", client_info.app_build);
+    assert_eq!("unknown", client_info.app_display_version);
+    assert_eq!(None, client_info.locale);
+}
+
+// Glean is a global singleton, so this file contains a single test
+// initializing it.
+#[test]
+fn client_info_contains_the_detected_system() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    let client_info = ClientInfoMetrics::builder()
+        .app_build("1234/A")
+        .app_display_version("1.0.3")
+        .locale("es-ES")
+        .build();
+    glean::initialize_sync(cfg, client_info, Duration::from_secs(10)).unwrap();
+
+    // The metrics ping is not sent empty.
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["metrics".into()],
+        ..Default::default()
+    });
+    counter.add(1);
+
+    let payload = glean::test::force_metrics_ping("today").unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    let client_info = &payload["client_info"];
+    assert_eq!("1234/A", client_info["app_build"]);
+    assert_eq!("1.0.3", client_info["app_display_version"]);
+    assert_eq!("es-ES", client_info["locale"]);
+    #[cfg(target_os = "linux")]
+    assert_eq!("Linux", client_info["os"]);
+
+    let os_version = client_info["os_version"].as_str().unwrap();
+    assert!(!os_version.is_empty());
+    #[cfg(target_os = "linux")]
+    assert_eq!(
+        std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .unwrap()
+            .trim(),
+        os_version
+    );
+
+    assert!(!client_info["device_manufacturer"]
+        .as_str()
+        .unwrap()
+        .is_empty());
+    assert!(!client_info["device_model"].as_str().unwrap().is_empty());
+
+    glean::shutdown();
+}
@@ -0,0 +1,69 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::private::{PingType, TextMetric};
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else writes to the database while it is compacted.
+#[test]
+fn compacting_reclaims_the_space_of_submitted_metrics() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let kept = TextMetric::new(CommonMetricData {
+        name: "kept".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Application,
+        ..Default::default()
+    });
+    kept.set("value");
+
+    // Submitting the ping clears its metrics, leaving unused space in the database.
+    let ping = PingType::new("compacted", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+    for i in 0..500 {
+        let text = TextMetric::new(CommonMetricData {
+            name: format!("text_{}", i),
+            category: "test".into(),
+            send_in_pings: vec!["compacted".into()],
+            lifetime: Lifetime::Ping,
+            ..Default::default()
+        });
+        text.set("x".repeat(1000));
+    }
+    assert!(ping.submit(None).wait());
+
+    let size_before = glean_core::storage_size_bytes(dir.path());
+    let reclaimed = glean::compact_storage_sync().unwrap();
+    let size_after = glean_core::storage_size_bytes(dir.path());
+    assert_eq!(size_before - reclaimed, size_after);
+    // The safe-mode backend rewrites its file on every commit, so there's nothing to reclaim.
+    #[cfg(not(feature = "rkv-safe-mode"))]
+    assert!(reclaimed > 0);
+
+    // The data is kept, and new data is recorded as usual.
+    assert_eq!(Some("value".to_string()), kept.test_get_value("store1"));
+    kept.set("new value");
+    assert_eq!(Some("new value".to_string()), kept.test_get_value("store1"));
+
+    // Compacting again in the background leaves the data intact.
+    glean::compact_storage();
+    glean::dispatcher::block_on_queue();
+    assert_eq!(Some("new value".to_string()), kept.test_get_value("store1"));
+
+    glean::shutdown();
+}
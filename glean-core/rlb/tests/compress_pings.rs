@@ -0,0 +1,35 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn ping_bodies_are_not_compressed_if_disabled() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Without an uploader, submitted pings stay pending.
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .compress_pings(false)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("custom", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+
+    // The request body is the JSON payload itself.
+    let bytes = glean::test::ping_payload_bytes("custom").unwrap();
+    let payload: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(payload["ping_info"]["seq"].is_number());
+
+    glean::shutdown();
+}
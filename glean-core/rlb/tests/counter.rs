@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::CounterMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType, Lifetime,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_counter(name: &str) -> CounterMetric {
+    CounterMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Application,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn counter_saturates_and_records_an_overflow_error() {
+    setup_glean();
+    let metric = new_counter("saturating");
+
+    metric.add(2);
+    metric.add(i32::MAX);
+
+    assert_eq!(Some(i32::MAX), metric.test_get_value("store1"));
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
+
+#[test]
+fn negative_amounts_are_ignored_and_record_an_error() {
+    setup_glean();
+    let metric = new_counter("negative");
+
+    metric.add(1);
+    metric.add(-1);
+    metric.add(0);
+
+    assert_eq!(Some(1), metric.test_get_value("store1"));
+    assert_eq!(
+        2,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+    assert_eq!(
+        0,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
@@ -0,0 +1,83 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+use std::time::Duration;
+
+use glean::{
+    private::CustomDistributionMetric, ClientInfoMetrics, CommonMetricData, Configuration,
+    ErrorType, HistogramType,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+fn new_custom_distribution(name: &str, histogram_type: HistogramType) -> CustomDistributionMetric {
+    CustomDistributionMetric::new(
+        CommonMetricData {
+            name: name.into(),
+            category: "test".into(),
+            send_in_pings: vec!["metrics".into()],
+            ..Default::default()
+        },
+        10,
+        100,
+        10,
+        histogram_type,
+    )
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn samples_outside_the_range_are_recorded_in_the_outermost_buckets() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    initialize(dir.path());
+
+    let linear = new_custom_distribution("linear", HistogramType::Linear);
+    linear.accumulate_samples_signed(vec![1, 50, 1000, -5]);
+
+    let data = linear.test_get_value("metrics").unwrap();
+    assert_eq!(1051, data.sum);
+    // Below the minimum, in the underflow bucket.
+    assert_eq!(Some(&1), data.values.get(&0));
+    // Above the maximum, in the last bucket.
+    assert_eq!(Some(&1), data.values.get(&100));
+    assert_eq!(3, data.values.values().sum::<u64>());
+    // Negative samples are dropped.
+    assert_eq!(
+        1,
+        linear.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+
+    let exponential = new_custom_distribution("exponential", HistogramType::Exponential);
+    exponential.accumulate_samples_signed(vec![0, 5000]);
+
+    let data = exponential.test_get_value("metrics").unwrap();
+    assert_eq!(Some(&1), data.values.get(&0));
+    assert_eq!(Some(&1), data.values.get(&100));
+
+    let payload = glean::test::force_metrics_ping("manual").unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    let distributions = &payload["metrics"]["custom_distribution"];
+    assert_eq!(1051, distributions["test.linear"]["sum"]);
+    assert_eq!(1, distributions["test.linear"]["values"]["0"]);
+    assert_eq!(1, distributions["test.linear"]["values"]["100"]);
+    assert_eq!(5000, distributions["test.exponential"]["sum"]);
+    assert_eq!(1, distributions["test.exponential"]["values"]["0"]);
+    assert_eq!(1, distributions["test.exponential"]["values"]["100"]);
+
+    glean::shutdown();
+}
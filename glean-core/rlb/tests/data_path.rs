@@ -0,0 +1,53 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::OsString;
+use std::fs;
+use std::time::Duration;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// A directory name that is not valid unicode.
+#[cfg(target_os = "linux")]
+fn non_unicode_dir_name() -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+    OsString::from_vec(b"glean-\xff\xfe".to_vec())
+}
+
+/// A directory name that is not valid unicode.
+#[cfg(windows)]
+fn non_unicode_dir_name() -> OsString {
+    use std::os::windows::ffi::OsStringExt;
+    // An unpaired surrogate.
+    OsString::from_wide(&[0x67, 0x6c, 0x65, 0x61, 0x6e, 0xD800])
+}
+
+/// Other platforms, e.g. macOS, don't allow file names that are not valid unicode.
+#[cfg(not(any(target_os = "linux", windows)))]
+fn non_unicode_dir_name() -> OsString {
+    OsString::from("glean")
+}
+
+// Glean is a global singleton, so this file contains a single test.
+#[test]
+fn glean_works_with_non_unicode_data_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_path = dir.path().join("dätä-пример").join(non_unicode_dir_name());
+
+    let cfg = Configuration::builder()
+        .data_path(&data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("custom", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+
+    let pending_pings = fs::read_dir(data_path.join("pending_pings")).unwrap();
+    assert_eq!(1, pending_pings.count());
+}
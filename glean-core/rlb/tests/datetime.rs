@@ -0,0 +1,111 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+
+use chrono::{FixedOffset, TimeZone};
+use glean_core::MockClock;
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::DatetimeMetric, ClientInfoMetrics, CommonMetricData, Configuration, TimeUnit,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+// 2020-05-07T13:45:21.123Z
+const NOW_MILLIS: i64 = 1_588_859_121_123;
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+    glean::test::set_clock(Arc::new(MockClock::at_millis(NOW_MILLIS)));
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_datetime(name: &str, time_unit: TimeUnit) -> DatetimeMetric {
+    DatetimeMetric::new(
+        CommonMetricData {
+            name: name.into(),
+            category: "test".into(),
+            send_in_pings: vec!["store1".into()],
+            ..Default::default()
+        },
+        time_unit,
+    )
+}
+
+#[test]
+fn datetime_is_truncated_to_the_time_unit() {
+    setup_glean();
+    let minute = new_datetime("minute", TimeUnit::Minute);
+    let day = new_datetime("day", TimeUnit::Day);
+
+    let value = FixedOffset::west(5 * 3600)
+        .ymd(2020, 5, 7)
+        .and_hms_nano(13, 45, 21, 123_456_789);
+    minute.set(Some(value));
+    day.set(Some(value));
+
+    assert_eq!(
+        Some("2020-05-07T13:45-05:00".to_string()),
+        minute.test_get_value_as_string("store1")
+    );
+    assert_eq!(
+        Some("2020-05-07-05:00".to_string()),
+        day.test_get_value_as_string("store1")
+    );
+}
+
+#[test]
+fn test_get_value_preserves_the_offset() {
+    setup_glean();
+    let metric = new_datetime("offset", TimeUnit::Second);
+
+    metric.set_with_details(2020, 5, 7, 13, 45, 21, 123_456_789, 2 * 3600);
+
+    let expected = FixedOffset::east(2 * 3600)
+        .ymd(2020, 5, 7)
+        .and_hms(13, 45, 21);
+    let value = metric.test_get_value("store1").unwrap();
+    assert_eq!(expected, value);
+    assert_eq!(2 * 3600, value.offset().local_minus_utc());
+}
+
+#[test]
+fn setting_none_records_the_current_time() {
+    setup_glean();
+    let metric = new_datetime("now", TimeUnit::Millisecond);
+
+    metric.set(None);
+
+    let value = metric.test_get_value("store1").unwrap();
+    assert_eq!(NOW_MILLIS, value.timestamp_millis());
+}
+
+#[test]
+fn invalid_offsets_are_rejected() {
+    setup_glean();
+    let metric = new_datetime("invalid", TimeUnit::Day);
+
+    metric.set_with_details(2020, 5, 7, 0, 0, 0, 0, 24 * 3600);
+
+    assert_eq!(None, metric.test_get_value("store1"));
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(glean::ErrorType::InvalidValue, None)
+    );
+}
@@ -0,0 +1,85 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use glean::{
+    net::{PingUploader, UploadResult},
+    private::PingType,
+    ClientInfoMetrics, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// An uploader keeping the headers of every upload request.
+#[derive(Clone, Debug, Default)]
+struct HeaderRecorder {
+    headers: Arc<Mutex<Vec<HashMap<String, String>>>>,
+}
+
+impl HeaderRecorder {
+    fn debug_ids(&self) -> Vec<Option<String>> {
+        self.headers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|headers| headers.get("X-Debug-ID").cloned())
+            .collect()
+    }
+}
+
+impl PingUploader for HeaderRecorder {
+    fn upload(
+        &self,
+        _url: String,
+        _body: Vec<u8>,
+        headers: HashMap<String, String>,
+    ) -> UploadResult {
+        self.headers.lock().unwrap().push(headers);
+        UploadResult::HttpStatus(200)
+    }
+}
+
+#[test]
+fn debug_view_tag_is_added_to_uploads_after_it_was_set() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    let uploader = HeaderRecorder::default();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("tagged", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+
+    assert!(ping.submit(None).wait());
+    assert!(glean::test::wait_until(
+        || uploader.debug_ids().len() == 1,
+        Duration::from_secs(5)
+    ));
+    assert_eq!(vec![None], uploader.debug_ids());
+
+    assert!(!glean::set_debug_view_tag("invalid tag"));
+    assert!(!glean::set_debug_view_tag("this-tag-is-way-too-long"));
+    assert!(glean::set_debug_view_tag("valid-tag"));
+
+    assert!(ping.submit(None).wait());
+    assert!(glean::test::wait_until(
+        || uploader.debug_ids().len() == 2,
+        Duration::from_secs(5)
+    ));
+    assert_eq!(
+        vec![None, Some("valid-tag".to_string())],
+        uploader.debug_ids()
+    );
+
+    glean::shutdown();
+}
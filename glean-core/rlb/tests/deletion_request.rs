@@ -0,0 +1,35 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use glean::{test::MockUploader, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while upload is disabled.
+#[test]
+fn disabling_upload_uploads_the_deletion_request_ping_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let uploader = MockUploader::new();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .build()
+        .unwrap();
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    glean::set_upload_enabled(false);
+    glean::dispatcher::block_on_queue();
+
+    // The upload happened before the dispatcher processed the next task.
+    assert_eq!(1, uploader.attempts_for("deletion-request"));
+    assert_eq!(1, uploader.call_count());
+
+    // Disabling upload again doesn't send another deletion-request ping.
+    glean::set_upload_enabled(false);
+    glean::dispatcher::block_on_queue();
+    assert_eq!(1, uploader.attempts_for("deletion-request"));
+}
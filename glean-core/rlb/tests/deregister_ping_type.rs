@@ -0,0 +1,59 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn deregistered_pings_are_not_queued() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let plugin = PingType::new("plugin", true, /* send_if_empty */ true, vec![]);
+    let handle = glean::register_ping_type(&plugin);
+    let other = PingType::new("other", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&other);
+
+    // Builtin pings are registered too.
+    for name in &["baseline", "metrics", "events", "plugin", "other"] {
+        assert!(
+            glean::test_has_ping_type(name),
+            "{} is not registered",
+            name
+        );
+    }
+    let names = glean::test_get_registered_ping_names();
+    assert!(names.ends_with(&["plugin".to_string(), "other".to_string()]));
+
+    assert!(plugin.submit(None).wait());
+    assert_eq!(1, glean::test::pending_ping_doc_ids("plugin").len());
+
+    glean::deregister_ping_type(handle);
+    assert!(!glean::test_has_ping_type("plugin"));
+    assert!(!glean::test_get_registered_ping_names().contains(&"plugin".to_string()));
+
+    assert!(!plugin.submit(None).wait());
+    assert!(!glean::submit_ping_by_name("plugin", None).wait());
+    // Only the ping submitted before deregistering is pending.
+    assert_eq!(1, glean::test::pending_ping_doc_ids("plugin").len());
+
+    // Other pings are not affected.
+    assert!(other.submit(None).wait());
+    assert_eq!(1, glean::test::pending_ping_doc_ids("other").len());
+
+    // The ping can be registered again.
+    glean::register_ping_type(&plugin);
+    assert!(plugin.submit(None).wait());
+    assert_eq!(2, glean::test::pending_ping_doc_ids("plugin").len());
+}
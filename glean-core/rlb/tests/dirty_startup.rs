@@ -0,0 +1,86 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use glean::{ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+/// Reads the reasons of all pending `baseline` pings.
+fn baseline_reasons(data_path: &Path) -> Vec<String> {
+    fs::read_dir(data_path.join("pending_pings"))
+        .unwrap()
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut lines = content.lines();
+            let path = lines.next().unwrap();
+            if !path.contains("/baseline/") {
+                return None;
+            }
+            let body: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+            Some(body["ping_info"]["reason"].as_str().unwrap().to_string())
+        })
+        .collect()
+}
+
+fn count_reason(data_path: &Path, reason: &str) -> usize {
+    baseline_reasons(data_path)
+        .iter()
+        .filter(|r| *r == reason)
+        .count()
+}
+
+/// Simulates a session that was not shut down cleanly, by setting the dirty flag
+/// with a Glean instance that is gone before the Glean bindings are initialized.
+fn mark_dirty(data_path: &Path) {
+    let cfg = glean_core::Configuration {
+        data_path: data_path.into(),
+        application_id: GLOBAL_APPLICATION_ID.into(),
+        language_binding_name: "Rust".into(),
+        upload_enabled: true,
+        max_events: None,
+        delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
+    };
+    let glean = glean_core::Glean::new(cfg).unwrap();
+    glean.set_dirty_flag(true);
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn dirty_startup_submits_baseline_ping() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    mark_dirty(dir.path());
+
+    initialize(dir.path());
+    assert_eq!(1, count_reason(dir.path(), "dirty_startup"));
+    glean::shutdown();
+
+    // After a clean shutdown, no new dirty startup ping is sent.
+    initialize(dir.path());
+    assert_eq!(1, count_reason(dir.path(), "dirty_startup"));
+    glean::shutdown();
+}
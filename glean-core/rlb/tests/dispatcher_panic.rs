@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::CounterMetric, ClientInfoMetrics, CommonMetricData, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn recording_continues_after_a_task_panicked() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    });
+
+    assert_eq!(0, glean::get_dispatcher_panic_count());
+
+    counter.add(1);
+    glean::dispatcher::launch(|| panic!("deliberately failing task"));
+    counter.add(2);
+
+    assert_eq!(Some(3), counter.test_get_value("store1"));
+    assert_eq!(1, glean::get_dispatcher_panic_count());
+}
@@ -0,0 +1,51 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use glean::dispatcher::{self, DispatchError};
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs on the shut down dispatcher.
+#[test]
+fn dispatcher_shutdown_finishes_queued_tasks() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("queued", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+
+    // Not waiting for the submission: the shutdown does.
+    let _ = ping.submit(None);
+    assert_eq!(Ok(()), dispatcher::shutdown(Duration::from_secs(10)));
+    assert_eq!(1, glean::test::pending_ping_doc_ids("queued").len());
+
+    // Tasks launched after the shutdown are rejected.
+    let ran = Arc::new(AtomicBool::new(false));
+    {
+        let ran = Arc::clone(&ran);
+        dispatcher::launch(move || ran.store(true, Ordering::SeqCst));
+    }
+    dispatcher::block_on_queue();
+    assert!(!ran.load(Ordering::SeqCst));
+    assert!(!ping.submit(None).wait());
+    assert_eq!(
+        Err(DispatchError::ShutDown),
+        dispatcher::shutdown(Duration::from_secs(10))
+    );
+
+    glean::shutdown();
+}
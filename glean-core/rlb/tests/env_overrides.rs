@@ -0,0 +1,61 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use glean::{ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Environment variables are global to the process,
+// so this file contains a single test to make sure nothing else sees them.
+#[test]
+fn environment_variables_override_the_configuration() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let configured_dir = tempfile::tempdir().unwrap();
+    let env_dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(configured_dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .upload_enabled(true)
+        .max_events(10)
+        .channel("release")
+        .build()
+        .unwrap();
+
+    env::set_var("GLEAN_UPLOAD_ENABLED", "0");
+    env::set_var("GLEAN_DATA_PATH", env_dir.path());
+    env::set_var("GLEAN_MAX_EVENTS", "20");
+    env::set_var("GLEAN_CHANNEL", "nightly");
+    env::set_var("GLEAN_DELAY_PING_LIFETIME_IO", "1");
+
+    let mut overridden = cfg.clone();
+    overridden.apply_env_overrides();
+    assert!(!overridden.upload_enabled);
+    assert_eq!(PathBuf::from(env_dir.path()), overridden.data_path);
+    assert_eq!(Some(20), overridden.max_events);
+    assert_eq!(Some("nightly".to_string()), overridden.channel);
+    assert!(overridden.delay_ping_lifetime_io);
+
+    // Invalid values are ignored.
+    env::set_var("GLEAN_UPLOAD_ENABLED", "yes");
+    env::set_var("GLEAN_MAX_EVENTS", "many");
+    env::set_var("GLEAN_DELAY_PING_LIFETIME_IO", "");
+    let mut overridden = cfg.clone();
+    overridden.apply_env_overrides();
+    assert!(overridden.upload_enabled);
+    assert_eq!(Some(10), overridden.max_events);
+    assert!(!overridden.delay_ping_lifetime_io);
+    assert_eq!(Some("nightly".to_string()), overridden.channel);
+
+    // `initialize` applies the overrides.
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+    assert!(env_dir.path().join("db").exists());
+    assert!(!configured_dir.path().join("db").exists());
+
+    glean::shutdown();
+}
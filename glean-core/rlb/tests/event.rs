@@ -0,0 +1,113 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use glean::{private::EventMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .max_events(5)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_event(name: &str, store: &str) -> EventMetric {
+    EventMetric::new(
+        CommonMetricData {
+            name: name.into(),
+            category: "ui".into(),
+            send_in_pings: vec![store.into()],
+            ..Default::default()
+        },
+        vec!["source".into(), "target".into()],
+    )
+}
+
+#[test]
+fn events_are_recorded_with_extras() {
+    setup_glean();
+    let click = new_event("click", "store1");
+
+    click.record(None);
+    let mut extra = HashMap::new();
+    extra.insert("source".to_string(), "button".to_string());
+    click.record(Some(extra));
+
+    let events = click.test_get_value("store1").unwrap();
+    assert_eq!(2, events.len());
+    assert_eq!("ui", events[0].category);
+    assert_eq!("click", events[0].name);
+    assert_eq!(None, events[0].extra);
+    assert_eq!("button", events[1].extra.as_ref().unwrap()["source"]);
+    assert!(events[0].timestamp <= events[1].timestamp);
+}
+
+#[test]
+fn unknown_extra_keys_are_rejected() {
+    setup_glean();
+    let event = new_event("unknown_key", "store1");
+
+    let mut extra = HashMap::new();
+    extra.insert("source".to_string(), "button".to_string());
+    extra.insert("unknown".to_string(), "value".to_string());
+    event.record(Some(extra));
+
+    assert!(!event.test_has_value("store1"));
+    assert_eq!(
+        1,
+        event.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn long_extra_values_are_truncated() {
+    setup_glean();
+    let event = new_event("long_value", "store1");
+
+    let mut extra = HashMap::new();
+    extra.insert("target".to_string(), "x".repeat(150));
+    event.record(Some(extra));
+
+    let events = event.test_get_value("store1").unwrap();
+    assert_eq!("x".repeat(100), events[0].extra.as_ref().unwrap()["target"]);
+    assert_eq!(
+        1,
+        event.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
+
+#[test]
+fn events_ping_is_submitted_at_max_capacity() {
+    setup_glean();
+    let event = new_event("filling_up", "events");
+
+    for _ in 0..4 {
+        event.record(None);
+    }
+    assert_eq!(4, event.test_get_value("events").unwrap().len());
+    assert!(glean::test::pending_ping_doc_ids("events").is_empty());
+
+    // The 5th event fills up the store and the events ping is submitted.
+    event.record(None);
+    assert!(!event.test_has_value("events"));
+    assert_eq!(1, glean::test::pending_ping_doc_ids("events").len());
+}
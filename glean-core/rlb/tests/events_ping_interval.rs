@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use glean::{private::EventMetric, ClientInfoMetrics, CommonMetricData, Configuration};
+use glean_core::MockClock;
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// Reads the reasons of all pending `events` pings.
+fn events_reasons(data_path: &Path) -> Vec<String> {
+    fs::read_dir(data_path.join("pending_pings"))
+        .unwrap()
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut lines = content.lines();
+            let path = lines.next().unwrap();
+            if !path.contains("/events/") {
+                return None;
+            }
+            let body: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+            Some(body["ping_info"]["reason"].as_str().unwrap().to_string())
+        })
+        .collect()
+}
+
+/// Waits up to 5 seconds for the given number of `events` pings to be pending.
+fn wait_for_events_pings(count: usize) -> bool {
+    glean::test::wait_until(
+        || glean::test::pending_ping_doc_ids("events").len() >= count,
+        Duration::from_secs(5),
+    )
+}
+
+#[test]
+fn events_ping_is_submitted_periodically() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    let clock = Arc::new(MockClock::new());
+    glean::test::set_clock(clock.clone());
+
+    // The scheduler checks the clock every interval.
+    let interval = Duration::from_secs(1);
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .events_ping_interval(interval)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let click = EventMetric::new(
+        CommonMetricData {
+            name: "click".into(),
+            category: "ui".into(),
+            send_in_pings: vec!["events".into()],
+            ..Default::default()
+        },
+        vec![],
+    );
+    click.record(None);
+
+    // The ping isn't sent before the interval passed on the clock.
+    glean::dispatcher::block_on_queue();
+    assert!(glean::test::pending_ping_doc_ids("events").is_empty());
+
+    clock.advance(interval);
+    assert!(wait_for_events_pings(1));
+    assert_eq!(vec!["periodic"], events_reasons(dir.path()));
+    assert!(!click.test_has_value("events"));
+
+    // Shutting down stops the scheduler.
+    glean::shutdown();
+}
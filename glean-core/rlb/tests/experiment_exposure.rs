@@ -0,0 +1,64 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use glean::{ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// Reads the events of all pending pings with the given name.
+fn ping_events(data_path: &Path, ping_name: &str) -> Vec<serde_json::Value> {
+    fs::read_dir(data_path.join("pending_pings"))
+        .unwrap()
+        .flat_map(|entry| {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut lines = content.lines();
+            let path = lines.next().unwrap();
+            if !path.contains(&format!("/{}/", ping_name)) {
+                return vec![];
+            }
+            let body: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+            body["events"].as_array().cloned().unwrap_or_default()
+        })
+        .collect()
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn exposures_are_recorded_for_active_experiments() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    glean::set_experiment_active("experiment".into(), "treatment".into(), None);
+    glean::record_experiment_exposure("experiment");
+    // Not active, so this is only logged.
+    glean::record_experiment_exposure("not_enrolled");
+
+    assert!(glean::submit_ping_by_name("events", None).wait());
+    assert!(glean::submit_ping_by_name("baseline", Some("active")).wait());
+
+    for ping_name in &["events", "baseline"] {
+        let events = ping_events(dir.path(), ping_name);
+        assert_eq!(1, events.len(), "{} ping: {:?}", ping_name, events);
+        assert_eq!("nimbus", events[0]["category"]);
+        assert_eq!("exposure", events[0]["name"]);
+        assert_eq!(
+            serde_json::json!({"experiment_id": "experiment"}),
+            events[0]["extra"]
+        );
+    }
+
+    glean::shutdown();
+}
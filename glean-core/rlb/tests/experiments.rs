@@ -0,0 +1,39 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn experiment_branch_is_reported_while_active() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    assert_eq!(None, glean::test::get_experiment_branch("experiment"));
+
+    glean::set_experiment_active("experiment".into(), "treatment".into(), None);
+    assert_eq!(
+        Some("treatment".to_string()),
+        glean::test::get_experiment_branch("experiment")
+    );
+    assert_eq!(None, glean::test::get_experiment_branch("other"));
+    assert!(glean::test_is_experiment_active("experiment"));
+    assert!(!glean::test_is_experiment_active("other"));
+
+    glean::set_experiment_inactive("experiment".into());
+    assert_eq!(None, glean::test::get_experiment_branch("experiment"));
+    assert!(!glean::test_is_experiment_active("experiment"));
+
+    glean::shutdown();
+}
@@ -0,0 +1,50 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use glean::private::CounterMetric;
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn active_experiments_are_reported_in_pings() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let mut extra = HashMap::new();
+    extra.insert("type".to_string(), "rollout".to_string());
+    glean::set_experiment_active("active".into(), "branch_a".into(), Some(extra));
+    glean::set_experiment_active("ended".into(), "branch_b".into(), None);
+    glean::set_experiment_inactive("ended".into());
+
+    // The metrics ping is only submitted if it contains data.
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["metrics".into()],
+        ..Default::default()
+    });
+    counter.add(1);
+
+    let payload = glean::test::force_metrics_ping("manual").unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "active": {"branch": "branch_a", "extra": {"type": "rollout"}},
+        }),
+        payload["ping_info"]["experiments"]
+    );
+
+    glean::shutdown();
+}
@@ -0,0 +1,53 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+use serde_json::json;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn experiments_snapshot_annotates_pings() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let mut extra = HashMap::new();
+    extra.insert("source".to_string(), "test".to_string());
+
+    let mut experiments = HashMap::new();
+    experiments.insert("first".to_string(), ("control".to_string(), None));
+    experiments.insert("second".to_string(), ("treatment".to_string(), Some(extra)));
+    experiments.insert("third".to_string(), ("treatment".to_string(), None));
+    glean::test::set_experiments_snapshot(experiments);
+
+    let ping = PingType::new("annotated", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+
+    let doc_id = &glean::test::pending_ping_doc_ids("annotated")[0];
+    let content = fs::read_to_string(dir.path().join("pending_pings").join(doc_id)).unwrap();
+    let body: serde_json::Value = serde_json::from_str(content.lines().nth(1).unwrap()).unwrap();
+    assert_eq!(
+        json!({
+            "first": { "branch": "control" },
+            "second": { "branch": "treatment", "extra": { "source": "test" } },
+            "third": { "branch": "treatment" },
+        }),
+        body["ping_info"]["experiments"]
+    );
+
+    glean::shutdown();
+}
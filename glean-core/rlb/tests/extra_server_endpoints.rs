@@ -0,0 +1,91 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use glean::{
+    net::{PingUploader, UploadResult},
+    private::PingType,
+    ClientInfoMetrics, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// An uploader keeping the URLs of all upload requests.
+#[derive(Clone, Debug, Default)]
+struct UrlRecorder {
+    urls: Arc<Mutex<Vec<String>>>,
+}
+
+impl UrlRecorder {
+    /// Gets the server the last upload of the given ping was sent to.
+    fn server_for(&self, ping_name: &str) -> Option<String> {
+        let segment = format!("/submit/org-mozilla-glean-test-app/{}/", ping_name);
+        self.urls
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|url| url.find(&segment).map(|index| url[..index].to_string()))
+    }
+}
+
+impl PingUploader for UrlRecorder {
+    fn upload(
+        &self,
+        url: String,
+        _body: Vec<u8>,
+        _headers: HashMap<String, String>,
+    ) -> UploadResult {
+        self.urls.lock().unwrap().push(url);
+        UploadResult::HttpStatus(200)
+    }
+}
+
+#[test]
+fn pings_are_routed_to_their_configured_servers() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    let uploader = UrlRecorder::default();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .server_endpoint("https://default.example.com")
+        .extra_server_endpoint("crash", "https://crash.example.com")
+        .extra_server_endpoint("custom-*", "https://custom.example.com/")
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    for name in &["crash", "custom-one", "routed-default"] {
+        let ping = PingType::new(*name, true, /* send_if_empty */ true, vec![]);
+        glean::register_ping_type(&ping);
+        assert!(ping.submit(None).wait());
+    }
+
+    assert!(glean::test::wait_until(
+        || uploader.server_for("routed-default").is_some()
+            && uploader.server_for("crash").is_some()
+            && uploader.server_for("custom-one").is_some(),
+        Duration::from_secs(5)
+    ));
+    assert_eq!(
+        Some("https://crash.example.com".to_string()),
+        uploader.server_for("crash")
+    );
+    assert_eq!(
+        Some("https://custom.example.com".to_string()),
+        uploader.server_for("custom-one")
+    );
+    assert_eq!(
+        Some("https://default.example.com".to_string()),
+        uploader.server_for("routed-default")
+    );
+
+    glean::shutdown();
+}
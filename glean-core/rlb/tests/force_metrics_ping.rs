@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::CounterMetric, ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn new_counter(name: &str, lifetime: Lifetime) -> CounterMetric {
+    CounterMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["metrics".into()],
+        lifetime,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn forced_metrics_ping_contains_recorded_metrics() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let application = new_counter("application", Lifetime::Application);
+    let ping = new_counter("ping", Lifetime::Ping);
+    application.add(3);
+    ping.add(1);
+
+    let payload = glean::test::force_metrics_ping("today").unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!("today", payload["ping_info"]["reason"]);
+    assert_eq!(3, payload["metrics"]["counter"]["test.application"]);
+    assert_eq!(1, payload["metrics"]["counter"]["test.ping"]);
+
+    // Only application lifetime data is kept for the next ping.
+    let payload = glean::test::force_metrics_ping("tomorrow").unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(3, payload["metrics"]["counter"]["test.application"]);
+    assert!(payload["metrics"]["counter"].get("test.ping").is_none());
+}
@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+use std::time::Duration;
+
+use glean::{private::GaugeMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn gauge_goes_up_and_down() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    initialize(dir.path());
+
+    let gauge = GaugeMetric::new(CommonMetricData {
+        name: "gauge".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    });
+
+    gauge.set(0);
+    assert_eq!(Some(0), gauge.test_get_value("store1"));
+
+    gauge.add(4);
+    gauge.add(-6);
+    assert_eq!(Some(-2), gauge.test_get_value("store1"));
+
+    gauge.set(i64::MIN);
+    gauge.add(-1);
+    assert_eq!(Some(i64::MIN), gauge.test_get_value("store1"));
+    assert_eq!(
+        1,
+        gauge.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+
+    glean::shutdown();
+}
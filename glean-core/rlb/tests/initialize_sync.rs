@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{
+    private::BooleanMetric, ClientInfoMetrics, CommonMetricData, Configuration, InitializeError,
+    Lifetime,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn initialize_sync_reports_the_outcome() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(!glean::is_initialized());
+    assert!(!glean::is_init_complete());
+
+    // A file can't be used as the data directory.
+    let file_path = dir.path().join("notadir");
+    std::fs::write(&file_path, "test").unwrap();
+    let cfg = Configuration::builder()
+        .data_path(&file_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    assert!(matches!(
+        glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), TIMEOUT),
+        Err(InitializeError::InvalidDataPath(..))
+    ));
+    assert!(!glean::is_initialized());
+
+    // The data directory is usable, but a file can't be used as the database directory.
+    let data_path = dir.path().join("nodb");
+    std::fs::create_dir(&data_path).unwrap();
+    std::fs::write(data_path.join("db"), "test").unwrap();
+    let cfg = Configuration::builder()
+        .data_path(&data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    assert_eq!(
+        Err(InitializeError::Failed),
+        glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), TIMEOUT)
+    );
+    assert!(glean::is_initialized());
+    assert!(!glean::is_init_complete());
+
+    // Shutting down after a failed initialization doesn't block.
+    glean::shutdown();
+    assert!(!glean::is_initialized());
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    assert_eq!(
+        Ok(()),
+        glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), TIMEOUT)
+    );
+    assert!(glean::is_initialized());
+    assert!(glean::is_init_complete());
+
+    let metric = BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Application,
+        ..Default::default()
+    });
+    metric.set(true);
+    assert_eq!(Some(true), metric.test_get_value("store1"));
+
+    glean::shutdown();
+    assert!(!glean::is_initialized());
+    assert!(!glean::is_init_complete());
+}
@@ -0,0 +1,44 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+
+use glean::{ClientInfoMetrics, Configuration, InitializeError};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test.
+#[test]
+fn initialize_fails_right_away_for_an_unusable_data_path() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // A file is in the way of the data directory.
+    let file_path = dir.path().join("notadir");
+    fs::write(&file_path, "test").unwrap();
+    let cfg = Configuration::builder()
+        .data_path(file_path.join("glean"))
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    match glean::initialize(cfg, ClientInfoMetrics::unknown()) {
+        Err(InitializeError::InvalidDataPath(path, _)) => {
+            assert_eq!(file_path.join("glean"), path)
+        }
+        other => panic!("Expected an invalid data path, got {:?}", other),
+    }
+    assert!(!glean::is_initialized());
+
+    // Glean can still be initialized with a usable data path.
+    let cfg = Configuration::builder()
+        .data_path(dir.path().join("glean"))
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+    assert!(glean::is_initialized());
+
+    glean::shutdown();
+}
@@ -0,0 +1,143 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::{BooleanMetric, CounterMetric, LabeledMetric},
+    ClientInfoMetrics, CommonMetricData, Configuration, ErrorType,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn meta(name: &str) -> CommonMetricData {
+    CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn static_labels_are_recorded() {
+    setup_glean();
+    let labeled: LabeledMetric<CounterMetric> = LabeledMetric::new(
+        meta("static_counter"),
+        Some(vec!["label1".into(), "label2".into()]),
+    );
+
+    labeled.get("label1").add(1);
+    labeled.get("label2").add(2);
+    labeled.get("label1").add(3);
+    // Labels not in the list are recorded in the `__other__` bucket, without an error.
+    labeled.get("not_there").add(4);
+
+    assert_eq!(Some(4), labeled.get("label1").test_get_value("store1"));
+    assert_eq!(Some(2), labeled.get("label2").test_get_value("store1"));
+    assert_eq!(Some(4), labeled.get("__other__").test_get_value("store1"));
+    assert_eq!(
+        0,
+        labeled.test_get_num_recorded_errors(ErrorType::InvalidLabel, None)
+    );
+}
+
+#[test]
+fn dynamic_labels_are_recorded() {
+    setup_glean();
+    let labeled: LabeledMetric<BooleanMetric> = LabeledMetric::new(meta("dynamic_boolean"), None);
+
+    labeled.get("enabled").set(true);
+    labeled.get("disabled").set(false);
+
+    assert_eq!(Some(true), labeled.get("enabled").test_get_value("store1"));
+    assert_eq!(
+        Some(false),
+        labeled.get("disabled").test_get_value("store1")
+    );
+    assert_eq!(None, labeled.get("__other__").test_get_value("store1"));
+}
+
+#[test]
+fn invalid_dynamic_labels_go_to_other() {
+    setup_glean();
+    let labeled: LabeledMetric<CounterMetric> = LabeledMetric::new(meta("invalid_labels"), None);
+
+    labeled.get("NotSnakeCase").add(1);
+    labeled.get("1.not_fine").add(1);
+    labeled.get("this.$isnotfine").add(1);
+
+    assert_eq!(Some(3), labeled.get("__other__").test_get_value("store1"));
+    assert_eq!(
+        3,
+        labeled.test_get_num_recorded_errors(ErrorType::InvalidLabel, None)
+    );
+}
+
+#[test]
+fn dynamic_labels_are_limited() {
+    setup_glean();
+    let labeled: LabeledMetric<CounterMetric> = LabeledMetric::new(meta("many_labels"), None);
+
+    for i in 0..=16 {
+        labeled.get(&format!("label_{}", i)).add(1);
+    }
+
+    for i in 0..16 {
+        assert_eq!(
+            Some(1),
+            labeled
+                .get(&format!("label_{}", i))
+                .test_get_value("store1")
+        );
+    }
+    // The 17th label is recorded in the `__other__` bucket.
+    assert_eq!(Some(1), labeled.get("__other__").test_get_value("store1"));
+}
+
+#[test]
+fn labeled_counter_values_can_be_read_per_label() {
+    setup_glean();
+    let labeled: LabeledMetric<CounterMetric> = LabeledMetric::new(meta("per_label"), None);
+
+    labeled.get("first").add(1);
+    labeled.get("second").add(5);
+    labeled.get("first").add(2);
+
+    assert_eq!(
+        Some(3),
+        glean::test::get_labeled_counter(&labeled, "first", "store1")
+    );
+    assert_eq!(
+        Some(5),
+        glean::test::get_labeled_counter(&labeled, "second", "store1")
+    );
+    assert_eq!(
+        None,
+        glean::test::get_labeled_counter(&labeled, "unused", "store1")
+    );
+    assert_eq!(
+        None,
+        glean::test::get_labeled_counter(&labeled, "first", "other_store")
+    );
+}
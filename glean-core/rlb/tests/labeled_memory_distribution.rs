@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{
+    private::{LabeledMetric, MemoryDistributionMetric},
+    ClientInfoMetrics, CommonMetricData, Configuration, MemoryUnit,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn labeled_memory_distributions_are_sent_per_label() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .enable_metrics_ping(false)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let labeled: LabeledMetric<MemoryDistributionMetric> = LabeledMetric::with_memory_unit(
+        CommonMetricData {
+            name: "heap".into(),
+            category: "test".into(),
+            send_in_pings: vec!["metrics".into()],
+            ..Default::default()
+        },
+        MemoryUnit::Kilobyte,
+        Some(vec!["small".into(), "medium".into(), "large".into()]),
+    );
+
+    let samples: &[(&str, &[u64])] = &[
+        ("small", &[1, 2, 3]),
+        ("medium", &[10, 20, 30, 40]),
+        ("large", &[100, 200, 300]),
+    ];
+    for (label, values) in samples {
+        for value in values.iter() {
+            labeled.get(label).accumulate(*value);
+        }
+    }
+
+    let payload = glean::test::force_metrics_ping("today").unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    let section = &payload["metrics"]["labeled_memory_distribution"]["test.heap"];
+    assert_eq!(3, section.as_object().unwrap().len());
+
+    for (label, values) in samples {
+        let histogram = &section[*label];
+        let sum: u64 = values.iter().map(|value| value * 1024).sum();
+        assert_eq!(sum, histogram["sum"], "sum of {}", label);
+
+        let count: u64 = histogram["values"]
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|count| count.as_u64().unwrap())
+            .sum();
+        assert_eq!(values.len() as u64, count, "samples of {}", label);
+    }
+}
@@ -0,0 +1,53 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{
+    net::UploadResult, private::PingType, test::MockUploader, ClientInfoMetrics, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// Waits up to 5 seconds for an upload result to be processed.
+fn wait_for_upload_result() -> Option<(String, UploadResult)> {
+    glean::test::wait_until(
+        || glean::test::last_upload_result().is_some(),
+        Duration::from_secs(5),
+    );
+    glean::test::last_upload_result()
+}
+
+#[test]
+fn last_upload_result_is_the_processed_response() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let uploader = MockUploader::new();
+    uploader.push_result(UploadResult::HttpStatus(200));
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .upload_enabled(true)
+        .uploader(uploader)
+        .build()
+        .unwrap();
+    glean::test::use_deterministic_doc_ids();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    assert_eq!(None, glean::test::last_upload_result());
+
+    let ping = PingType::new("uploaded", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+
+    assert_eq!(
+        Some((
+            "00000000-0000-0000-0000-000000000001".to_string(),
+            UploadResult::HttpStatus(200)
+        )),
+        wait_for_upload_result()
+    );
+}
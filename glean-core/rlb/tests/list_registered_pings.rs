@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test.
+#[test]
+fn registered_pings_are_listed() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Nothing is known before initialization.
+    assert!(glean::list_registered_pings().is_empty());
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let names = glean::list_registered_pings();
+    for name in &["baseline", "metrics", "events", "deletion-request"] {
+        assert!(names.contains(&name.to_string()), "{} is not listed", name);
+    }
+
+    let first = PingType::new("first", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&first);
+    let second = PingType::new("second", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&second);
+    glean::dispatcher::block_on_queue();
+
+    let names = glean::list_registered_pings();
+    assert!(names.ends_with(&["first".to_string(), "second".to_string()]));
+    assert_eq!(
+        1,
+        names
+            .iter()
+            .filter(|name| *name == "deletion-request")
+            .count()
+    );
+
+    glean::shutdown();
+}
@@ -0,0 +1,88 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::MemoryDistributionMetric, ClientInfoMetrics, CommonMetricData, Configuration,
+    ErrorType, MemoryUnit,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_memory_distribution(name: &str, memory_unit: MemoryUnit) -> MemoryDistributionMetric {
+    MemoryDistributionMetric::new(
+        CommonMetricData {
+            name: name.into(),
+            category: "test".into(),
+            send_in_pings: vec!["store1".into()],
+            ..Default::default()
+        },
+        memory_unit,
+    )
+}
+
+#[test]
+fn samples_are_converted_to_bytes() {
+    setup_glean();
+    let metric = new_memory_distribution("kilobytes", MemoryUnit::Kilobyte);
+
+    metric.accumulate(1);
+    metric.accumulate(2);
+
+    let data = metric.test_get_value("store1").unwrap();
+    assert_eq!(3 * 1024, data.sum);
+    assert_eq!(2, data.values.values().sum::<u64>());
+}
+
+#[test]
+fn too_large_samples_are_clamped() {
+    setup_glean();
+    let metric = new_memory_distribution("too_large", MemoryUnit::Gigabyte);
+
+    // The maximum is 1 terabyte.
+    metric.accumulate(2048);
+
+    let data = metric.test_get_value("store1").unwrap();
+    assert_eq!(1 << 40, data.sum);
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn negative_samples_are_dropped() {
+    setup_glean();
+    let metric = new_memory_distribution("negative", MemoryUnit::Byte);
+
+    metric.accumulate_samples_signed(vec![-1, 10, -2, 20]);
+
+    let data = metric.test_get_value("store1").unwrap();
+    assert_eq!(30, data.sum);
+    assert_eq!(2, data.values.values().sum::<u64>());
+    assert_eq!(
+        2,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
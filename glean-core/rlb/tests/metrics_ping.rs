@@ -0,0 +1,118 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::Timelike;
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path, enable_metrics_ping: bool) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .enable_metrics_ping(enable_metrics_ping)
+        .build()
+        .unwrap();
+
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+/// Reads the reasons of all pending `metrics` pings.
+fn metrics_reasons(data_path: &Path) -> Vec<String> {
+    let pending_pings = match fs::read_dir(data_path.join("pending_pings")) {
+        Ok(pending_pings) => pending_pings,
+        Err(_) => return vec![],
+    };
+    pending_pings
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut lines = content.lines();
+            let path = lines.next().unwrap();
+            if !path.contains("/metrics/") {
+                return None;
+            }
+            let body: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+            Some(body["ping_info"]["reason"].as_str().unwrap().to_string())
+        })
+        .collect()
+}
+
+/// Records data for the `metrics` ping and sets when it was last sent,
+/// with a Glean instance that is gone before the Glean bindings are initialized.
+///
+/// The data path must not have been used by the Glean bindings yet,
+/// as they keep the database open after shutting down.
+fn prepare_last_sent(data_path: &Path, days_ago: i64) {
+    let cfg = glean_core::Configuration {
+        data_path: data_path.into(),
+        application_id: GLOBAL_APPLICATION_ID.into(),
+        language_binding_name: "Rust".into(),
+        upload_enabled: true,
+        max_events: None,
+        delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
+    };
+    let glean = glean_core::Glean::new(cfg).unwrap();
+
+    let metric = glean_core::metrics::BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["metrics".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    });
+    metric.set(&glean, true);
+
+    let now = chrono::Local::now();
+    let last_sent = now.with_timezone(now.offset()) - chrono::Duration::days(days_ago);
+    glean.set_metrics_ping_last_sent(last_sent);
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn metrics_ping_is_checked_on_startup() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    // The first run only records the current time.
+    let first_run = tempfile::tempdir().unwrap();
+    initialize(first_run.path(), true);
+    assert!(metrics_reasons(first_run.path()).is_empty());
+    glean::shutdown();
+
+    // The ping was sent today as far as Glean knows.
+    let sent_today = tempfile::tempdir().unwrap();
+    prepare_last_sent(sent_today.path(), 0);
+    initialize(sent_today.path(), true);
+    assert!(metrics_reasons(sent_today.path()).is_empty());
+    glean::shutdown();
+
+    // The ping is overdue once the scheduled hour passed.
+    let sent_before = tempfile::tempdir().unwrap();
+    prepare_last_sent(sent_before.path(), 2);
+    initialize(sent_before.path(), true);
+    if chrono::Local::now().hour() >= 4 {
+        assert_eq!(vec!["overdue"], metrics_reasons(sent_before.path()));
+    } else {
+        assert!(metrics_reasons(sent_before.path()).is_empty());
+    }
+    glean::shutdown();
+
+    // Overdue pings are left alone if the scheduler is disabled.
+    let disabled = tempfile::tempdir().unwrap();
+    prepare_last_sent(disabled.path(), 2);
+    initialize(disabled.path(), false);
+    assert!(metrics_reasons(disabled.path()).is_empty());
+    glean::shutdown();
+}
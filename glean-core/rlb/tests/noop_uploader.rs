@@ -0,0 +1,39 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{
+    net::{NoopUploader, UploadResult},
+    private::PingType,
+    ClientInfoMetrics, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn noop_uploader_drops_pings_as_sent() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(NoopUploader)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("dropped", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+
+    assert!(glean::test::wait_until(
+        || glean::test::last_upload_result().is_some(),
+        Duration::from_secs(5),
+    ));
+    let (_, result) = glean::test::last_upload_result().unwrap();
+    assert_eq!(UploadResult::HttpStatus(200), result);
+    assert!(glean::test::pending_ping_doc_ids("dropped").is_empty());
+}
@@ -0,0 +1,146 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::json;
+
+use glean::{private::ObjectMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_object<T: Serialize + JsonSchema>(name: &str) -> ObjectMetric<T> {
+    ObjectMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    })
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Balloon {
+    colour: String,
+    #[schemars(range(min = 1, max = 100))]
+    diameter: u32,
+    label: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Party {
+    host: String,
+    balloons: Vec<Balloon>,
+}
+
+fn balloon(colour: &str, diameter: u32) -> Balloon {
+    Balloon {
+        colour: colour.into(),
+        diameter,
+        label: None,
+    }
+}
+
+#[test]
+fn nested_objects_are_recorded() {
+    setup_glean();
+    let metric = new_object::<Party>("nested");
+
+    metric.set(Party {
+        host: "glean".into(),
+        balloons: vec![
+            balloon("red", 10),
+            Balloon {
+                label: Some("Happy birthday".into()),
+                ..balloon("blue", 20)
+            },
+        ],
+    });
+
+    assert_eq!(
+        Some(json!({
+            "host": "glean",
+            "balloons": [
+                {"colour": "red", "diameter": 10, "label": null},
+                {"colour": "blue", "diameter": 20, "label": "Happy birthday"},
+            ],
+        })),
+        metric.test_get_value("store1")
+    );
+    assert_eq!(
+        0,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn arrays_are_recorded() {
+    setup_glean();
+    let metric = new_object::<Vec<Balloon>>("array");
+
+    metric.set(vec![balloon("green", 30)]);
+
+    assert_eq!(
+        Some(json!([{"colour": "green", "diameter": 30, "label": null}])),
+        metric.test_get_value("store1")
+    );
+}
+
+#[test]
+fn values_not_matching_the_schema_are_rejected() {
+    setup_glean();
+    let metric = new_object::<Balloon>("mismatch");
+
+    metric.set(balloon("red", 10));
+    // The schema limits the diameter to 100.
+    metric.set(balloon("red", 500));
+
+    assert_eq!(
+        Some(json!({"colour": "red", "diameter": 10, "label": null})),
+        metric.test_get_value("store1")
+    );
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn null_values_are_rejected() {
+    setup_glean();
+    let metric = new_object::<Option<Balloon>>("null");
+
+    // `None` matches the schema, but is not an object.
+    metric.set(None);
+    assert_eq!(None, metric.test_get_value("store1"));
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+
+    metric.set(Some(balloon("yellow", 40)));
+    assert_eq!(
+        Some(json!({"colour": "yellow", "diameter": 40, "label": null})),
+        metric.test_get_value("store1")
+    );
+}
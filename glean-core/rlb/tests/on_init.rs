@@ -0,0 +1,60 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use glean::{ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Builds a configuration sending the outcome of the initialization to the returned receiver.
+fn config(data_path: &Path) -> (Configuration, Receiver<bool>) {
+    let (sender, outcomes) = channel();
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .on_init(move |result| sender.send(result.is_ok()).unwrap())
+        .build()
+        .unwrap();
+    (cfg, outcomes)
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn on_init_is_called_once_with_the_outcome() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // The data path is checked before initializing in the background.
+    let file_path = dir.path().join("notadir");
+    std::fs::write(&file_path, "test").unwrap();
+    let (cfg, outcomes) = config(&file_path);
+    assert!(glean::initialize(cfg, ClientInfoMetrics::unknown()).is_err());
+    assert_eq!(Ok(false), outcomes.try_recv());
+
+    // Glean fails to open the database in the background.
+    let data_path = dir.path().join("nodb");
+    std::fs::create_dir(&data_path).unwrap();
+    std::fs::write(data_path.join("db"), "test").unwrap();
+    let (cfg, outcomes) = config(&data_path);
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+    assert_eq!(Ok(false), outcomes.recv_timeout(TIMEOUT));
+    glean::shutdown();
+    // The sender was dropped with the callback.
+    assert!(outcomes.recv().is_err());
+
+    let (cfg, outcomes) = config(&dir.path().join("glean"));
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+    assert_eq!(Ok(true), outcomes.recv_timeout(TIMEOUT));
+
+    // Initializing again with the same configuration doesn't call it again.
+    glean::test::simulate_restart().unwrap();
+    assert!(outcomes.try_recv().is_err());
+
+    glean::shutdown();
+}
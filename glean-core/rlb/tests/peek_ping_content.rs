@@ -0,0 +1,59 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::private::{CounterMetric, PingType};
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is initialized.
+#[test]
+fn peeking_shows_the_ping_content_without_consuming_it() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let ping = PingType::new("peeked", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+    assert_eq!(None, glean::peek_ping_content("peeked"));
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    assert_eq!(None, glean::peek_ping_content("unregistered"));
+    // Nothing recorded yet.
+    assert_eq!(None, glean::peek_ping_content("peeked"));
+
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["peeked".into()],
+        ..Default::default()
+    });
+    counter.add(3);
+    // Recording is asynchronous, peeking isn't.
+    assert_eq!(Some(3), counter.test_get_value("peeked"));
+
+    let content = glean::peek_ping_content("peeked").unwrap();
+    assert_eq!(3, content["metrics"]["counter"]["test.counter"]);
+    assert_eq!(0, content["ping_info"]["seq"]);
+    assert!(content["client_info"]["client_id"].is_string());
+
+    // The data is still there, for peeking again and for the real submission.
+    assert_eq!(
+        content["metrics"],
+        glean::peek_ping_content("peeked").unwrap()["metrics"]
+    );
+    assert!(ping.submit(None).wait());
+    assert_eq!(None, glean::peek_ping_content("peeked"));
+
+    glean::shutdown();
+    assert_eq!(None, glean::peek_ping_content("peeked"));
+}
@@ -0,0 +1,47 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn pending_ping_count_follows_the_queue() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Nothing is pending before Glean is initialized.
+    assert_eq!(0, glean::pending_ping_count());
+    assert_eq!(None, glean::max_pending_pings());
+
+    // Without an uploader, pings stay pending.
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .max_pending_pings(10)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+    assert_eq!(Some(10), glean::max_pending_pings());
+
+    let before = glean::pending_ping_count();
+    let ping = PingType::new("counted", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    for expected in 1..=3 {
+        assert!(ping.submit(None).wait());
+        assert_eq!(before + expected, glean::pending_ping_count());
+    }
+
+    // Lowering the maximum deletes the oldest pings.
+    glean::set_max_pending_pings(1);
+    assert!(glean::test::wait_until(
+        || glean::max_pending_pings() == Some(1),
+        Duration::from_secs(5)
+    ));
+    assert_eq!(1, glean::pending_ping_count());
+
+    glean::shutdown();
+}
@@ -0,0 +1,45 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn pending_ping_doc_ids_are_in_queue_order() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::test::use_deterministic_doc_ids();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("queued", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    let other = PingType::new("other", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&other);
+
+    assert!(ping.submit(None).wait());
+    assert!(other.submit(None).wait());
+    assert!(ping.submit(None).wait());
+
+    assert_eq!(
+        vec![
+            "00000000-0000-0000-0000-000000000001",
+            "00000000-0000-0000-0000-000000000003",
+        ],
+        glean::test::pending_ping_doc_ids("queued")
+    );
+    assert_eq!(
+        vec!["00000000-0000-0000-0000-000000000002"],
+        glean::test::pending_ping_doc_ids("other")
+    );
+    assert!(glean::test::pending_ping_doc_ids("unknown").is_empty());
+}
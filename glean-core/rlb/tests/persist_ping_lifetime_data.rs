@@ -0,0 +1,63 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+use std::time::Duration;
+
+use glean::private::CounterMetric;
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path, delay_ping_lifetime_io: bool) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .delay_ping_lifetime_io(delay_ping_lifetime_io)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+fn ping_counter(name: &str) -> CounterMetric {
+    CounterMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    })
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is restarted.
+#[test]
+fn persisted_ping_lifetime_data_survives_a_restart() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    initialize(dir.path(), true);
+
+    let persisted = ping_counter("persisted");
+    persisted.add(3);
+    assert!(glean::persist_ping_lifetime_data().is_ok());
+
+    // Not persisted, so it is lost when the application is killed.
+    let in_memory = ping_counter("in_memory");
+    in_memory.add(5);
+
+    glean::test::simulate_restart().unwrap();
+
+    assert_eq!(Some(3), persisted.test_get_value("store1"));
+    assert_eq!(None, in_memory.test_get_value("store1"));
+
+    glean::shutdown();
+
+    // Without delayed I/O there is nothing to persist.
+    initialize(dir.path(), false);
+    persisted.add(1);
+    assert!(glean::persist_ping_lifetime_data().is_ok());
+    assert_eq!(Some(4), persisted.test_get_value("store1"));
+
+    glean::shutdown();
+}
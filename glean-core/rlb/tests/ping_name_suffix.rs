@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{
+    private::{CounterMetric, PingType},
+    test::MockUploader,
+    ClientInfoMetrics, CommonMetricData, Configuration, Lifetime,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn ping_name_suffix_is_only_used_for_upload() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    let uploader = MockUploader::new();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .ping_name_suffix("staging")
+        .build()
+        .unwrap();
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    let ping = PingType::new("suffixed", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+
+    // Data is stored for the ping as defined.
+    let metric = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["suffixed".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    });
+    metric.add(1);
+    assert_eq!(Some(1), metric.test_get_value("suffixed"));
+
+    assert!(ping.submit(None).wait());
+    assert_eq!(None, metric.test_get_value("suffixed"));
+
+    // The ping is uploaded with the suffixed name.
+    assert!(glean::test::wait_until(
+        || uploader.call_count() >= 1,
+        Duration::from_secs(5)
+    ));
+    assert_eq!(1, uploader.attempts_for("suffixed-staging"));
+    assert_eq!(0, uploader.attempts_for("suffixed"));
+}
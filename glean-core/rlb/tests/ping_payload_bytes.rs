@@ -0,0 +1,47 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::io::Read;
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn payload_bytes_decompress_to_the_ping_payload() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Without an uploader, submitted pings stay pending.
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("custom", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(glean::test::ping_payload_bytes("custom").is_none());
+    assert!(ping.submit(None).wait());
+
+    let bytes = glean::test::ping_payload_bytes("custom").unwrap();
+    let mut payload = String::new();
+    GzDecoder::new(&bytes[..])
+        .read_to_string(&mut payload)
+        .unwrap();
+
+    // The pending ping file holds the upload path and the JSON payload.
+    let doc_id = &glean::test::pending_ping_doc_ids("custom")[0];
+    let content = fs::read_to_string(dir.path().join("pending_pings").join(doc_id)).unwrap();
+    let expected: serde_json::Value =
+        serde_json::from_str(content.lines().nth(1).unwrap()).unwrap();
+    let actual: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(expected, actual);
+
+    glean::shutdown();
+}
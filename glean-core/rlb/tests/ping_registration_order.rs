@@ -0,0 +1,60 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is initialized.
+#[test]
+fn pings_registered_before_init_keep_their_order() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let pings: Vec<_> = ["ping-a", "ping-b", "ping-c"]
+        .iter()
+        .map(|name| PingType::new(*name, true, /* send_if_empty */ true, vec![]))
+        .collect();
+    for ping in &pings {
+        glean::register_ping_type(ping);
+    }
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    // The pings registered before init are present right after it.
+    for name in &["ping-a", "ping-b", "ping-c"] {
+        assert!(
+            glean::test_has_ping_type(name),
+            "{} is not registered",
+            name
+        );
+    }
+
+    let late = PingType::new("ping-late", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&late);
+
+    let names = glean::test_get_registered_ping_names();
+    let custom: Vec<_> = names
+        .iter()
+        .filter(|name| name.starts_with("ping-"))
+        .collect();
+    assert_eq!(vec!["ping-a", "ping-b", "ping-c", "ping-late"], custom);
+
+    // The builtin pings are registered first.
+    let first_custom = names.iter().position(|name| name == "ping-a").unwrap();
+    assert!(names[..first_custom].contains(&"baseline".to_string()));
+
+    // The pings registered before init can be submitted right away.
+    assert!(pings[1].submit(None).wait());
+
+    glean::shutdown();
+}
@@ -0,0 +1,96 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#![cfg(feature = "prometheus-export")]
+
+use std::time::Duration;
+
+use glean::{
+    private::{
+        CounterMetric, GaugeMetric, LabeledMetric, MemoryDistributionMetric,
+        TimingDistributionMetric,
+    },
+    ClientInfoMetrics, CommonMetricData, Configuration, Lifetime, MemoryUnit, TimeUnit,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn meta(name: &str, lifetime: Lifetime) -> CommonMetricData {
+    CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime,
+        ..Default::default()
+    }
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to check the export before and after initialization.
+#[test]
+fn stored_metrics_are_exported() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // Nothing can be exported before Glean is initialized.
+    assert_eq!("", glean::export_to_prometheus());
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let counter = CounterMetric::new(meta("counter", Lifetime::Ping));
+    let gauge = GaugeMetric::new(meta("gauge", Lifetime::Application));
+    let labeled: LabeledMetric<CounterMetric> =
+        LabeledMetric::new(meta("labeled_counter", Lifetime::User), None);
+    let timing_distribution = TimingDistributionMetric::new(
+        meta("timing_distribution", Lifetime::Ping),
+        TimeUnit::Nanosecond,
+    );
+    let memory_distribution = MemoryDistributionMetric::new(
+        meta("memory_distribution", Lifetime::Ping),
+        MemoryUnit::Byte,
+    );
+
+    counter.add(2);
+    gauge.set(-3);
+    labeled.get("label_1").add(4);
+    timing_distribution.accumulate_samples_signed(vec![1, 2]);
+    memory_distribution.accumulate(5);
+    glean::dispatcher::block_on_queue();
+
+    let output = glean::export::prometheus::export_to_prometheus();
+    let lines: Vec<_> = output.lines().collect();
+
+    assert!(lines.contains(&"# TYPE test_counter counter"));
+    assert!(lines.contains(&"test_counter{ping=\"store1\",lifetime=\"ping\"} 2"));
+    assert!(lines.contains(&"# TYPE test_gauge gauge"));
+    assert!(lines.contains(&"test_gauge{ping=\"store1\"} -3"));
+    assert!(lines.contains(&"# TYPE test_labeled_counter counter"));
+    assert!(lines.contains(&"test_labeled_counter{ping=\"store1\",label=\"label_1\"} 4"));
+
+    assert!(lines.contains(&"# TYPE test_timing_distribution summary"));
+    assert!(lines.contains(&"test_timing_distribution_sum{ping=\"store1\",lifetime=\"ping\"} 3"));
+    assert!(lines.contains(&"test_timing_distribution_count{ping=\"store1\",lifetime=\"ping\"} 2"));
+
+    assert!(lines.contains(&"# TYPE test_memory_distribution histogram"));
+    assert!(lines.contains(
+        &"test_memory_distribution_bucket{ping=\"store1\",lifetime=\"ping\",le=\"+Inf\"} 1"
+    ));
+    assert!(lines.contains(&"test_memory_distribution_sum{ping=\"store1\",lifetime=\"ping\"} 5"));
+
+    // Every family is announced once, before its samples.
+    let type_lines = lines.iter().filter(|l| l.starts_with("# TYPE ")).count();
+    let mut names: Vec<_> = lines
+        .iter()
+        .filter(|l| l.starts_with("# TYPE "))
+        .map(|l| l.split(' ').nth(2).unwrap())
+        .collect();
+    names.dedup();
+    assert_eq!(type_lines, names.len());
+
+    glean::shutdown();
+}
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+use std::time::Duration;
+
+use glean::{
+    private::QuantityMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType,
+    Lifetime,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+fn new_quantity(name: &str, lifetime: Lifetime) -> QuantityMetric {
+    QuantityMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime,
+        ..Default::default()
+    })
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn quantity_is_set_and_respects_its_lifetime() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    initialize(dir.path());
+
+    let user = new_quantity("user", Lifetime::User);
+    let application = new_quantity("application", Lifetime::Application);
+
+    // Setting a value overwrites the previous one.
+    user.set(10);
+    user.set(7);
+    assert_eq!(Some(7), user.test_get_value("store1"));
+
+    // Negative values are rejected, keeping the previous value.
+    user.set(-1);
+    assert_eq!(Some(7), user.test_get_value("store1"));
+    assert_eq!(
+        1,
+        user.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+
+    application.set(0);
+    assert_eq!(Some(0), application.test_get_value("store1"));
+
+    // Adding accumulates on the current value.
+    user.add(3);
+    user.add(5);
+    assert_eq!(Some(15), user.test_get_value("store1"));
+
+    glean::shutdown();
+    initialize(dir.path());
+
+    // Only the `user` lifetime value survives a restart.
+    assert_eq!(Some(15), user.test_get_value("store1"));
+    assert_eq!(None, application.test_get_value("store1"));
+
+    glean::shutdown();
+}
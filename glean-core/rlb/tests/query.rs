@@ -0,0 +1,62 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#![cfg(feature = "expose-runtime-values")]
+
+use std::time::Duration;
+
+use glean::{
+    private::{BooleanMetric, CounterMetric, TimespanMetric, TimingDistributionMetric},
+    query, ClientInfoMetrics, CommonMetricData, Configuration, TimeUnit,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn meta(name: &str) -> CommonMetricData {
+    CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    }
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to check the values before and after initialization.
+#[test]
+fn stored_values_can_be_queried() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let boolean = BooleanMetric::new(meta("boolean"));
+    let counter = CounterMetric::new(meta("counter"));
+    let timespan = TimespanMetric::new(meta("timespan"), TimeUnit::Millisecond);
+    let timing_distribution =
+        TimingDistributionMetric::new(meta("timing_distribution"), TimeUnit::Nanosecond);
+
+    // Nothing can be read before Glean is initialized.
+    boolean.set(true);
+    assert_eq!(None, query::query_boolean(&boolean));
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    counter.add(2);
+    timespan.set_raw(Duration::from_millis(5), false);
+    timing_distribution.accumulate_samples_signed(vec![1, 2]);
+    glean::dispatcher::block_on_queue();
+
+    assert_eq!(Some(true), query::query_boolean(&boolean));
+    assert_eq!(Some(2), query::query_counter(&counter));
+    assert_eq!(Some(5), query::query_timespan(&timespan));
+    assert_eq!(
+        Some(3),
+        query::query_timing_distribution(&timing_distribution).map(|data| data.sum)
+    );
+
+    glean::shutdown();
+}
@@ -0,0 +1,98 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use glean::{private::RateMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+fn new_rate(name: &str) -> RateMetric {
+    RateMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["metrics".into()],
+        ..Default::default()
+    })
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn rate_records_numerator_and_denominator() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    initialize(dir.path());
+
+    // A zero denominator is still reported.
+    let no_denominator = new_rate("no_denominator");
+    no_denominator.add_to_numerator(2);
+    assert_eq!(Some((2, 0)), no_denominator.test_get_value("metrics"));
+
+    // Negative amounts are rejected.
+    no_denominator.add_to_numerator(-1);
+    no_denominator.add_to_denominator(-1);
+    assert_eq!(Some((2, 0)), no_denominator.test_get_value("metrics"));
+    assert_eq!(
+        2,
+        no_denominator.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+
+    // Large values accumulate, saturating at `i32::MAX`.
+    let large = new_rate("large");
+    for _ in 0..3 {
+        large.add_to_numerator(i32::MAX / 2);
+        large.add_to_denominator(1_000_000_000);
+    }
+    assert_eq!(Some((i32::MAX, i32::MAX)), large.test_get_value("metrics"));
+    assert_eq!(
+        2,
+        large.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+
+    // Recording from several threads at once adds up.
+    let shared = new_rate("shared");
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                for _ in 0..25 {
+                    shared.add_to_numerator(1);
+                    shared.add_to_denominator(2);
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(Some((100, 200)), shared.test_get_value("metrics"));
+
+    let payload = glean::test::force_metrics_ping("manual").unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    let rates = &payload["metrics"]["rate"];
+    assert_eq!(
+        serde_json::json!({"numerator": 2, "denominator": 0}),
+        rates["test.no_denominator"]
+    );
+    assert_eq!(
+        serde_json::json!({"numerator": 100, "denominator": 200}),
+        rates["test.shared"]
+    );
+
+    glean::shutdown();
+}
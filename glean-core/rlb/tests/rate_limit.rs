@@ -0,0 +1,43 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::{Duration, Instant};
+
+use glean::{private::PingType, test::MockUploader, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn uploads_wait_for_the_rate_limiting_interval() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let uploader = MockUploader::new();
+    let interval = Duration::from_secs(1);
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .enable_metrics_ping(false)
+        .rate_limit(1, interval)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("limited", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+
+    let start = Instant::now();
+    assert!(ping.submit(None).wait());
+    assert!(ping.submit(None).wait());
+
+    // Only one upload is allowed per interval, so the second one
+    // happens after waiting for the time suggested by glean-core.
+    assert!(glean::test::wait_until(
+        || uploader.attempts_for("limited") == 2,
+        Duration::from_secs(10),
+    ));
+    assert!(start.elapsed() >= interval);
+    assert!(glean::test::pending_ping_doc_ids("limited").is_empty());
+}
@@ -0,0 +1,162 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#![cfg(feature = "reqwest-uploader")]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+
+use glean::net::{PingUploader, ReqwestUploader, UploadResult};
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// A request as received by the test server.
+struct Request {
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// Starts a server answering a single request with the given status.
+///
+/// Returns its URL and a receiver for the request.
+fn serve_once(status: u16) -> (String, mpsc::Receiver<Request>) {
+    serve_once_with_headers(status, "")
+}
+
+/// Starts a server answering a single request with the given status and extra response headers,
+/// each terminated by `\r\n`.
+///
+/// Returns its URL and a receiver for the request.
+fn serve_once_with_headers(
+    status: u16,
+    response_headers: &'static str,
+) -> (String, mpsc::Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let path = request_line.split(' ').nth(1).unwrap().to_string();
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_at(line.find(':').unwrap());
+            headers.insert(name.to_lowercase(), value[1..].trim().to_string());
+        }
+
+        let length = headers["content-length"].parse().unwrap();
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let response = format!(
+            "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n{}\r\n",
+            status, response_headers
+        );
+        reader.get_mut().write_all(response.as_bytes()).unwrap();
+
+        sender
+            .send(Request {
+                path,
+                headers,
+                body,
+            })
+            .unwrap();
+    });
+
+    (url, receiver)
+}
+
+#[test]
+fn statuses_are_reported_to_glean() {
+    let uploader = ReqwestUploader::new();
+
+    for status in &[200, 404, 500] {
+        let (url, _request) = serve_once(*status);
+        assert_eq!(
+            UploadResult::HttpStatus(u32::from(*status)),
+            uploader.upload(url, b"{}".to_vec(), HashMap::new())
+        );
+    }
+}
+
+#[test]
+fn retry_after_is_reported_to_glean() {
+    let (url, _request) = serve_once_with_headers(503, "Retry-After: 120\r\n");
+    assert_eq!(
+        UploadResult::HttpStatusWithRetryAfter(503, "120".to_string()),
+        ReqwestUploader::new().upload(url, b"{}".to_vec(), HashMap::new())
+    );
+}
+
+#[test]
+fn connection_errors_are_recoverable() {
+    // Nothing listens on the port anymore once the listener is dropped.
+    let url = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    };
+
+    assert_eq!(
+        UploadResult::RecoverableFailure,
+        ReqwestUploader::new().upload(url, b"{}".to_vec(), HashMap::new())
+    );
+}
+
+#[test]
+fn pings_reach_the_server() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    let (url, requests) = serve_once(200);
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .server_endpoint(url)
+        .uploader(ReqwestUploader::new())
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("uploaded", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+
+    let request = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert!(request
+        .path
+        .starts_with("/submit/org-mozilla-glean-test-app/uploaded/1/"));
+    assert_eq!("gzip", request.headers["content-encoding"]);
+    assert_eq!(
+        "application/json; charset=utf-8",
+        request.headers["content-type"]
+    );
+
+    let mut body = String::new();
+    GzDecoder::new(&request.body[..])
+        .read_to_string(&mut body)
+        .unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(0, payload["ping_info"]["seq"]);
+
+    glean::shutdown();
+}
@@ -9,7 +9,11 @@ use std::path::PathBuf;
 use jsonschema_valid::{self, schemas::Draft};
 use serde_json::Value;
 
-use glean::{private::PingType, ClientInfoMetrics, Configuration};
+use glean::private::{
+    GaugeMetric, LabeledMetric, MemoryDistributionMetric, ObjectMetric, PingType, RateMetric,
+    TextMetric, UrlMetric,
+};
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, MemoryUnit};
 
 const SCHEMA_JSON: &str = include_str!("../../../glean.1.schema.json");
 
@@ -23,27 +27,34 @@ const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
 // We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
 fn new_glean() -> tempfile::TempDir {
     let dir = tempfile::tempdir().unwrap();
-    let tmpname = dir.path().display().to_string();
-
-    let cfg = Configuration {
-        data_path: tmpname,
-        application_id: GLOBAL_APPLICATION_ID.into(),
-        upload_enabled: true,
-        max_events: None,
-        delay_ping_lifetime_io: false,
-        channel: None,
-    };
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
 
     let client_info = ClientInfoMetrics {
         app_build: env!("CARGO_PKG_VERSION").to_string(),
         app_display_version: env!("CARGO_PKG_VERSION").to_string(),
+        locale: None,
     };
 
-    glean::initialize(cfg, client_info);
+    glean::initialize(cfg, client_info).unwrap();
 
     dir
 }
 
+// The metadata of a metric sent in the `test` ping.
+fn test_metric(name: &str) -> CommonMetricData {
+    CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["test".into()],
+        ..Default::default()
+    }
+}
+
 #[test]
 fn validate_against_schema() {
     let schema = load_schema();
@@ -53,6 +64,27 @@ fn validate_against_schema() {
     // Register and submit a ping for testing
     let ping_type = PingType::new("test", true, /* send_if_empty */ true, vec![]);
     glean::register_ping_type(&ping_type);
+
+    // Record metrics in the sections that are not part of the builtin pings.
+    UrlMetric::new(test_metric("url")).set("https://example.com/path");
+    TextMetric::new(test_metric("text")).set("Some longer text.");
+    GaugeMetric::new(test_metric("gauge")).set(-5);
+    let labeled: LabeledMetric<MemoryDistributionMetric> =
+        LabeledMetric::with_memory_unit(test_metric("labeled_memory"), MemoryUnit::Kilobyte, None);
+    labeled.get("label").accumulate(100);
+    let rate = RateMetric::new(test_metric("rate"));
+    rate.add_to_numerator(1);
+    rate.add_to_denominator(10);
+    ObjectMetric::<Vec<String>>::new(test_metric("object")).set(vec!["value".into()]);
+    let sections = [
+        "url",
+        "text",
+        "gauge",
+        "labeled_memory_distribution",
+        "rate",
+        "object",
+    ];
+
     ping_type.submit(None);
     glean::dispatcher::block_on_queue();
 
@@ -80,7 +112,15 @@ fn validate_against_schema() {
     let body = lines.next().unwrap().unwrap();
 
     // Now validate against the vendored schema
-    let data = serde_json::from_str(&body).unwrap();
+    let data: Value = serde_json::from_str(&body).unwrap();
+    for section in &sections {
+        assert!(
+            data["metrics"][section].is_object(),
+            "Missing section {} in {:#?}",
+            section,
+            data
+        );
+    }
     let cfg = jsonschema_valid::Config::from_schema(&schema, Some(Draft::Draft6)).unwrap();
     let validation = cfg.validate(&data);
     match validation {
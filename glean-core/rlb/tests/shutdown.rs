@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::Path;
+
+use glean::{private::BooleanMetric, ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path) {
+    let cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .delay_ping_lifetime_io(true)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+}
+
+/// Reads the pending pings as (URL path, JSON body) tuples.
+fn pending_pings(data_path: &Path) -> Vec<(String, serde_json::Value)> {
+    fs::read_dir(data_path.join("pending_pings"))
+        .unwrap()
+        .map(|entry| {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut lines = content.lines();
+            let path = lines.next().unwrap().to_string();
+            let body = serde_json::from_str(lines.next().unwrap()).unwrap();
+            (path, body)
+        })
+        .collect()
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is shut down.
+#[test]
+fn shutdown_flushes_data_and_allows_reinitialization() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    initialize(dir.path());
+
+    let metric = BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    });
+    metric.set(true);
+    assert_eq!(Some(true), metric.test_get_value("store1"));
+
+    glean::shutdown();
+
+    let baseline_pings: Vec<_> = pending_pings(dir.path())
+        .into_iter()
+        .filter(|(path, _)| path.contains("/baseline/"))
+        .collect();
+    assert_eq!(1, baseline_pings.len());
+    assert_eq!(
+        "app-shutdown",
+        baseline_pings[0].1["ping_info"]["reason"].as_str().unwrap()
+    );
+
+    // Shutting down twice is harmless.
+    glean::shutdown();
+
+    // The ping lifetime data was persisted on shutdown and is loaded again.
+    initialize(dir.path());
+    assert_eq!(Some(true), metric.test_get_value("store1"));
+    glean::shutdown();
+
+    // Tasks launched while Glean is shut down are queued until it's initialized again.
+    metric.set(false);
+    initialize(dir.path());
+    assert_eq!(Some(false), metric.test_get_value("store1"));
+
+    glean::shutdown();
+}
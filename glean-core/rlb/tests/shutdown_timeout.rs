@@ -0,0 +1,31 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use glean::{ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+#[test]
+fn shutdown_does_not_wait_for_slow_tasks_beyond_the_timeout() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .dispatcher_flush_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    glean::dispatcher::launch(|| thread::sleep(Duration::from_secs(3)));
+
+    let start = Instant::now();
+    glean::shutdown();
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(!glean::is_initialized());
+}
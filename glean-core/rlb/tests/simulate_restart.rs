@@ -0,0 +1,88 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use glean::private::{CounterMetric, PingType};
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, InitializeError, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// Reads the `ping_info` of all pending pings with the given name.
+fn ping_infos(data_path: &Path, ping_name: &str) -> Vec<serde_json::Value> {
+    fs::read_dir(data_path.join("pending_pings"))
+        .unwrap()
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut lines = content.lines();
+            let path = lines.next().unwrap();
+            if !path.contains(&format!("/{}/", ping_name)) {
+                return None;
+            }
+            let body: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+            Some(body["ping_info"].clone())
+        })
+        .collect()
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is restarted.
+#[test]
+fn simulated_restart_keeps_data_and_sends_dirty_startup_ping() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Nothing to restart yet.
+    assert_eq!(
+        Err(InitializeError::Failed),
+        glean::test::simulate_restart()
+    );
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let metric = CounterMetric::new(CommonMetricData {
+        name: "user_counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::User,
+        ..Default::default()
+    });
+    metric.add(3);
+
+    let ping = PingType::new("restart", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+
+    glean::test::simulate_restart().unwrap();
+    assert!(glean::is_init_complete());
+
+    // User lifetime data is kept.
+    assert_eq!(Some(3), metric.test_get_value("store1"));
+
+    // The application was not shut down cleanly.
+    let reasons: Vec<_> = ping_infos(dir.path(), "baseline")
+        .iter()
+        .map(|info| info["reason"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(vec!["dirty_startup".to_string()], reasons);
+
+    // Sequence numbers continue where they left off.
+    glean::register_ping_type(&ping);
+    assert!(ping.submit(None).wait());
+    let mut seqs: Vec<_> = ping_infos(dir.path(), "restart")
+        .iter()
+        .map(|info| info["seq"].as_u64().unwrap())
+        .collect();
+    seqs.sort_unstable();
+    assert_eq!(vec![0, 1], seqs);
+
+    glean::shutdown();
+}
@@ -0,0 +1,93 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use glean::{
+    net::{PingUploader, UploadResult},
+    private::PingType,
+    ClientInfoMetrics, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// An uploader keeping the `X-Source-Tags` header of every upload request.
+#[derive(Clone, Debug, Default)]
+struct SourceTagsRecorder {
+    source_tags: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl SourceTagsRecorder {
+    fn source_tags(&self) -> Vec<Option<String>> {
+        self.source_tags.lock().unwrap().clone()
+    }
+}
+
+impl PingUploader for SourceTagsRecorder {
+    fn upload(
+        &self,
+        _url: String,
+        _body: Vec<u8>,
+        headers: HashMap<String, String>,
+    ) -> UploadResult {
+        self.source_tags
+            .lock()
+            .unwrap()
+            .push(headers.get("X-Source-Tags").cloned());
+        UploadResult::HttpStatus(200)
+    }
+}
+
+fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn source_tags_are_added_to_uploads_after_they_were_set() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    let uploader = SourceTagsRecorder::default();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let ping = PingType::new("tagged", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+
+    assert!(!glean::set_source_tags(vec![]));
+    assert!(!glean::set_source_tags(tags(&["invalid tag"])));
+    assert!(!glean::set_source_tags(tags(&["glean-reserved"])));
+    assert!(!glean::set_source_tags(tags(&[
+        "a", "b", "c", "d", "e", "f"
+    ])));
+
+    // Invalid tags don't set anything.
+    assert!(ping.submit(None).wait());
+    assert!(glean::test::wait_until(
+        || uploader.source_tags().len() == 1,
+        Duration::from_secs(5)
+    ));
+    assert_eq!(vec![None], uploader.source_tags());
+
+    assert!(glean::set_source_tags(tags(&["automation", "perf"])));
+
+    assert!(ping.submit(None).wait());
+    assert!(glean::test::wait_until(
+        || uploader.source_tags().len() == 2,
+        Duration::from_secs(5)
+    ));
+    assert_eq!(
+        vec![None, Some("automation,perf".to_string())],
+        uploader.source_tags()
+    );
+
+    glean::shutdown();
+}
@@ -0,0 +1,77 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use glean::private::{PingType, TextMetric};
+use glean::test::MockUploader;
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else changes the storage size while it is checked.
+#[test]
+fn storage_size_grows_with_recorded_data_and_shrinks_after_upload() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    assert_eq!(0, glean::storage_size_bytes());
+    assert_eq!(None, glean::max_storage_bytes());
+
+    // Without an uploader, submitted pings stay on disk.
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+    let initial_size = glean::storage_size_bytes();
+    assert!(initial_size > 0);
+
+    let ping = PingType::new("sized", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+    for i in 0..500 {
+        let text = TextMetric::new(CommonMetricData {
+            name: format!("text_{}", i),
+            category: "test".into(),
+            send_in_pings: vec!["sized".into()],
+            lifetime: Lifetime::Ping,
+            ..Default::default()
+        });
+        text.set(format!("{:x}", i * 7919).repeat(100));
+    }
+    glean::dispatcher::block_on_queue();
+    let recorded_size = glean::storage_size_bytes();
+    assert!(recorded_size > initial_size);
+
+    // Submitting moves the data into a pending ping. Whether the database file
+    // shrinks when the data is removed from it depends on the storage backend.
+    assert!(ping.submit(None).wait());
+    let submitted_size = glean::storage_size_bytes();
+
+    glean::shutdown();
+
+    // Uploading removes the pending ping from disk.
+    let uploader = MockUploader::new();
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .max_storage_bytes(10_000_000)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+    // Pings pending from the previous run are uploaded along with the next submitted one.
+    let trigger = PingType::new("trigger", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&trigger);
+    assert!(trigger.submit(None).wait());
+    assert!(glean::test::wait_until(
+        || uploader.attempts_for("sized") == 1 && glean::storage_size_bytes() < submitted_size,
+        Duration::from_secs(10)
+    ));
+    assert_eq!(Some(10_000_000), glean::max_storage_bytes());
+
+    glean::shutdown();
+}
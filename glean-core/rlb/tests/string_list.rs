@@ -0,0 +1,119 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::{PingType, StringListMetric},
+    ClientInfoMetrics, CommonMetricData, Configuration, ErrorType,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_string_list(name: &str, ping_name: &str) -> StringListMetric {
+    StringListMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec![ping_name.into()],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn values_can_be_added_and_set() {
+    setup_glean();
+    let metric = new_string_list("add_and_set", "store1");
+
+    metric.add("first");
+    metric.add("second".to_string());
+    assert_eq!(
+        Some(vec!["first".to_string(), "second".to_string()]),
+        metric.test_get_value("store1")
+    );
+
+    metric.set(vec!["third".into()]);
+    assert_eq!(
+        Some(vec!["third".to_string()]),
+        metric.test_get_value("store1")
+    );
+}
+
+#[test]
+fn long_values_are_truncated() {
+    setup_glean();
+    let metric = new_string_list("truncated", "store1");
+
+    metric.add("x".repeat(60));
+
+    assert_eq!(Some(vec!["x".repeat(50)]), metric.test_get_value("store1"));
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
+
+#[test]
+fn list_is_capped_at_20_values() {
+    setup_glean();
+    let metric = new_string_list("capped", "store1");
+
+    for i in 0..21 {
+        metric.add(format!("value{}", i));
+    }
+
+    let value = metric.test_get_value("store1").unwrap();
+    assert_eq!(20, value.len());
+    assert_eq!("value19", value[19]);
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn list_is_serialized_as_json_array_in_the_ping() {
+    setup_glean();
+    let metric = new_string_list("in_ping", "strings");
+    let ping = PingType::new("strings", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+
+    metric.set(vec!["a".into(), "b".into()]);
+    assert_eq!(
+        Some(r#"["a","b"]"#.to_string()),
+        metric.test_get_value_as_json_string("strings")
+    );
+    assert!(ping.submit(None).wait());
+
+    let entry = fs::read_dir(GLEAN_DIR.path().join("pending_pings"))
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .find(|content| content.lines().next().unwrap().contains("/strings/"))
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_str(entry.lines().nth(1).unwrap()).unwrap();
+    assert_eq!(
+        serde_json::json!(["a", "b"]),
+        body["metrics"]["string_list"]["test.in_ping"]
+    );
+}
@@ -0,0 +1,157 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::{mpsc, Arc, Mutex};
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    dispatcher::{self, Priority},
+    private::{BooleanMetric, PingType},
+    ClientInfoMetrics, CommonMetricData, Configuration, ErrorKind, Lifetime,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton and can only be initialized once per process,
+// so all tests in this file share the same instance in a temporary directory.
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+#[test]
+fn submission_reports_whether_the_ping_was_queued() {
+    setup_glean();
+
+    let empty_ping = PingType::new("empty", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&empty_ping);
+    assert!(!empty_ping.submit(None).wait());
+
+    let sent_ping = PingType::new("sent", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&sent_ping);
+    assert!(sent_ping.submit(None).wait());
+
+    assert!(!glean::submit_ping_by_name("unknown", None).wait());
+}
+
+#[test]
+fn before_next_submit_callback_runs_once_before_collection() {
+    setup_glean();
+
+    let ping = PingType::new("callback", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+
+    let metric = BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["callback".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    });
+    metric.set(true);
+
+    let calls = Arc::new(Mutex::new(vec![]));
+    {
+        let calls = Arc::clone(&calls);
+        let metric = metric.clone();
+        ping.test_before_next_submit(move |reason| {
+            // The metric is only cleared once the ping is collected.
+            let value = metric.test_get_value("callback");
+            calls
+                .lock()
+                .unwrap()
+                .push((reason.map(|s| s.to_string()), value));
+        });
+    }
+
+    assert!(ping.submit(Some("test")).wait());
+    // The callback doesn't run for later submissions.
+    metric.set(true);
+    assert!(ping.submit(None).wait());
+
+    assert_eq!(
+        vec![(Some("test".to_string()), Some(true))],
+        *calls.lock().unwrap()
+    );
+    assert_eq!(None, metric.test_get_value("callback"));
+}
+
+#[test]
+fn submit_ping_and_wait_returns_after_the_ping_was_queued() {
+    setup_glean();
+
+    let ping = PingType::new("waited", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+
+    let before = glean::test::pending_ping_doc_ids("waited").len();
+    glean::submit_ping_and_wait(&ping, None).unwrap();
+    assert_eq!(
+        before + 1,
+        glean::test::pending_ping_doc_ids("waited").len()
+    );
+
+    // Skipping an empty ping is not an error.
+    let empty_ping = PingType::new("waited-empty", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&empty_ping);
+    glean::submit_ping_and_wait(&empty_ping, None).unwrap();
+    assert!(glean::test::pending_ping_doc_ids("waited-empty").is_empty());
+
+    let unregistered = PingType::new("waited-unregistered", true, true, vec![]);
+    match glean::submit_ping_and_wait(&unregistered, None) {
+        Err(e) => match e.kind() {
+            ErrorKind::PingNotFound(name) => assert_eq!("waited-unregistered", name),
+            _ => panic!("Unexpected error: {}", e),
+        },
+        Ok(()) => panic!("Submitting an unregistered ping should fail"),
+    }
+}
+
+#[test]
+fn high_priority_submission_runs_before_queued_recordings() {
+    setup_glean();
+
+    let ping = PingType::new("priority", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+
+    let metric = BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["priority".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    });
+
+    // Block the worker thread until we're done queueing.
+    let (unblock_sender, unblock_receiver) = mpsc::channel::<()>();
+    dispatcher::launch(move || {
+        unblock_receiver.recv().unwrap();
+    });
+
+    metric.set(true);
+    let submission = ping.submit_with_priority(None, Priority::High);
+    unblock_sender.send(()).unwrap();
+
+    // The ping was collected before the metric was recorded, so it was empty.
+    assert!(!submission.wait());
+    assert_eq!(Some(true), metric.test_get_value("priority"));
+
+    // Submissions of normal priority keep their order.
+    assert!(ping.submit(None).wait());
+    assert_eq!(None, metric.test_get_value("priority"));
+}
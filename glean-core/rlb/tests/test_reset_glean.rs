@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+
+use glean::private::CounterMetric;
+use glean::{ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn config(data_path: &Path) -> Configuration {
+    Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap()
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is reset.
+#[test]
+fn reset_glean_optionally_clears_the_stores() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Resetting works before Glean was ever initialized.
+    glean::test_reset_glean(config(dir.path()), ClientInfoMetrics::unknown(), true).unwrap();
+    assert!(glean::is_init_complete());
+
+    let metric = CounterMetric::new(CommonMetricData {
+        name: "user_counter".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        lifetime: Lifetime::User,
+        ..Default::default()
+    });
+    metric.add(3);
+    glean::set_log_pings(true);
+
+    // Data is kept without clearing the stores.
+    glean::test_reset_glean(config(dir.path()), ClientInfoMetrics::unknown(), false).unwrap();
+    assert!(glean::is_init_complete());
+    assert_eq!(Some(3), metric.test_get_value("store1"));
+    // A reset is not a dirty startup.
+    assert!(glean::test::pending_ping_doc_ids("baseline").is_empty());
+
+    // Clearing the stores removes all data.
+    glean::test_reset_glean(config(dir.path()), ClientInfoMetrics::unknown(), true).unwrap();
+    assert!(glean::is_init_complete());
+    assert_eq!(None, metric.test_get_value("store1"));
+
+    glean::shutdown();
+}
@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use once_cell::sync::Lazy;
+
+use glean::{private::TextMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_text(name: &str) -> TextMetric {
+    TextMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn kilobyte_texts_are_recorded_unchanged() {
+    setup_glean();
+    let metric = new_text("kilobyte");
+
+    let value = "a stack trace\n".repeat(1024 / 14 + 1);
+    metric.set(value.clone());
+    assert_eq!(Some(value), metric.test_get_value("store1"));
+    assert_eq!(
+        0,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
+
+#[test]
+fn long_texts_are_truncated() {
+    setup_glean();
+    let metric = new_text("long");
+
+    metric.set("x".repeat(300 * 1024));
+    assert_eq!(
+        Some("x".repeat(200 * 1024)),
+        metric.test_get_value("store1")
+    );
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use glean_core::MockClock;
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::TimespanMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType,
+    Lifetime, TimeUnit,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// The tests in this file share the clock, so they only advance it.
+static CLOCK: Lazy<Arc<MockClock>> = Lazy::new(|| Arc::new(MockClock::new()));
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+    glean::test::set_clock(CLOCK.clone());
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_timespan(name: &str) -> TimespanMetric {
+    TimespanMetric::new(
+        CommonMetricData {
+            name: name.into(),
+            category: "test".into(),
+            send_in_pings: vec!["store1".into()],
+            lifetime: Lifetime::Application,
+            ..Default::default()
+        },
+        TimeUnit::Millisecond,
+    )
+}
+
+#[test]
+fn timespan_records_elapsed_time() {
+    setup_glean();
+    let metric = new_timespan("elapsed");
+
+    metric.start();
+    CLOCK.advance(Duration::from_millis(10));
+    metric.stop();
+
+    assert!(metric.test_get_value("store1").unwrap() >= 10);
+    assert_eq!(
+        0,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidState, None)
+    );
+}
+
+#[test]
+fn starting_twice_keeps_the_first_start() {
+    setup_glean();
+    let metric = new_timespan("started_twice");
+
+    metric.start();
+    CLOCK.advance(Duration::from_millis(10));
+    metric.start();
+    metric.stop();
+
+    assert!(metric.test_get_value("store1").unwrap() >= 10);
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidState, None)
+    );
+}
+
+#[test]
+fn stopping_without_start_records_an_error() {
+    setup_glean();
+    let metric = new_timespan("not_started");
+
+    metric.stop();
+
+    assert_eq!(None, metric.test_get_value("store1"));
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidState, None)
+    );
+}
+
+#[test]
+fn cancelled_timespans_are_not_recorded() {
+    setup_glean();
+    let metric = new_timespan("cancelled");
+
+    metric.start();
+    metric.cancel();
+    metric.stop();
+
+    assert_eq!(None, metric.test_get_value("store1"));
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidState, None)
+    );
+}
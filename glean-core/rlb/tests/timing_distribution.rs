@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use glean_core::MockClock;
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::TimingDistributionMetric, ClientInfoMetrics, CommonMetricData, Configuration,
+    ErrorType, TimeUnit,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// The tests in this file share the clock, so they only advance it.
+static CLOCK: Lazy<Arc<MockClock>> = Lazy::new(|| Arc::new(MockClock::new()));
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+    glean::test::set_clock(CLOCK.clone());
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_timing_distribution(name: &str, time_unit: TimeUnit) -> TimingDistributionMetric {
+    TimingDistributionMetric::new(
+        CommonMetricData {
+            name: name.into(),
+            category: "test".into(),
+            send_in_pings: vec!["store1".into()],
+            ..Default::default()
+        },
+        time_unit,
+    )
+}
+
+#[test]
+fn concurrent_timers_are_accumulated() {
+    setup_glean();
+    let metric = new_timing_distribution("concurrent", TimeUnit::Nanosecond);
+
+    let first = metric.start();
+    let second = metric.start();
+    let cancelled = metric.start();
+    assert_ne!(first, second);
+
+    CLOCK.advance(Duration::from_millis(10));
+    metric.stop_and_accumulate(second);
+    metric.cancel(cancelled);
+    metric.stop_and_accumulate(first);
+    metric.stop_and_accumulate(cancelled);
+
+    let data = metric.test_get_value("store1").unwrap();
+    assert_eq!(2, data.values.values().sum::<u64>());
+    assert!(data.sum >= 2 * 10_000_000);
+
+    // Stopping the cancelled timer is an error.
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidState, None)
+    );
+}
+
+#[test]
+fn samples_are_bucketed() {
+    setup_glean();
+    let metric = new_timing_distribution("samples", TimeUnit::Nanosecond);
+
+    metric.accumulate_samples_signed(vec![1, 1, 10, -5]);
+
+    let data = metric.test_get_value("store1").unwrap();
+    assert_eq!(12, data.sum);
+    // Samples of 1 end up in the same bucket, 10 in another one.
+    assert_eq!(Some(&2), data.values.get(&1));
+    assert_eq!(3, data.values.values().sum::<u64>());
+
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn too_long_samples_are_clamped() {
+    setup_glean();
+    let metric = new_timing_distribution("too_long", TimeUnit::Nanosecond);
+
+    // The maximum sample time is 10 minutes.
+    let max_sample_time = 1000 * 1000 * 1000 * 60 * 10;
+    metric.accumulate_samples_signed(vec![max_sample_time + 1]);
+
+    let data = metric.test_get_value("store1").unwrap();
+    assert_eq!(max_sample_time as u64, data.sum);
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
@@ -0,0 +1,93 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#![cfg(feature = "opentelemetry")]
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use opentelemetry::trace::{Tracer, TracerProvider as _};
+use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+
+use glean::net::{PingUploader, UploadResult};
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// Keeps the headers of the uploaded pings, by ping name.
+#[derive(Clone, Debug, Default)]
+struct HeaderUploader {
+    headers: Arc<Mutex<HashMap<String, HashMap<String, String>>>>,
+}
+
+impl PingUploader for HeaderUploader {
+    fn upload(
+        &self,
+        url: String,
+        _body: Vec<u8>,
+        headers: HashMap<String, String>,
+    ) -> UploadResult {
+        let ping_name = url.rsplit('/').nth(2).unwrap_or_default().to_string();
+        self.headers.lock().unwrap().insert(ping_name, headers);
+        UploadResult::HttpStatus(200)
+    }
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to check pings submitted in and out of a span.
+#[test]
+fn pings_carry_the_traceparent_of_the_span_they_were_submitted_in() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    let uploader = HeaderUploader::default();
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .enable_trace_context(true)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    let traced = PingType::new("traced", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&traced);
+    let untraced = PingType::new("untraced", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&untraced);
+
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let tracer = provider.tracer("glean-test");
+
+    // The ping is assembled on the dispatcher thread, after the span ended.
+    let submission = tracer.in_span("submit", |_cx| traced.submit(None));
+    assert!(submission.wait());
+    assert!(untraced.submit(None).wait());
+
+    let has_headers = || {
+        let headers = uploader.headers.lock().unwrap();
+        headers.contains_key("traced") && headers.contains_key("untraced")
+    };
+    assert!(glean::test::wait_until(has_headers, Duration::from_secs(5)));
+
+    let spans = exporter.get_finished_spans().unwrap();
+    assert_eq!(1, spans.len());
+    let span_context = &spans[0].span_context;
+    let expected = format!(
+        "00-{}-{}-01",
+        span_context.trace_id(),
+        span_context.span_id()
+    );
+
+    let headers = uploader.headers.lock().unwrap();
+    assert_eq!(Some(&expected), headers["traced"].get("traceparent"));
+    assert!(!headers["untraced"].contains_key("traceparent"));
+    drop(headers);
+
+    glean::shutdown();
+}
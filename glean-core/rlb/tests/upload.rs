@@ -0,0 +1,60 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    net::UploadResult, private::PingType, test::MockUploader, ClientInfoMetrics, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// Glean is a global singleton and can only be initialized once per process,
+// so all tests in this file share the same instance and uploader.
+static UPLOADER: Lazy<MockUploader> = Lazy::new(MockUploader::new);
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(UPLOADER.clone())
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+/// Waits up to 5 seconds for the uploader to be called the given number of times.
+fn wait_for_calls(count: usize) {
+    glean::test::wait_until(|| UPLOADER.call_count() >= count, Duration::from_secs(5));
+}
+
+#[test]
+fn recoverable_failures_are_retried() {
+    setup_glean();
+
+    UPLOADER.push_result(UploadResult::RecoverableFailure);
+    UPLOADER.push_result(UploadResult::RecoverableFailure);
+    UPLOADER.push_result(UploadResult::HttpStatus(200));
+
+    let ping = PingType::new("retried", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+
+    assert!(ping.submit(None).wait());
+
+    wait_for_calls(3);
+    assert_eq!(3, UPLOADER.call_count());
+    assert_eq!(3, UPLOADER.attempts_for("retried"));
+}
@@ -0,0 +1,108 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use glean::{
+    net::{PingUploader, UploadResult},
+    private::PingType,
+    ClientInfoMetrics, Configuration,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+/// An uploader keeping the names of all uploaded pings, in order.
+///
+/// Uploads block until the uploader is opened,
+/// so that all pings submitted before are pending.
+#[derive(Clone, Debug, Default)]
+struct GatedRecorder {
+    open: Arc<(Mutex<bool>, Condvar)>,
+    ping_names: Arc<Mutex<Vec<String>>>,
+}
+
+impl GatedRecorder {
+    fn open(&self) {
+        let (open, opened) = &*self.open;
+        *open.lock().unwrap() = true;
+        opened.notify_all();
+    }
+
+    fn ping_names(&self) -> Vec<String> {
+        self.ping_names.lock().unwrap().clone()
+    }
+}
+
+impl PingUploader for GatedRecorder {
+    fn upload(
+        &self,
+        url: String,
+        _body: Vec<u8>,
+        _headers: HashMap<String, String>,
+    ) -> UploadResult {
+        let (open, opened) = &*self.open;
+        let mut open = open.lock().unwrap();
+        while !*open {
+            open = opened.wait(open).unwrap();
+        }
+
+        // The path is `/submit/<application id>/<ping name>/<version>/<document id>`.
+        let ping_name = url.split('/').rev().nth(2).unwrap().to_string();
+        self.ping_names.lock().unwrap().push(ping_name);
+        UploadResult::HttpStatus(200)
+    }
+}
+
+#[test]
+fn pending_pings_are_uploaded_before_the_deletion_request() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+    let uploader = GatedRecorder::default();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .uploader(uploader.clone())
+        .upload_pending_pings_before_disabling(true)
+        .build()
+        .unwrap();
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+
+    for name in &["first", "second"] {
+        let ping = PingType::new(*name, true, /* send_if_empty */ true, vec![]);
+        glean::register_ping_type(&ping);
+        assert!(ping.submit(None).wait());
+    }
+
+    // A ping still queued when upload is disabled is submitted and uploaded as well.
+    // The dispatcher is kept busy, so the submission is still queued.
+    let (started, wait_for_start) = crossbeam_channel::bounded::<()>(0);
+    let (release, released) = crossbeam_channel::bounded::<()>(0);
+    glean::dispatcher::launch(move || {
+        started.send(()).unwrap();
+        let _ = released.recv();
+    });
+    wait_for_start.recv().unwrap();
+    let ping = PingType::new("queued", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+    let queued = ping.submit(None);
+
+    glean::set_upload_enabled(false);
+    release.send(()).unwrap();
+    uploader.open();
+    assert!(queued.wait());
+
+    assert!(glean::test::wait_until(
+        || uploader.ping_names().len() == 4,
+        Duration::from_secs(5)
+    ));
+    assert_eq!(
+        vec!["first", "second", "queued", "deletion-request"],
+        uploader.ping_names()
+    );
+
+    glean::shutdown();
+}
@@ -0,0 +1,138 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+
+use once_cell::sync::Lazy;
+
+use glean::{
+    private::{PingType, UrlMetric},
+    ClientInfoMetrics, CommonMetricData, Configuration, ErrorType,
+};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_url(name: &str, ping_name: &str) -> UrlMetric {
+    UrlMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec![ping_name.into()],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn http_urls_are_recorded() {
+    setup_glean();
+    let metric = new_url("valid", "store1");
+
+    metric.set("https://example.com/path?query=1");
+    assert_eq!(
+        Some("https://example.com/path?query=1".to_string()),
+        metric.test_get_value("store1")
+    );
+
+    metric.set("http://example.com".to_string());
+    assert_eq!(
+        Some("http://example.com".to_string()),
+        metric.test_get_value("store1")
+    );
+}
+
+#[test]
+fn query_and_fragment_are_only_stripped_if_redacted() {
+    setup_glean();
+    let meta = CommonMetricData {
+        name: "redacted".into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    };
+
+    let redacted = UrlMetric::with_redacted_query(meta.clone());
+    redacted.set("https://example.com/path?user=alice&token=secret#section");
+    assert_eq!(
+        Some("https://example.com/path".to_string()),
+        redacted.test_get_value("store1")
+    );
+
+    let kept = UrlMetric::new(CommonMetricData {
+        name: "kept".into(),
+        ..meta
+    });
+    kept.set("https://example.com/path?user=alice&token=secret#section");
+    assert_eq!(
+        Some("https://example.com/path?user=alice&token=secret#section".to_string()),
+        kept.test_get_value("store1")
+    );
+}
+
+#[test]
+fn invalid_urls_are_rejected() {
+    setup_glean();
+    let metric = new_url("invalid", "store1");
+
+    metric.set("https://example.com");
+    metric.set("file:///etc/passwd");
+    metric.set("not a url");
+    assert_eq!(
+        Some("https://example.com".to_string()),
+        metric.test_get_value("store1")
+    );
+    assert_eq!(
+        2,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+
+    metric.set(format!("https://example.com/{}", "x".repeat(8192)));
+    assert_eq!(
+        Some("https://example.com".to_string()),
+        metric.test_get_value("store1")
+    );
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidOverflow, None)
+    );
+}
+
+#[test]
+fn url_is_sent_in_the_url_section_of_the_ping() {
+    setup_glean();
+    let metric = new_url("in_ping", "urls");
+    let ping = PingType::new("urls", true, /* send_if_empty */ false, vec![]);
+    glean::register_ping_type(&ping);
+
+    metric.set("https://example.com");
+    assert!(ping.submit(None).wait());
+
+    let entry = fs::read_dir(GLEAN_DIR.path().join("pending_pings"))
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .find(|content| content.lines().next().unwrap().contains("/urls/"))
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_str(entry.lines().nth(1).unwrap()).unwrap();
+    assert_eq!(
+        serde_json::json!("https://example.com"),
+        body["metrics"]["url"]["test.in_ping"]
+    );
+}
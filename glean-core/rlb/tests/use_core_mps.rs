@@ -0,0 +1,72 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+// Reading thread names from `/proc` only works on Linux.
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use glean::{private::BooleanMetric, ClientInfoMetrics, CommonMetricData, Configuration, Lifetime};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+fn initialize(data_path: &Path, use_core_mps: bool) {
+    let mut cfg = Configuration::builder()
+        .data_path(data_path)
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+    // Set as by applications creating the configuration without the builder.
+    cfg.use_core_mps = use_core_mps;
+
+    glean::initialize_sync(cfg, ClientInfoMetrics::unknown(), Duration::from_secs(10)).unwrap();
+}
+
+/// Whether a thread of this process runs the `metrics` ping scheduler.
+///
+/// Linux truncates thread names to 15 bytes.
+fn scheduler_thread_running() -> bool {
+    fs::read_dir("/proc/self/task").unwrap().any(|task| {
+        let comm = fs::read_to_string(task.unwrap().path().join("comm")).unwrap_or_default();
+        comm.trim_end() == &"glean.metrics_ping_scheduler"[..15]
+    })
+}
+
+// Glean is a global singleton, so this file contains a single test.
+// The scheduler is only checked after initializing without it first,
+// as a stopped scheduler thread exits in the background.
+#[test]
+fn no_scheduler_thread_is_started_without_core_mps() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let dir = tempfile::tempdir().unwrap();
+
+    initialize(dir.path(), false);
+    assert!(!scheduler_thread_running());
+
+    // The `metrics` ping can still be submitted by the application.
+    let metric = BooleanMetric::new(CommonMetricData {
+        name: "bool_metric".into(),
+        category: "test".into(),
+        send_in_pings: vec!["metrics".into()],
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    });
+    metric.set(true);
+    assert!(glean::submit_ping_by_name("metrics", Some("today")).wait());
+    assert!(!scheduler_thread_running());
+
+    glean::shutdown();
+
+    // With the scheduler, its thread is found.
+    let dir = tempfile::tempdir().unwrap();
+    initialize(dir.path(), true);
+    assert!(glean::test::wait_until(
+        scheduler_thread_running,
+        Duration::from_secs(10)
+    ));
+
+    glean::shutdown();
+}
@@ -0,0 +1,78 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use once_cell::sync::Lazy;
+
+use glean::{private::UuidMetric, ClientInfoMetrics, CommonMetricData, Configuration, ErrorType};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+fn new_uuid(name: &str) -> UuidMetric {
+    UuidMetric::new(CommonMetricData {
+        name: name.into(),
+        category: "test".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn generated_uuid_is_stored() {
+    setup_glean();
+    let metric = new_uuid("generated");
+
+    let uuid = metric.generate_and_set();
+
+    assert_eq!(Some(uuid.to_string()), metric.test_get_value("store1"));
+    assert_eq!(Some(uuid::Version::Random), uuid.get_version());
+}
+
+#[test]
+fn uuid_can_be_set() {
+    setup_glean();
+    let metric = new_uuid("set");
+
+    let uuid = uuid::Uuid::new_v4();
+    metric.set(uuid);
+    assert_eq!(Some(uuid.to_string()), metric.test_get_value("store1"));
+
+    metric.set_from_str("2a4a4a17-4b8f-4a2d-a8f9-6d10dd2ae5a8");
+    assert_eq!(
+        Some("2a4a4a17-4b8f-4a2d-a8f9-6d10dd2ae5a8".to_string()),
+        metric.test_get_value("store1")
+    );
+}
+
+#[test]
+fn invalid_uuid_strings_are_rejected() {
+    setup_glean();
+    let metric = new_uuid("invalid");
+
+    metric.set_from_str("not-a-uuid");
+
+    assert_eq!(None, metric.test_get_value("store1"));
+    assert_eq!(
+        1,
+        metric.test_get_num_recorded_errors(ErrorType::InvalidValue, None)
+    );
+}
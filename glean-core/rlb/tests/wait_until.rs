@@ -0,0 +1,78 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use glean::{private::PingType, ClientInfoMetrics, Configuration};
+
+const GLOBAL_APPLICATION_ID: &str = "org.mozilla.glean.test.app";
+
+// We need to keep the `TempDir` alive, so that it's not deleted before we stop using it.
+static GLEAN_DIR: Lazy<tempfile::TempDir> = Lazy::new(|| {
+    let dir = tempfile::tempdir().unwrap();
+
+    let cfg = Configuration::builder()
+        .data_path(dir.path())
+        .application_id(GLOBAL_APPLICATION_ID)
+        .build()
+        .unwrap();
+
+    glean::initialize(cfg, ClientInfoMetrics::unknown()).unwrap();
+
+    dir
+});
+
+fn setup_glean() {
+    Lazy::force(&GLEAN_DIR);
+}
+
+/// Counts the ping files in the pending pings directory with the given ping name in their path.
+///
+/// This reads the directory directly, without waiting for the dispatcher.
+fn pending_ping_files(ping_name: &str) -> usize {
+    let dir = match fs::read_dir(GLEAN_DIR.path().join("pending_pings")) {
+        Ok(dir) => dir,
+        Err(_) => return 0,
+    };
+    let path_fragment = format!("/{}/", ping_name);
+    dir.filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok())
+        .filter(|content| {
+            let path = content.lines().next().unwrap_or_default();
+            path.contains(&path_fragment)
+        })
+        .count()
+}
+
+#[test]
+fn waits_until_a_submitted_ping_is_pending() {
+    setup_glean();
+
+    let ping = PingType::new("async", true, /* send_if_empty */ true, vec![]);
+    glean::register_ping_type(&ping);
+
+    // The ping is submitted on the dispatcher, without waiting for it.
+    let _ = ping.submit(None);
+
+    assert!(glean::test::wait_until(
+        || pending_ping_files("async") == 1,
+        Duration::from_secs(5)
+    ));
+}
+
+#[test]
+fn gives_up_after_the_timeout() {
+    setup_glean();
+
+    let start = Instant::now();
+    assert!(!glean::test::wait_until(
+        || false,
+        Duration::from_millis(100)
+    ));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_secs(1));
+}
@@ -0,0 +1,60 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashSet;
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+
+use glean::private::CounterMetric;
+use glean::CommonMetricData;
+
+/// Lists the Glean test directories in the system's temporary directory.
+fn temp_glean_dirs() -> HashSet<PathBuf> {
+    fs::read_dir(std::env::temp_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("glean-test-")
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+// Glean is a global singleton, so this file contains a single test
+// to make sure nothing else runs while Glean is initialized.
+#[test]
+fn with_temp_glean_cleans_up_after_itself() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let before = temp_glean_dirs();
+
+    let (value, data_path) = glean::test::with_temp_glean(|| {
+        let counter = CounterMetric::new(CommonMetricData {
+            name: "counter".into(),
+            category: "test".into(),
+            send_in_pings: vec!["metrics".into()],
+            ..Default::default()
+        });
+        counter.add(2);
+
+        let created: Vec<_> = temp_glean_dirs().difference(&before).cloned().collect();
+        assert_eq!(1, created.len());
+        (counter.test_get_value("metrics"), created[0].clone())
+    });
+
+    assert_eq!(Some(2), value);
+    assert!(!data_path.exists());
+    assert!(!glean::is_initialized());
+
+    // Cleaning up also happens when the test panics.
+    let result = panic::catch_unwind(|| {
+        glean::test::with_temp_glean(|| panic!("test failure"));
+    });
+    assert!(result.is_err());
+    assert!(!glean::is_initialized());
+    assert!(temp_glean_dirs().difference(&before).next().is_none());
+}
@@ -0,0 +1,133 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The source of time for metrics and pings.
+
+use std::fmt::Debug;
+use std::panic::RefUnwindSafe;
+use std::time::Instant;
+#[cfg(any(test, feature = "test-support"))]
+use std::{sync::Mutex, time::Duration};
+
+use chrono::{DateTime, FixedOffset, Local, TimeZone, Utc};
+
+/// A source of the current time.
+///
+/// Glean takes the wall clock time, e.g. for datetime metrics and the times in `ping_info`,
+/// and the monotonic time, e.g. for rate limiting pings and backing off uploads, from its clock,
+/// see [`Glean::set_clock`](struct.Glean.html#method.set_clock).
+pub trait Clock: Debug + Send + Sync + RefUnwindSafe {
+    /// Gets the wall clock time, in milliseconds since the Unix epoch.
+    fn now_millis(&self) -> i64;
+
+    /// Gets the monotonic time.
+    fn monotonic_now(&self) -> Instant;
+
+    /// Gets the wall clock time in the local timezone, with its fixed offset.
+    fn now_with_offset(&self) -> DateTime<FixedOffset> {
+        let now = Local.timestamp_millis(self.now_millis());
+        now.with_timezone(now.offset())
+    }
+}
+
+/// The system's clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now_millis(&self) -> i64 {
+        Utc::now().timestamp_millis()
+    }
+
+    fn monotonic_now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when it is advanced, for deterministic tests.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Debug)]
+pub struct MockClock {
+    start_millis: i64,
+    start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl MockClock {
+    /// Creates a clock stopped at the current system time.
+    pub fn new() -> Self {
+        Self::at_millis(RealClock.now_millis())
+    }
+
+    /// Creates a clock stopped at the given wall clock time, in milliseconds since the Unix epoch.
+    pub fn at_millis(millis: i64) -> Self {
+        Self {
+            start_millis: millis,
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::default()),
+        }
+    }
+
+    /// Moves both the wall clock and the monotonic time ahead by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl Clock for MockClock {
+    fn now_millis(&self) -> i64 {
+        self.start_millis + self.elapsed.lock().unwrap().as_millis() as i64
+    }
+
+    fn monotonic_now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mock_clock_only_moves_when_advanced() {
+        let clock = MockClock::at_millis(1_000);
+        let start = clock.monotonic_now();
+        assert_eq!(1_000, clock.now_millis());
+        assert_eq!(start, clock.monotonic_now());
+
+        clock.advance(Duration::from_millis(1_500));
+        assert_eq!(2_500, clock.now_millis());
+        assert_eq!(Duration::from_millis(1_500), clock.monotonic_now() - start);
+    }
+
+    #[test]
+    fn real_clock_gets_the_local_time() {
+        let now = Local::now();
+        let fixed_now = RealClock.now_with_offset();
+
+        // We can't compare across differing timezones, so we just compare the UTC timestamps.
+        // The clock has millisecond precision, so the second timestamp might be truncated.
+        assert!(
+            fixed_now.timestamp_millis() >= now.timestamp_millis(),
+            "Time mismatch. Local now: {:?}, Fixed now: {:?}",
+            now,
+            fixed_now
+        );
+    }
+
+    #[test]
+    fn now_with_offset_is_the_wall_clock_time() {
+        let clock = MockClock::at_millis(489_237_000);
+        assert_eq!(489_237_000, clock.now_with_offset().timestamp_millis());
+    }
+}
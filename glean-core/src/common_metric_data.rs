@@ -6,7 +6,7 @@ use std::convert::TryFrom;
 
 use crate::error::{Error, ErrorKind};
 use crate::metrics::dynamic_label;
-use crate::Glean;
+use crate::{Glean, INTERNAL_STORAGE};
 
 /// The supported metrics' lifetimes.
 ///
@@ -101,6 +101,29 @@ impl CommonMetricData {
         }
     }
 
+    /// Whether this is one of Glean's own metrics describing its health,
+    /// in the `glean.error`, `glean.upload`, `glean.database` or `glean.metrics` category.
+    pub(crate) fn is_internal(&self) -> bool {
+        matches!(
+            self.category.as_str(),
+            "glean.error" | "glean.upload" | "glean.database" | "glean.metrics"
+        )
+    }
+
+    /// Whether this metric is owned by Glean, rather than by the application.
+    ///
+    /// Glean's own metrics have no category or one in the `glean` namespace,
+    /// or are only kept in Glean's internal storage.
+    pub(crate) fn is_glean_owned(&self) -> bool {
+        self.category.is_empty()
+            || self.category == "glean"
+            || self.category.starts_with("glean.")
+            || self
+                .send_in_pings
+                .iter()
+                .all(|ping| ping == INTERNAL_STORAGE || ping == "glean_client_info")
+    }
+
     /// The metric's unique identifier, including the category, name and label.
     ///
     /// If `category` is empty, it's ommitted.
@@ -6,7 +6,7 @@ use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 use std::fs;
 use std::num::NonZeroU64;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 use std::sync::RwLock;
 
@@ -47,6 +47,7 @@ mod backend {
 }
 
 use crate::metrics::Metric;
+use crate::util::dir_size;
 use crate::CommonMetricData;
 use crate::Glean;
 use crate::Lifetime;
@@ -54,6 +55,9 @@ use crate::Result;
 use backend::*;
 
 pub struct Database {
+    /// The directory the database files are stored in.
+    path: PathBuf,
+
     /// Handle to the database environment.
     rkv: Rkv,
 
@@ -104,6 +108,18 @@ fn file_size(path: &Path) -> Option<NonZeroU64> {
         .and_then(NonZeroU64::new)
 }
 
+/// Get the path of the database file in the given data path.
+///
+/// FIXME(bug 1670634): This is probably more knowledge
+/// than we should have about the database internals.
+pub(crate) fn database_file(data_path: &Path) -> PathBuf {
+    #[cfg(not(feature = "rkv-safe-mode"))]
+    const FILE_NAME: &str = "data.mdb";
+    #[cfg(feature = "rkv-safe-mode")]
+    const FILE_NAME: &str = "data.safe.bin";
+    data_path.join("db").join(FILE_NAME)
+}
+
 impl Database {
     /// Initializes the data store.
     ///
@@ -112,23 +128,14 @@ impl Database {
     ///
     /// It also loads any Lifetime::Ping data that might be
     /// persisted, in case `delay_ping_lifetime_io` is set.
-    pub fn new(data_path: &str, delay_ping_lifetime_io: bool) -> Result<Self> {
-        let path = Path::new(data_path).join("db");
+    pub fn new(data_path: &Path, delay_ping_lifetime_io: bool) -> Result<Self> {
+        let path = data_path.join("db");
         log::debug!("Database path: {:?}", path.display());
 
-        // FIXME(bug 1670634): This is probably more knowledge
-        // than we should have about the database internals.
-        // We could instead iterate over the directory and sum up the file sizes.
-        #[cfg(not(feature = "rkv-safe-mode"))]
-        let file_size = file_size(&path.join("data.mdb"));
-        #[cfg(feature = "rkv-safe-mode")]
-        let file_size = file_size(&path.join("data.safe.bin"));
+        let file_size = file_size(&database_file(data_path));
 
         let rkv = Self::open_rkv(&path)?;
-        let user_store = rkv.open_single(Lifetime::User.as_str(), StoreOptions::create())?;
-        let ping_store = rkv.open_single(Lifetime::Ping.as_str(), StoreOptions::create())?;
-        let application_store =
-            rkv.open_single(Lifetime::Application.as_str(), StoreOptions::create())?;
+        let (user_store, ping_store, application_store) = Self::open_stores(&rkv)?;
         let ping_lifetime_data = if delay_ping_lifetime_io {
             Some(RwLock::new(BTreeMap::new()))
         } else {
@@ -136,6 +143,7 @@ impl Database {
         };
 
         let db = Self {
+            path,
             rkv,
             user_store,
             ping_store,
@@ -171,6 +179,15 @@ impl Database {
         Ok(rkv)
     }
 
+    /// Opens the "lifetime" stores, as `(user, ping, application)`.
+    fn open_stores(rkv: &Rkv) -> Result<(SingleStore, SingleStore, SingleStore)> {
+        let user_store = rkv.open_single(Lifetime::User.as_str(), StoreOptions::create())?;
+        let ping_store = rkv.open_single(Lifetime::Ping.as_str(), StoreOptions::create())?;
+        let application_store =
+            rkv.open_single(Lifetime::Application.as_str(), StoreOptions::create())?;
+        Ok((user_store, ping_store, application_store))
+    }
+
     /// Build the key of the final location of the data in the database.
     /// Such location is built using the storage name and the metric
     /// key/name (if available).
@@ -290,6 +307,58 @@ impl Database {
         }
     }
 
+    /// Iterates with the provided transaction function
+    /// over all the data stored with the given lifetime, across all storages.
+    ///
+    /// * If the storage is unavailable, the transaction function is never invoked.
+    /// * If the read data cannot be deserialized it will be silently skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `lifetime` - The metric lifetime to iterate over.
+    /// * `transaction_fn` - Called for each entry being iterated over. It is
+    ///   passed three arguments: `(storage_name: &str, metric_id: &str, metric: &Metric)`.
+    ///
+    /// # Panics
+    ///
+    /// This function will **not** panic on database errors.
+    pub fn iter_lifetime<F>(&self, lifetime: Lifetime, mut transaction_fn: F)
+    where
+        F: FnMut(&str, &str, &Metric),
+    {
+        let mut call = |key: &str, metric: &Metric| {
+            if let Some((storage_name, metric_id)) = key.split_once('#') {
+                transaction_fn(storage_name, metric_id, metric);
+            }
+        };
+
+        // Lifetime::Ping data is not immediately persisted to disk if
+        // Glean has `delay_ping_lifetime_io` set to true
+        if lifetime == Lifetime::Ping {
+            if let Some(ping_lifetime_data) = &self.ping_lifetime_data {
+                let data = ping_lifetime_data
+                    .read()
+                    .expect("Can't read ping lifetime data");
+                for (key, value) in data.iter() {
+                    call(key, value);
+                }
+                return;
+            }
+        }
+
+        let reader = unwrap_or!(self.rkv.read(), return);
+        let mut iter = unwrap_or!(self.get_store(lifetime).iter_start(&reader), return);
+
+        while let Some(Ok((key, value))) = iter.next() {
+            let key = unwrap_or!(str::from_utf8(key), continue);
+            let metric: Metric = match value {
+                rkv::Value::Blob(blob) => unwrap_or!(bincode::deserialize(blob), continue),
+                _ => continue,
+            };
+            call(key, &metric);
+        }
+    }
+
     /// Determines if the storage has the given metric.
     ///
     /// If data cannot be read it is assumed that the storage does not have the metric.
@@ -352,6 +421,8 @@ impl Database {
             return;
         }
 
+        glean.count_metric_write(data);
+
         let name = data.identifier(glean);
 
         for ping_name in data.storage_names() {
@@ -414,6 +485,8 @@ impl Database {
             return;
         }
 
+        glean.count_metric_write(data);
+
         let name = data.identifier(glean);
         for ping_name in data.storage_names() {
             if let Err(e) =
@@ -508,10 +581,11 @@ impl Database {
         // Lifetime::Ping data will be saved to `ping_lifetime_data`
         // in case `delay_ping_lifetime_io` is set to true
         if let Some(ping_lifetime_data) = &self.ping_lifetime_data {
+            let storage_key = Self::get_storage_key(storage_name, None);
             ping_lifetime_data
                 .write()
                 .expect("Can't access ping lifetime data as writable")
-                .clear();
+                .retain(|metric_id, _| !metric_id.starts_with(&storage_key));
         }
 
         self.write_with_store(Lifetime::Ping, |mut writer, store| {
@@ -658,6 +732,111 @@ impl Database {
         }
         Ok(())
     }
+
+    /// Rewrites the database, reclaiming the space left behind by removed entries.
+    ///
+    /// The stored data is copied to a new database next to the current one,
+    /// which then takes its place. Data with ping lifetime held in memory is not affected.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes reclaimed on disk.
+    ///
+    /// # Errors
+    ///
+    /// Any error copying the data or moving the database files.
+    /// The current database is kept in that case.
+    pub fn compact(&mut self) -> Result<u64> {
+        let size_before = dir_size(&self.path);
+        let path = self.path.clone();
+        let compact_path = path.with_extension("compact");
+        let old_path = path.with_extension("old");
+        let swap_path = path.with_extension("swap");
+        // Leftovers of an interrupted compaction.
+        let _ = fs::remove_dir_all(&compact_path);
+        let _ = fs::remove_dir_all(&old_path);
+        let _ = fs::remove_dir_all(&swap_path);
+
+        if let Err(e) = self.copy_to(&compact_path) {
+            let _ = fs::remove_dir_all(&compact_path);
+            return Err(e);
+        }
+
+        // Directories with open files can't be moved on Windows,
+        // so an empty database takes the place of the current one while it is replaced.
+        if let Err(e) = self.reopen(&swap_path) {
+            let _ = fs::remove_dir_all(&compact_path);
+            let _ = fs::remove_dir_all(&swap_path);
+            return Err(e);
+        }
+
+        let replaced = fs::rename(&path, &old_path)
+            .and_then(|_| fs::rename(&compact_path, &path))
+            .map_err(Into::into)
+            .and_then(|_| self.reopen(&path));
+        if let Err(e) = replaced {
+            // Move the current database back, if it was moved.
+            if old_path.exists() {
+                let _ = fs::remove_dir_all(&path);
+                let _ = fs::rename(&old_path, &path);
+            }
+            let _ = fs::remove_dir_all(&compact_path);
+            match self.reopen(&path) {
+                Ok(()) => {
+                    let _ = fs::remove_dir_all(&swap_path);
+                }
+                Err(e) => log::error!("Failed to reopen the database: {}", e),
+            }
+            return Err(e);
+        }
+
+        if let Err(e) = fs::remove_dir_all(&old_path) {
+            log::warn!("Failed to remove the database before compaction: {}", e);
+        }
+        let _ = fs::remove_dir_all(&swap_path);
+
+        let size_after = dir_size(&path);
+        log::info!(
+            "Compacted the database from {} to {} bytes",
+            size_before,
+            size_after
+        );
+        Ok(size_before.saturating_sub(size_after))
+    }
+
+    /// Opens the database in the given directory, closing the current one.
+    ///
+    /// The path the database was opened with is kept.
+    fn reopen(&mut self, path: &Path) -> Result<()> {
+        let rkv = Self::open_rkv(path)?;
+        let (user_store, ping_store, application_store) = Self::open_stores(&rkv)?;
+        self.user_store = user_store;
+        self.ping_store = ping_store;
+        self.application_store = application_store;
+        self.rkv = rkv;
+        Ok(())
+    }
+
+    /// Copies all data stored on disk to a new database in the given directory.
+    fn copy_to(&self, path: &Path) -> Result<()> {
+        let rkv = Self::open_rkv(path)?;
+        let (user_store, ping_store, application_store) = Self::open_stores(&rkv)?;
+        let reader = self.rkv.read()?;
+
+        for (lifetime, store) in &[
+            (Lifetime::User, user_store),
+            (Lifetime::Ping, ping_store),
+            (Lifetime::Application, application_store),
+        ] {
+            let mut writer = rkv.write()?;
+            let mut iter = self.get_store(*lifetime).iter_start(&reader)?;
+            while let Some((key, value)) = iter.next().transpose()? {
+                store.put(&mut writer, key, &value)?;
+            }
+            writer.commit()?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -670,15 +849,14 @@ mod test {
 
     #[test]
     fn test_panicks_if_fails_dir_creation() {
-        assert!(Database::new("/!#\"'@#°ç", false).is_err());
+        assert!(Database::new(Path::new("/!#\"'@#°ç"), false).is_err());
     }
 
     #[test]
     fn test_data_dir_rkv_inits() {
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
 
-        Database::new(&str_dir, false).unwrap();
+        Database::new(dir.path(), false).unwrap();
 
         assert!(dir.path().exists());
     }
@@ -687,8 +865,7 @@ mod test {
     fn test_ping_lifetime_metric_recorded() {
         // Init the database in a temporary directory.
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
-        let db = Database::new(&str_dir, false).unwrap();
+        let db = Database::new(dir.path(), false).unwrap();
 
         assert!(db.ping_lifetime_data.is_none());
 
@@ -724,8 +901,7 @@ mod test {
     fn test_application_lifetime_metric_recorded() {
         // Init the database in a temporary directory.
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
-        let db = Database::new(&str_dir, false).unwrap();
+        let db = Database::new(dir.path(), false).unwrap();
 
         // Attempt to record a known value.
         let test_value = "test-value";
@@ -762,8 +938,7 @@ mod test {
     fn test_user_lifetime_metric_recorded() {
         // Init the database in a temporary directory.
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
-        let db = Database::new(&str_dir, false).unwrap();
+        let db = Database::new(dir.path(), false).unwrap();
 
         // Attempt to record a known value.
         let test_value = "test-value";
@@ -793,12 +968,63 @@ mod test {
         assert_eq!(1, found_metrics, "We only expect 1 Lifetime.User metric.");
     }
 
+    #[test]
+    fn test_iter_lifetime_covers_all_storages() {
+        for &delay_ping_lifetime_io in &[false, true] {
+            // Init the database in a temporary directory.
+            let dir = tempdir().unwrap();
+            let db = Database::new(dir.path(), delay_ping_lifetime_io).unwrap();
+
+            for storage in &["store1", "store2"] {
+                db.record_per_lifetime(
+                    Lifetime::Ping,
+                    storage,
+                    "telemetry_test.counter",
+                    &Metric::Counter(1),
+                )
+                .unwrap();
+            }
+            db.record_per_lifetime(
+                Lifetime::User,
+                "store1",
+                "telemetry_test.user",
+                &Metric::Counter(2),
+            )
+            .unwrap();
+
+            let mut found = Vec::new();
+            db.iter_lifetime(Lifetime::Ping, |storage_name, metric_id, metric| {
+                found.push((
+                    storage_name.to_string(),
+                    metric_id.to_string(),
+                    metric.clone(),
+                ));
+            });
+            found.sort_by(|a, b| a.0.cmp(&b.0));
+
+            assert_eq!(
+                vec![
+                    (
+                        "store1".to_string(),
+                        "telemetry_test.counter".to_string(),
+                        Metric::Counter(1)
+                    ),
+                    (
+                        "store2".to_string(),
+                        "telemetry_test.counter".to_string(),
+                        Metric::Counter(1)
+                    ),
+                ],
+                found
+            );
+        }
+    }
+
     #[test]
     fn test_clear_ping_storage() {
         // Init the database in a temporary directory.
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
-        let db = Database::new(&str_dir, false).unwrap();
+        let db = Database::new(dir.path(), false).unwrap();
 
         // Attempt to record a known value for every single lifetime.
         let test_storage = "test-storage";
@@ -873,8 +1099,7 @@ mod test {
     fn test_remove_single_metric() {
         // Init the database in a temporary directory.
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
-        let db = Database::new(&str_dir, false).unwrap();
+        let db = Database::new(dir.path(), false).unwrap();
 
         let test_storage = "test-storage-single-lifetime";
         let metric_id_pattern = "telemetry_test.single_metric";
@@ -926,12 +1151,73 @@ mod test {
         }
     }
 
+    // The safe-mode backend rewrites its file on every commit, so there's nothing to reclaim.
+    #[cfg(not(feature = "rkv-safe-mode"))]
+    #[test]
+    fn test_compact_reclaims_space_of_removed_metrics() {
+        // Init the database in a temporary directory.
+        let dir = tempdir().unwrap();
+        let mut db = Database::new(dir.path(), false).unwrap();
+
+        let test_storage = "test-storage-compact";
+        let db_size = || dir_size(&dir.path().join("db"));
+
+        db.record_per_lifetime(
+            Lifetime::User,
+            test_storage,
+            "telemetry_test.retained",
+            &Metric::String("retain".into()),
+        )
+        .unwrap();
+        for i in 0..1000 {
+            db.record_per_lifetime(
+                Lifetime::Application,
+                test_storage,
+                &format!("telemetry_test.removed_{}", i),
+                &Metric::String("x".repeat(100)),
+            )
+            .unwrap();
+        }
+        db.clear_lifetime(Lifetime::Application);
+        let size_before = db_size();
+
+        let reclaimed = db.compact().unwrap();
+        assert!(reclaimed > 0);
+        assert_eq!(size_before - reclaimed, db_size());
+        assert!(!dir.path().join("db.compact").exists());
+        assert!(!dir.path().join("db.old").exists());
+        assert!(!dir.path().join("db.swap").exists());
+
+        // The remaining data is kept and the compacted database is usable.
+        let mut found = vec![];
+        db.iter_store_from(Lifetime::User, test_storage, None, |metric_id, metric| {
+            found.push((
+                String::from_utf8_lossy(metric_id).into_owned(),
+                metric.clone(),
+            ));
+        });
+        assert_eq!(
+            vec![(
+                "telemetry_test.retained".to_string(),
+                Metric::String("retain".into())
+            )],
+            found
+        );
+        db.record_per_lifetime(
+            Lifetime::Application,
+            test_storage,
+            "telemetry_test.new",
+            &Metric::String("new".into()),
+        )
+        .unwrap();
+        assert!(db.has_metric(Lifetime::Application, test_storage, "telemetry_test.new"));
+    }
+
     #[test]
     fn test_delayed_ping_lifetime_persistence() {
         // Init the database in a temporary directory.
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
-        let db = Database::new(&str_dir, true).unwrap();
+        let db = Database::new(dir.path(), true).unwrap();
         let test_storage = "test-storage";
 
         assert!(db.ping_lifetime_data.is_some());
@@ -1037,18 +1323,41 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_clear_delayed_ping_lifetime_storage_only_clears_one_store() {
+        // Init the database in a temporary directory.
+        let dir = tempdir().unwrap();
+        let db = Database::new(dir.path(), true).unwrap();
+
+        let test_metric_id = "telemetry_test.test_name";
+        for store in &["store1", "store2"] {
+            db.record_per_lifetime(
+                Lifetime::Ping,
+                store,
+                test_metric_id,
+                &Metric::String("test-value".to_string()),
+            )
+            .unwrap();
+        }
+
+        db.clear_ping_lifetime_storage("store1").unwrap();
+
+        let data = db.ping_lifetime_data.as_ref().unwrap().read().unwrap();
+        assert!(data.get(&format!("store1#{}", test_metric_id)).is_none());
+        assert!(data.get(&format!("store2#{}", test_metric_id)).is_some());
+    }
+
     #[test]
     fn test_load_ping_lifetime_data_from_memory() {
         // Init the database in a temporary directory.
         let dir = tempdir().unwrap();
-        let str_dir = dir.path().display().to_string();
 
         let test_storage = "test-storage";
         let test_value = "test-value";
         let test_metric_id = "telemetry_test.test_name";
 
         {
-            let db = Database::new(&str_dir, true).unwrap();
+            let db = Database::new(dir.path(), true).unwrap();
 
             // Attempt to record a known value.
             db.record_per_lifetime(
@@ -1087,7 +1396,7 @@ mod test {
         // Now create a new instace of the db and check if data was
         // correctly loaded from rkv to memory.
         {
-            let db = Database::new(&str_dir, true).unwrap();
+            let db = Database::new(dir.path(), true).unwrap();
 
             // Verify that test_value is in memory.
             let data = match &db.ping_lifetime_data {
@@ -1117,7 +1426,6 @@ mod test {
         let (mut glean, dir) = new_glean(None);
 
         // Init the database in a temporary directory.
-        let str_dir = dir.path().display().to_string();
 
         let test_storage = "test-storage";
         let test_data = CommonMetricData::new("category", "name", test_storage);
@@ -1125,7 +1433,7 @@ mod test {
 
         // Attempt to record metric with the record and record_with functions,
         // this should work since upload is enabled.
-        let db = Database::new(&str_dir, true).unwrap();
+        let db = Database::new(dir.path(), true).unwrap();
         db.record(&glean, &test_data, &Metric::String("record".to_owned()));
         db.iter_store_from(
             Lifetime::Ping,
@@ -182,7 +182,7 @@ fn tokenize_string(value: String) -> Option<Vec<String>> {
 /// The regex crate isn't used here because it adds to the binary size,
 /// and the Glean SDK doesn't use regular expressions anywhere else.
 #[allow(clippy::ptr_arg)]
-fn validate_tag(value: &String) -> bool {
+pub(crate) fn validate_tag(value: &String) -> bool {
     if value.is_empty() {
         log::error!("A tag must have at least one character.");
         return false;
@@ -204,7 +204,7 @@ fn validate_tag(value: &String) -> bool {
             }
         }
         count += 1;
-        if count == 20 {
+        if count > 20 {
             log::error!("A tag cannot exceed 20 characters.");
             return false;
         }
@@ -216,7 +216,7 @@ fn validate_tag(value: &String) -> bool {
 /// This builds upon the existing `validate_tag` function, since all the
 /// tags should respect the same rules to make the pipeline happy.
 #[allow(clippy::ptr_arg)]
-fn validate_source_tags(tags: &Vec<String>) -> bool {
+pub(crate) fn validate_source_tags(tags: &Vec<String>) -> bool {
     if tags.is_empty() {
         return false;
     }
@@ -288,12 +288,14 @@ mod test {
     fn validates_tag_correctly() {
         assert!(validate_tag(&"valid-value".to_string()));
         assert!(validate_tag(&"-also-valid-value".to_string()));
+        assert!(validate_tag(&"exactly-20-chars-tag".to_string()));
         assert!(!validate_tag(&"invalid_value".to_string()));
         assert!(!validate_tag(&"invalid value".to_string()));
         assert!(!validate_tag(&"!nv@lid-val*e".to_string()));
         assert!(!validate_tag(
             &"invalid-value-because-way-too-long".to_string()
         ));
+        assert!(!validate_tag(&"exactly-21-chars-tags".to_string()));
         assert!(!validate_tag(&"".to_string()));
     }
 
@@ -67,6 +67,9 @@ pub enum ErrorKind {
 
     /// Ping request body size overflowed
     PingBodyOverflow(usize),
+
+    /// A ping with this name is not registered
+    PingNotFound(String),
 }
 
 /// A specialized [`Error`] type for this crate's operations.
@@ -123,6 +126,7 @@ impl Display for Error {
                 "Ping request body size exceeded maximum size allowed: {}kB.",
                 s / 1024
             ),
+            PingNotFound(name) => write!(f, "No ping named '{}' is registered", name),
         }
     }
 }
@@ -56,6 +56,9 @@ impl RecordedEvent {
     }
 }
 
+/// The timestamp and extras of an event, as compared to detect duplicates.
+type LastRecordedEvent = (u64, Option<HashMap<String, String>>);
+
 /// This struct handles the in-memory and on-disk storage logic for events.
 ///
 /// So that the data survives shutting down of the application, events are stored
@@ -76,6 +79,9 @@ pub struct EventDatabase {
     event_stores: RwLock<HashMap<String, Vec<RecordedEvent>>>,
     /// A lock to be held when doing operations on the filesystem
     file_lock: RwLock<()>,
+    /// The timestamp and extras of the last recorded event, by event metric.
+    /// Used to detect duplicates if a deduplication window is configured.
+    last_recorded: RwLock<HashMap<String, LastRecordedEvent>>,
 }
 
 impl EventDatabase {
@@ -85,14 +91,15 @@ impl EventDatabase {
     ///
     /// * `data_path` - The directory to store events in. A new directory
     /// * `events` - will be created inside of this directory.
-    pub fn new(data_path: &str) -> Result<Self> {
-        let path = Path::new(data_path).join("events");
+    pub fn new(data_path: &Path) -> Result<Self> {
+        let path = data_path.join("events");
         create_dir_all(&path)?;
 
         Ok(Self {
             path,
             event_stores: RwLock::new(HashMap::new()),
             file_lock: RwLock::new(()),
+            last_recorded: RwLock::new(HashMap::new()),
         })
     }
 
@@ -169,6 +176,34 @@ impl EventDatabase {
         ping_sent
     }
 
+    /// Checks whether an event is a duplicate of the last recorded event of the same metric,
+    /// i.e. it has the same extras and happened within the configured deduplication window.
+    ///
+    /// Events that are not duplicates are remembered as the last recorded event.
+    fn is_duplicate(
+        &self,
+        glean: &Glean,
+        meta: &CommonMetricData,
+        timestamp: u64,
+        extra: &Option<HashMap<String, String>>,
+    ) -> bool {
+        let window = match glean.event_dedup_window() {
+            Some(window) => window.as_millis() as u64,
+            None => return false,
+        };
+
+        let identifier = meta.base_identifier();
+        let mut last_recorded = self.last_recorded.write().unwrap(); // safe unwrap, only error case is poisoning
+        if let Some((last_timestamp, last_extra)) = last_recorded.get(&identifier) {
+            if last_extra == extra && timestamp.saturating_sub(*last_timestamp) < window {
+                return true;
+            }
+        }
+
+        last_recorded.insert(identifier, (timestamp, extra.clone()));
+        false
+    }
+
     /// Records an event in the desired stores.
     ///
     /// # Arguments
@@ -180,18 +215,33 @@ impl EventDatabase {
     ///   monotonically increasing timer (this value is obtained on the
     ///   platform-specific side).
     /// * `extra` - Extra data values, mapping strings to strings.
+    ///
+    /// # Returns
+    ///
+    /// Whether a ping was submitted because a store reached the maximum number of events.
     pub fn record(
         &self,
         glean: &Glean,
         meta: &CommonMetricData,
         timestamp: u64,
         extra: Option<HashMap<String, String>>,
-    ) {
+    ) -> bool {
         // If upload is disabled we don't want to record.
         if !glean.is_upload_enabled() {
-            return;
+            return false;
+        }
+
+        if self.is_duplicate(glean, meta, timestamp, &extra) {
+            log::info!(
+                "Suppressed a duplicate of the event {}",
+                meta.base_identifier()
+            );
+            glean.record_suppressed_duplicate_event(meta);
+            return false;
         }
 
+        glean.count_metric_write(meta);
+
         // Create RecordedEvent object, and its JSON form for serialization
         // on disk.
         let event = RecordedEvent {
@@ -218,16 +268,19 @@ impl EventDatabase {
 
         // If any of the event stores reached maximum size, submit the pings
         // containing those events immediately.
+        let mut submitted = false;
         for store_name in stores_to_submit {
-            if let Err(err) = glean.submit_ping_by_name(store_name, Some("max_capacity")) {
-                log::error!(
+            match glean.submit_ping_by_name(store_name, Some("max_capacity")) {
+                Ok(ping_submitted) => submitted |= ping_submitted,
+                Err(err) => log::error!(
                     "Got more than {} events, but could not send {} ping: {}",
                     glean.get_max_events(),
                     store_name,
                     err
-                );
+                ),
             }
         }
+        submitted
     }
 
     /// Writes an event to a single store on disk.
@@ -250,6 +303,10 @@ impl EventDatabase {
 
     /// Gets a snapshot of the stored event data as a JsonValue.
     ///
+    /// Events are ordered by their timestamp.
+    /// Events with the same timestamp keep the order they were recorded in,
+    /// also if they were loaded from disk after a restart.
+    ///
     /// # Arguments
     ///
     /// * `store_name` - The name of the desired store.
@@ -269,7 +326,10 @@ impl EventDatabase {
                     // events to a file on disk, where this would be expensive. Best to
                     // handle this in every case (whether events came from disk or memory)
                     // in a single location.
-                    store.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+                    // The sort is stable, so events with the same timestamp stay in the
+                    // order they were recorded in: `record` appends them to the store in
+                    // memory and on disk while holding the same lock.
+                    store.sort_by_key(|event| event.timestamp);
                     let first_timestamp = store[0].timestamp;
                     Some(JsonValue::from_iter(
                         store.iter().map(|e| e.serialize_relative(first_timestamp)),
@@ -305,6 +365,8 @@ impl EventDatabase {
     pub fn clear_all(&self) -> Result<()> {
         // safe unwrap, only error case is poisoning
         self.event_stores.write().unwrap().clear();
+        // safe unwrap, only error case is poisoning
+        self.last_recorded.write().unwrap().clear();
 
         // safe unwrap, only error case is poisoning
         let _lock = self.file_lock.write().unwrap();
@@ -370,7 +432,7 @@ mod test {
         let t = tempfile::tempdir().unwrap();
 
         {
-            let db = EventDatabase::new(&t.path().display().to_string()).unwrap();
+            let db = EventDatabase::new(t.path()).unwrap();
             db.write_event_to_disk("events", "{\"timestamp\": 500");
             db.write_event_to_disk("events", "{\"timestamp\"");
             db.write_event_to_disk(
@@ -380,7 +442,7 @@ mod test {
         }
 
         {
-            let db = EventDatabase::new(&t.path().display().to_string()).unwrap();
+            let db = EventDatabase::new(t.path()).unwrap();
             db.load_events_from_disk().unwrap();
             let events = &db.event_stores.read().unwrap()["events"];
             assert_eq!(1, events.len());
@@ -462,7 +524,7 @@ mod test {
     #[test]
     fn doesnt_record_when_upload_is_disabled() {
         let (mut glean, dir) = new_glean(None);
-        let db = EventDatabase::new(dir.path().to_str().unwrap()).unwrap();
+        let db = EventDatabase::new(dir.path()).unwrap();
 
         let test_storage = "test-storage";
         let test_category = "category";
@@ -122,6 +122,85 @@ impl UploadMetrics {
     }
 }
 
+#[derive(Debug)]
+pub struct PingMetrics {
+    pub rate_limit_exceeded: LabeledMetric<CounterMetric>,
+    pub metric_write_count: CounterMetric,
+}
+
+impl PingMetrics {
+    pub fn new() -> PingMetrics {
+        PingMetrics {
+            rate_limit_exceeded: LabeledMetric::new(
+                CounterMetric::new(CommonMetricData {
+                    name: "ping_rate_limit_exceeded".into(),
+                    category: "glean.error".into(),
+                    send_in_pings: vec!["metrics".into()],
+                    lifetime: Lifetime::Ping,
+                    disabled: false,
+                    dynamic_label: None,
+                }),
+                None,
+            ),
+
+            metric_write_count: CounterMetric::new(CommonMetricData {
+                name: "write_count".into(),
+                category: "glean.metrics".into(),
+                send_in_pings: vec!["metrics".into()],
+                lifetime: Lifetime::Ping,
+                disabled: false,
+                dynamic_label: None,
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EventMetrics {
+    pub duplicates_suppressed: LabeledMetric<CounterMetric>,
+}
+
+impl EventMetrics {
+    pub fn new() -> EventMetrics {
+        EventMetrics {
+            duplicates_suppressed: LabeledMetric::new(
+                CounterMetric::new(CommonMetricData {
+                    name: "event_duplicates_suppressed".into(),
+                    category: "glean.error".into(),
+                    send_in_pings: vec!["metrics".into()],
+                    lifetime: Lifetime::Ping,
+                    disabled: false,
+                    dynamic_label: None,
+                }),
+                None,
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ExperimentMetrics {
+    pub exposure: EventMetric,
+}
+
+impl ExperimentMetrics {
+    pub fn new() -> ExperimentMetrics {
+        ExperimentMetrics {
+            exposure: EventMetric::new(
+                CommonMetricData {
+                    name: "exposure".into(),
+                    category: "nimbus".into(),
+                    send_in_pings: vec!["events".into(), "baseline".into()],
+                    lifetime: Lifetime::Ping,
+                    disabled: false,
+                    dynamic_label: None,
+                },
+                vec!["experiment_id".into()],
+            ),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct DatabaseMetrics {
     pub size: MemoryDistributionMetric,
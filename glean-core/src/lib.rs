@@ -13,7 +13,12 @@
 //! ## [The Glean SDK Book](https://mozilla.github.io/glean)
 
 use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{DateTime, FixedOffset};
 use once_cell::sync::Lazy;
@@ -25,6 +30,7 @@ use uuid::Uuid;
 // alphabetizing it.
 mod macros;
 
+mod clock;
 mod common_metric_data;
 mod database;
 mod debug;
@@ -38,27 +44,36 @@ pub mod metrics;
 pub mod ping;
 pub mod storage;
 mod system;
+#[cfg(feature = "opentelemetry")]
+mod trace_context;
 pub mod traits;
 pub mod upload;
 mod util;
 
+#[cfg(any(test, feature = "test-support"))]
+pub use crate::clock::MockClock;
+pub use crate::clock::{Clock, RealClock};
 pub use crate::common_metric_data::{CommonMetricData, Lifetime};
 use crate::database::Database;
 use crate::debug::DebugOptions;
 pub use crate::error::{Error, ErrorKind, Result};
 pub use crate::error_recording::{test_get_num_recorded_errors, ErrorType};
 use crate::event_database::EventDatabase;
-use crate::internal_metrics::{CoreMetrics, DatabaseMetrics};
+use crate::internal_metrics::{
+    CoreMetrics, DatabaseMetrics, EventMetrics, ExperimentMetrics, PingMetrics,
+};
 use crate::internal_pings::InternalPings;
 use crate::metrics::{Metric, MetricType, PingType};
-use crate::ping::PingMaker;
+use crate::ping::{PingMaker, PingRateLimiter};
 use crate::storage::StorageManager;
-use crate::upload::{PingUploadManager, PingUploadTask, UploadResult};
-use crate::util::{local_now_with_offset, sanitize_application_id};
+use crate::upload::{PingRequest, PingUploadManager, PingUploadTask, UploadResult};
+use crate::util::sanitize_application_id;
 
 const GLEAN_VERSION: &str = env!("CARGO_PKG_VERSION");
 const GLEAN_SCHEMA_VERSION: u32 = 1;
 const DEFAULT_MAX_EVENTS: usize = 500;
+/// The default maximum number of ping uploads per interval.
+const DEFAULT_UPLOAD_RATE_LIMIT: (u32, Duration) = (15, Duration::from_secs(60));
 static KNOWN_CLIENT_ID: Lazy<Uuid> =
     Lazy::new(|| Uuid::parse_str("c0ffeec0-ffee-c0ff-eec0-ffeec0ffeec0").unwrap());
 // An internal ping name, not to be touched by anything else
@@ -82,6 +97,35 @@ pub fn global_glean() -> Option<&'static Mutex<Glean>> {
     GLEAN.get()
 }
 
+/// Checks whether a value is a valid tag for the `X-Debug-ID` and `X-Source-Tags` headers.
+///
+/// A valid tag matches the regex: "[a-zA-Z0-9-]{1,20}".
+/// The reason an invalid tag is rejected is logged.
+pub fn is_valid_tag(value: &str) -> bool {
+    debug::validate_tag(&value.to_string())
+}
+
+/// Checks whether a list of tags is valid for the `X-Source-Tags` header.
+///
+/// The list must contain 1 to 5 valid tags, see [`is_valid_tag`](fn.is_valid_tag.html).
+/// Tags starting with `glean` are reserved.
+/// The reason invalid tags are rejected is logged.
+pub fn is_valid_source_tags(tags: &[String]) -> bool {
+    debug::validate_source_tags(&tags.to_vec())
+}
+
+/// Gets the size in bytes of the data Glean stores in `data_path`:
+/// the database file and all pending pings, including deletion-request pings.
+///
+/// This only reads the sizes of the files, so it doesn't need the Glean object.
+/// Files that can't be read are not counted.
+pub fn storage_size_bytes(data_path: &Path) -> u64 {
+    let database_size = fs::metadata(database::database_file(data_path)).map_or(0, |m| m.len());
+    database_size
+        + util::dir_size(&data_path.join(PENDING_PINGS_DIRECTORY))
+        + util::dir_size(&data_path.join(DELETION_REQUEST_PINGS_DIRECTORY))
+}
+
 /// Sets or replaces the global Glean object.
 pub fn setup_glean(glean: Glean) -> Result<()> {
     // The `OnceCell` type wrapping our Glean is thread-safe and can only be set once.
@@ -117,7 +161,7 @@ pub struct Configuration {
     /// Whether upload should be enabled.
     pub upload_enabled: bool,
     /// Path to a directory to store all data in.
-    pub data_path: String,
+    pub data_path: PathBuf,
     /// The application ID (will be sanitized during initialization).
     pub application_id: String,
     /// The name of the programming language used by the binding creating this instance of Glean.
@@ -126,6 +170,25 @@ pub struct Configuration {
     pub max_events: Option<usize>,
     /// Whether Glean should delay persistence of data from metrics with ping lifetime.
     pub delay_ping_lifetime_io: bool,
+    /// Whether ping request bodies are gzip compressed before upload.
+    pub compress_pings: bool,
+    /// The maximum number of times each ping can be submitted per minute.
+    /// Further submissions are dropped. The `baseline` and `deletion-request` pings are never limited.
+    pub max_pings_per_minute: Option<u32>,
+    /// Server endpoints for pings that are not sent to the default endpoint, by ping name.
+    /// Ping names may contain `*` wildcards, matching any sequence of characters.
+    pub extra_server_endpoints: HashMap<String, String>,
+    /// Whether Glean's own `glean.error`, `glean.upload`, `glean.database` and `glean.metrics` metrics are not recorded.
+    pub disable_internal_error_metrics: bool,
+    /// The maximum number of ping uploads per interval, as `(max_uploads, interval)`.
+    /// Defaults to 15 uploads every 60 seconds if not set.
+    pub rate_limit: Option<(u32, Duration)>,
+    /// If set, identical events (same metric and extras) recorded within this window
+    /// of the last recorded one are dropped and counted in `glean.error.event_duplicates_suppressed`.
+    pub event_dedup_window: Option<Duration>,
+    /// Whether pings carry a `traceparent` header with the OpenTelemetry span they were submitted in.
+    /// Only takes effect with the `opentelemetry` feature.
+    pub enable_trace_context: bool,
 }
 
 /// The object holding meta information about a Glean instance.
@@ -144,6 +207,13 @@ pub struct Configuration {
 ///     upload_enabled: true,
 ///     max_events: None,
 ///     delay_ping_lifetime_io: false,
+///     compress_pings: true,
+///     max_pings_per_minute: None,
+///     extra_server_endpoints: Default::default(),
+///     disable_internal_error_metrics: false,
+///     rate_limit: None,
+///     event_dedup_window: None,
+///     enable_trace_context: false,
 /// };
 /// let mut glean = Glean::new(cfg).unwrap();
 /// let ping = PingType::new("sample", true, false, vec![]);
@@ -172,15 +242,34 @@ pub struct Glean {
     event_data_store: EventDatabase,
     core_metrics: CoreMetrics,
     database_metrics: DatabaseMetrics,
+    ping_metrics: PingMetrics,
     internal_pings: InternalPings,
     data_path: PathBuf,
     application_id: String,
     ping_registry: HashMap<String, PingType>,
+    /// The names of the registered ping types, in the order they were first registered.
+    ping_registration_order: Vec<String>,
     start_time: DateTime<FixedOffset>,
     max_events: usize,
     is_first_run: bool,
     upload_manager: PingUploadManager,
     debug: DebugOptions,
+    // The last document id handed out, if document ids are deterministic.
+    doc_id_counter: Option<AtomicU64>,
+    // Limits how often pings can be submitted, if configured.
+    ping_rate_limiter: Option<Mutex<PingRateLimiter>>,
+    // Whether Glean's own `glean.error`, `glean.upload`, `glean.database` and `glean.metrics` metrics are not recorded.
+    disable_internal_error_metrics: bool,
+    // The number of metric writes since the last `metrics` ping.
+    metric_write_count: AtomicU64,
+    // Identical events of the same metric within this window are only recorded once, if configured.
+    event_dedup_window: Option<Duration>,
+    // Whether pings carry a `traceparent` header with the current OpenTelemetry span.
+    enable_trace_context: bool,
+    event_metrics: EventMetrics,
+    experiment_metrics: ExperimentMetrics,
+    // The source of the current time.
+    clock: Arc<dyn Clock>,
 }
 
 impl Glean {
@@ -201,11 +290,12 @@ impl Glean {
         let data_store = Some(Database::new(&cfg.data_path, cfg.delay_ping_lifetime_io)?);
         let event_data_store = EventDatabase::new(&cfg.data_path)?;
 
-        // Create an upload manager with rate limiting of 10 pings every 60 seconds.
+        // Create an upload manager with rate limiting of 15 pings every 60 seconds, by default.
         let mut upload_manager = PingUploadManager::new(&cfg.data_path, &cfg.language_binding_name);
-        upload_manager.set_rate_limiter(
-            /* seconds per interval */ 60, /* max tasks per interval */ 15,
-        );
+        let (max_uploads, interval) = cfg.rate_limit.unwrap_or(DEFAULT_UPLOAD_RATE_LIMIT);
+        upload_manager.set_rate_limiter(interval, max_uploads);
+        upload_manager.set_compress_pings(cfg.compress_pings);
+        upload_manager.set_extra_server_endpoints(cfg.extra_server_endpoints.clone());
 
         // We only scan the pending ping sdirectories when calling this from a subprocess,
         // when calling this from ::new we need to scan the directories after dealing with the upload state.
@@ -219,15 +309,28 @@ impl Glean {
             event_data_store,
             core_metrics: CoreMetrics::new(),
             database_metrics: DatabaseMetrics::new(),
+            ping_metrics: PingMetrics::new(),
             internal_pings: InternalPings::new(),
             upload_manager,
-            data_path: PathBuf::from(&cfg.data_path),
+            data_path: cfg.data_path.clone(),
             application_id,
             ping_registry: HashMap::new(),
-            start_time: local_now_with_offset(),
+            ping_registration_order: Vec::new(),
+            start_time: RealClock.now_with_offset(),
             max_events: cfg.max_events.unwrap_or(DEFAULT_MAX_EVENTS),
             is_first_run: false,
             debug: DebugOptions::new(),
+            doc_id_counter: None,
+            ping_rate_limiter: cfg
+                .max_pings_per_minute
+                .map(|max| Mutex::new(PingRateLimiter::new(max))),
+            disable_internal_error_metrics: cfg.disable_internal_error_metrics,
+            metric_write_count: AtomicU64::new(0),
+            event_dedup_window: cfg.event_dedup_window,
+            enable_trace_context: cfg.enable_trace_context,
+            event_metrics: EventMetrics::new(),
+            experiment_metrics: ExperimentMetrics::new(),
+            clock: Arc::new(RealClock),
         })
     }
 
@@ -292,6 +395,13 @@ impl Glean {
             upload_enabled,
             max_events: None,
             delay_ping_lifetime_io: false,
+            compress_pings: true,
+            max_pings_per_minute: None,
+            extra_server_endpoints: Default::default(),
+            disable_internal_error_metrics: false,
+            rate_limit: None,
+            event_dedup_window: None,
+            enable_trace_context: false,
         };
 
         let mut glean = Self::new(cfg).unwrap();
@@ -407,6 +517,11 @@ impl Glean {
         self.upload_enabled
     }
 
+    /// Determines whether Glean's own `glean.error`, `glean.upload`, `glean.database` and `glean.metrics` metrics are disabled.
+    pub(crate) fn internal_error_metrics_disabled(&self) -> bool {
+        self.disable_internal_error_metrics
+    }
+
     /// Handles the changing of state from upload disabled to enabled.
     ///
     /// Should only be called when the state actually changes.
@@ -536,6 +651,54 @@ impl Glean {
         self.upload_manager.get_upload_task(self, self.log_pings())
     }
 
+    /// Gets the server endpoint a ping request is sent to,
+    /// if it is not the default endpoint.
+    ///
+    /// The endpoints are configured by ping name in `Configuration::extra_server_endpoints`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request of the ping to upload.
+    ///
+    /// # Returns
+    ///
+    /// The server endpoint, or `None` if the default endpoint should be used.
+    pub fn server_endpoint_for(&self, request: &PingRequest) -> Option<&str> {
+        self.upload_manager.server_endpoint_for(request)
+    }
+
+    /// Sets the maximum number of pending pings.
+    ///
+    /// Once there are more pending pings, the oldest ones are deleted.
+    /// Deletion-request pings are never deleted and don't count towards this limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_count` - The maximum number of pending pings.
+    pub fn set_max_pending_pings(&mut self, max_count: usize) {
+        self.upload_manager
+            .set_max_pending_pings_count(max_count as u64);
+        self.upload_manager.enforce_pending_pings_count(self);
+    }
+
+    /// Gets the number of pings waiting to be uploaded.
+    ///
+    /// This includes pings from previous runs that were not uploaded yet.
+    pub fn pending_pings_count(&self) -> usize {
+        self.upload_manager.pending_pings_count()
+    }
+
+    /// Gets the maximum number of pending pings.
+    ///
+    /// # Returns
+    ///
+    /// The maximum number, or `None` if the number of pending pings is not limited.
+    pub fn max_pending_pings(&self) -> Option<usize> {
+        self.upload_manager
+            .max_pending_pings_count()
+            .map(|max_count| max_count.try_into().unwrap_or(usize::MAX))
+    }
+
     /// Processes the response from an attempt to upload a ping.
     ///
     /// # Arguments
@@ -573,6 +736,19 @@ impl Glean {
         )
     }
 
+    /// Generates the document id for a new ping.
+    ///
+    /// This is a random UUID, unless deterministic document ids were requested.
+    fn generate_doc_id(&self) -> String {
+        match &self.doc_id_counter {
+            Some(counter) => {
+                let next = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                Uuid::from_u128(next.into()).to_string()
+            }
+            None => Uuid::new_v4().to_string(),
+        }
+    }
+
     /// Collects and submits a ping for eventual uploading.
     ///
     /// The ping content is assembled as soon as possible, but upload is not
@@ -599,8 +775,16 @@ impl Glean {
             return Ok(false);
         }
 
+        if !self.check_ping_rate_limit(&ping.name) {
+            return Ok(false);
+        }
+
+        if ping.name == "metrics" {
+            self.flush_metric_write_count();
+        }
+
         let ping_maker = PingMaker::new();
-        let doc_id = Uuid::new_v4().to_string();
+        let doc_id = self.generate_doc_id();
         let url_path = self.make_path(&ping.name, &doc_id);
         match ping_maker.collect(self, &ping, reason) {
             None => {
@@ -634,6 +818,71 @@ impl Glean {
         }
     }
 
+    /// Counts a write of a metric, to be reported in `glean.metrics.write_count`.
+    ///
+    /// Writes of Glean's own metrics are not counted.
+    pub(crate) fn count_metric_write(&self, meta: &CommonMetricData) {
+        if !meta.is_glean_owned() {
+            self.metric_write_count.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Gets the window in which identical events are only recorded once, if configured.
+    pub(crate) fn event_dedup_window(&self) -> Option<Duration> {
+        self.event_dedup_window
+    }
+
+    /// Whether pings carry a `traceparent` header with the OpenTelemetry span they were submitted in.
+    ///
+    /// Only takes effect with the `opentelemetry` feature.
+    pub fn trace_context_enabled(&self) -> bool {
+        self.enable_trace_context
+    }
+
+    /// Counts a suppressed duplicate event in `glean.error.event_duplicates_suppressed`.
+    pub(crate) fn record_suppressed_duplicate_event(&self, meta: &CommonMetricData) {
+        self.event_metrics
+            .duplicates_suppressed
+            .get(&meta.base_identifier())
+            .add(self, 1);
+    }
+
+    /// Records the metric writes counted since the last `metrics` ping
+    /// into `glean.metrics.write_count`.
+    fn flush_metric_write_count(&self) {
+        let count = self.metric_write_count.swap(0, Ordering::SeqCst);
+        if count > 0 {
+            let amount = i32::try_from(count).unwrap_or(i32::MAX);
+            self.ping_metrics.metric_write_count.add(self, amount);
+        }
+    }
+
+    /// Checks whether the ping can be submitted under the configured rate limit.
+    ///
+    /// Records an error for the ping if it exceeds the limit.
+    fn check_ping_rate_limit(&self, ping_name: &str) -> bool {
+        let allowed = match &self.ping_rate_limiter {
+            Some(limiter) => limiter
+                .lock()
+                .unwrap()
+                .try_submit(ping_name, self.clock.monotonic_now()),
+            None => true,
+        };
+
+        if !allowed {
+            log::warn!(
+                "The ping '{}' exceeded its rate limit, therefore no ping queued.",
+                ping_name
+            );
+            self.ping_metrics
+                .rate_limit_exceeded
+                .get(ping_name)
+                .add(self, 1);
+        }
+
+        allowed
+    }
+
     /// Collects and submits a ping by name for eventual uploading.
     ///
     /// The ping content is assembled as soon as possible, but upload is not
@@ -675,20 +924,83 @@ impl Glean {
         self.ping_registry.get(ping_name)
     }
 
+    /// Assembles the payload a ping would have if it was submitted now, for inspection.
+    ///
+    /// No data is cleared and the ping's sequence number and start time are not advanced,
+    /// so this does not affect the next submission of the ping.
+    ///
+    /// # Arguments
+    ///
+    /// * `ping_name` - the name of the ping to inspect.
+    ///
+    /// # Returns
+    ///
+    /// The ping payload as JSON, or `None` if the ping is not registered
+    /// or would not be sent because it has no data.
+    pub fn assemble_ping_for_inspection(&self, ping_name: &str) -> Option<serde_json::Value> {
+        let ping = self.get_ping_by_name(ping_name)?;
+        PingMaker::new().inspect(self, ping)
+    }
+
     /// Register a new [`PingType`](metrics/struct.PingType.html).
+    ///
+    /// Registering a ping type with the same name again replaces it,
+    /// keeping its position in [`registered_ping_names`](#method.registered_ping_names).
     pub fn register_ping_type(&mut self, ping: &PingType) {
         if self.ping_registry.contains_key(&ping.name) {
             log::debug!("Duplicate ping named '{}'", ping.name)
+        } else {
+            self.ping_registration_order.push(ping.name.clone());
         }
 
         self.ping_registry.insert(ping.name.clone(), ping.clone());
     }
 
+    /// Gets the names of all registered ping types, in the order they were registered.
+    pub fn registered_ping_names(&self) -> Vec<&str> {
+        self.ping_registration_order
+            .iter()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Removes a [`PingType`](metrics/struct.PingType.html) from the registry.
+    ///
+    /// Submitting the ping afterwards fails as if it was never registered.
+    /// Pings of that type that are already pending are still uploaded.
+    ///
+    /// # Returns
+    ///
+    /// Whether the ping was registered.
+    pub fn deregister_ping_type(&mut self, ping_name: &str) -> bool {
+        let registered = self.ping_registry.remove(ping_name).is_some();
+        if registered {
+            self.ping_registration_order
+                .retain(|name| name != ping_name);
+        } else {
+            log::debug!("Attempted to deregister unknown ping '{}'", ping_name);
+        }
+        registered
+    }
+
     /// Get create time of the Glean object.
     pub(crate) fn start_time(&self) -> DateTime<FixedOffset> {
         self.start_time
     }
 
+    /// Replaces the source of the current time, e.g. with a `MockClock` in tests.
+    ///
+    /// The create time of the Glean object is taken from the new clock as well.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.start_time = clock.now_with_offset();
+        self.clock = clock;
+    }
+
+    /// Gets the source of the current time.
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
     /// Indicates that an experiment is running.
     ///
     /// Glean will then add an experiment annotation to the environment
@@ -719,12 +1031,42 @@ impl Glean {
         metric.set_inactive(&self);
     }
 
+    /// Records that the user was exposed to an experiment, in the `nimbus.exposure` event.
+    ///
+    /// Unlike `set_experiment_active`, which records the enrollment, this records
+    /// the moment the user actually encountered the experiment.
+    /// Exposures to experiments that are not active are not recorded, but logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `experiment_id` - The id of the active experiment (maximum 30 bytes).
+    /// * `timestamp` - A monotonically increasing timestamp, in milliseconds.
+    pub fn record_experiment_exposure(&self, experiment_id: String, timestamp: u64) {
+        let metric = metrics::ExperimentMetric::new(&self, experiment_id.clone());
+        if !metric.is_active(&self) {
+            log::warn!(
+                "experiment_id_not_active: Not recording the exposure to inactive experiment '{}'",
+                experiment_id
+            );
+            return;
+        }
+
+        let mut extra = HashMap::new();
+        extra.insert(0, experiment_id);
+        self.experiment_metrics
+            .exposure
+            .record(self, timestamp, extra);
+    }
+
     /// Persists Lifetime::Ping data that might be in memory
     /// in case `delay_ping_lifetime_io` is set or was set
     /// at a previous time.
     ///
     /// If there is no data to persist, this function does nothing.
     pub fn persist_ping_lifetime_data(&self) -> Result<()> {
+        // Keep the writes counted so far for the next `metrics` ping.
+        self.flush_metric_write_count();
+
         if let Some(data) = self.data_store.as_ref() {
             return data.persist_ping_lifetime_data();
         }
@@ -732,6 +1074,20 @@ impl Glean {
         Ok(())
     }
 
+    /// Compacts the database, reclaiming the space left behind by removed metrics.
+    ///
+    /// See `Database::compact`.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes reclaimed on disk.
+    pub fn compact_storage(&mut self) -> Result<u64> {
+        match self.data_store.as_mut() {
+            Some(data) => data.compact(),
+            None => Ok(0),
+        }
+    }
+
     /// Sets internally-handled application lifetime metrics.
     fn set_application_lifetime_core_metrics(&self) {
         self.core_metrics.os.set(self, system::OS);
@@ -865,6 +1221,39 @@ impl Glean {
         }
     }
 
+    fn get_metrics_ping_last_sent_metric(&self) -> metrics::DatetimeMetric {
+        metrics::DatetimeMetric::new(
+            CommonMetricData {
+                name: "last_sent_time".into(),
+                category: "mps".into(),
+                send_in_pings: vec![INTERNAL_STORAGE.into()],
+                lifetime: Lifetime::User,
+                ..Default::default()
+            },
+            metrics::TimeUnit::Minute,
+        )
+    }
+
+    /// ** This is not meant to be used directly.**
+    ///
+    /// Stores when the `metrics` ping was last sent by the metrics ping scheduler.
+    pub fn set_metrics_ping_last_sent(&self, value: DateTime<FixedOffset>) {
+        self.get_metrics_ping_last_sent_metric()
+            .set(self, Some(value));
+    }
+
+    /// ** This is not meant to be used directly.**
+    ///
+    /// Gets when the `metrics` ping was last sent by the metrics ping scheduler.
+    ///
+    /// # Returns
+    ///
+    /// The stored date/time, or `None` if the ping was never sent.
+    pub fn metrics_ping_last_sent(&self) -> Option<DateTime<FixedOffset>> {
+        self.get_metrics_ping_last_sent_metric()
+            .get_value(self, INTERNAL_STORAGE)
+    }
+
     /// **Test-only API (exported for FFI purposes).**
     ///
     /// Checks if an experiment is currently active.
@@ -899,6 +1288,54 @@ impl Glean {
         metric.test_get_value_as_json_string(&self)
     }
 
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the branch of an active experiment.
+    ///
+    /// # Arguments
+    ///
+    /// * `experiment_id` - The id of the experiment (maximum 30 bytes).
+    ///
+    /// # Returns
+    ///
+    /// The branch if the requested experiment is active, `None` otherwise.
+    pub fn test_get_experiment_branch(&self, experiment_id: String) -> Option<String> {
+        let metric = metrics::ExperimentMetric::new(self, experiment_id);
+        metric.test_get_value(self).map(|e| e.branch)
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Makes the document ids of pings submitted from now on deterministic.
+    ///
+    /// Document ids count up from `00000000-0000-0000-0000-000000000001`.
+    pub fn test_set_deterministic_doc_ids(&mut self) {
+        self.doc_id_counter = Some(AtomicU64::new(0));
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the document ids of the pings with the given name
+    /// that are queued for upload, in queue order.
+    pub fn test_get_pending_ping_doc_ids(&self, ping_name: &str) -> Vec<String> {
+        self.upload_manager.queued_doc_ids(ping_name)
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the request body, as it would be uploaded,
+    /// of the most recently queued ping with the given name.
+    pub fn test_get_pending_ping_body(&self, ping_name: &str) -> Option<Vec<u8>> {
+        self.upload_manager.queued_body(ping_name)
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the JSON payload of the most recently queued ping with the given name.
+    pub fn test_get_pending_ping_payload(&self, ping_name: &str) -> Option<String> {
+        self.upload_manager.queued_payload(ping_name)
+    }
+
     /// **Test-only API (exported for FFI purposes).**
     ///
     /// Deletes all stored metrics.
@@ -8,6 +8,8 @@
 use std::collections::HashSet;
 use std::iter::FromIterator;
 
+use chrono::TimeZone;
+
 use super::*;
 use crate::metrics::RecordedExperimentData;
 use crate::metrics::{StringMetric, TimeUnit, TimespanMetric, TimingDistributionMetric};
@@ -345,7 +347,6 @@ fn correct_order() {
     use histogram::Histogram;
     use metrics::{Metric::*, TimeUnit};
     use std::time::Duration;
-    use util::local_now_with_offset;
 
     // Extract the discriminant of the serialized value,
     // that is: the first 4 bytes.
@@ -363,7 +364,7 @@ fn correct_order() {
         Counter(0),
         CustomDistributionExponential(Histogram::exponential(1, 500, 10)),
         CustomDistributionLinear(Histogram::linear(1, 500, 10)),
-        Datetime(local_now_with_offset(), TimeUnit::Second),
+        Datetime(RealClock.now_with_offset(), TimeUnit::Second),
         Experiment(RecordedExperimentData { branch: "branch".into(), extra: None, }),
         Quantity(0),
         String("glean".into()),
@@ -373,6 +374,11 @@ fn correct_order() {
         TimingDistribution(Histogram::functional(2.0, 8.0)),
         MemoryDistribution(Histogram::functional(2.0, 8.0)),
         Jwe("eyJhbGciOiJSU0EtT0FFUCIsImVuYyI6IkEyNTZHQ00ifQ.OKOawDo13gRp2ojaHV7LFpZcgV7T6DVZKTyKOMTYUmKoTCVJRgckCL9kiMT03JGeipsEdY3mx_etLbbWSrFr05kLzcSr4qKAq7YN7e9jwQRb23nfa6c9d-StnImGyFDbSv04uVuxIp5Zms1gNxKKK2Da14B8S4rzVRltdYwam_lDp5XnZAYpQdb76FdIKLaVmqgfwX7XWRxv2322i-vDxRfqNzo_tETKzpVLzfiwQyeyPGLBIO56YJ7eObdv0je81860ppamavo35UgoRdbYaBcoh9QcfylQr66oc6vFWXRcZ_ZT2LawVCWTIy3brGPi6UklfCpIMfIjf7iGdXKHzg.48V1_ALb6US04U3b.5eym8TW_c8SuK0ltJ3rpYIzOeDQz7TALvtu6UG9oMo4vpzs9tX_EFShS8iB7j6jiSdiwkIr3ajwQzaBtQD_A.XFBoMYUZodetZdvTiFvSkQ".into()),
+        Url("https://example.com".into()),
+        Text("a longer text".into()),
+        Gauge(-3),
+        Rate(2, 5),
+        Object("{\"key\":[1,2]}".into()),
     ];
 
     for metric in all_metrics {
@@ -398,6 +404,11 @@ fn correct_order() {
             TimingDistribution(..)            => assert_eq!(11, disc),
             MemoryDistribution(..)            => assert_eq!(12, disc),
             Jwe(..)                           => assert_eq!(13, disc),
+            Url(..)                           => assert_eq!(14, disc),
+            Text(..)                          => assert_eq!(15, disc),
+            Gauge(..)                         => assert_eq!(16, disc),
+            Rate(..)                          => assert_eq!(17, disc),
+            Object(..)                        => assert_eq!(18, disc),
         }
     }
 }
@@ -591,6 +602,25 @@ fn test_dirty_bit() {
     }
 }
 
+#[test]
+fn metrics_ping_last_sent_is_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    let tmpname = dir.path().display().to_string();
+    let last_sent = FixedOffset::east(3600).ymd(2020, 11, 4).and_hms(4, 0, 0);
+    {
+        let glean = Glean::with_options(&tmpname, GLOBAL_APPLICATION_ID, true);
+        assert_eq!(None, glean.metrics_ping_last_sent());
+
+        glean.set_metrics_ping_last_sent(last_sent);
+        assert_eq!(Some(last_sent), glean.metrics_ping_last_sent());
+    }
+
+    {
+        let glean = Glean::with_options(&tmpname, GLOBAL_APPLICATION_ID, true);
+        assert_eq!(Some(last_sent), glean.metrics_ping_last_sent());
+    }
+}
+
 #[test]
 fn test_change_metric_type_runtime() {
     let dir = tempfile::tempdir().unwrap();
@@ -833,3 +863,157 @@ fn records_database_file_size() {
     // We should see the database containing some data.
     assert!(data.sum > 0);
 }
+
+#[test]
+fn internal_error_metrics_can_be_disabled() {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg = Configuration {
+        data_path: dir.path().into(),
+        application_id: GLOBAL_APPLICATION_ID.into(),
+        language_binding_name: "Rust".into(),
+        upload_enabled: true,
+        max_events: None,
+        delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: true,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
+    };
+    let glean = Glean::new(cfg.clone()).unwrap();
+
+    let metric = StringMetric::new(CommonMetricData {
+        name: "string_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings: vec!["store1".into()],
+        disabled: false,
+        lifetime: Lifetime::Application,
+        ..Default::default()
+    });
+
+    // The value is still truncated and recorded, but the error isn't.
+    metric.set(&glean, "x".repeat(200));
+    assert_eq!(
+        Some("x".repeat(100)),
+        metric.test_get_value(&glean, "store1")
+    );
+    assert!(
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidOverflow, None)
+            .is_err()
+    );
+    drop(glean);
+
+    // Re-opening the database would record its size otherwise.
+    let glean = Glean::new(cfg).unwrap();
+    let snapshot = StorageManager
+        .snapshot_as_json(glean.storage(), "metrics", false)
+        .unwrap_or_else(|| serde_json::json!({}));
+    assert!(
+        !snapshot.to_string().contains("glean."),
+        "no internal metric expected, got {}",
+        snapshot
+    );
+}
+
+#[test]
+fn times_are_taken_from_the_clock() {
+    let (mut glean, _t) = new_glean(None);
+    // 2020-01-01T00:00:00Z
+    let clock = Arc::new(MockClock::at_millis(1_577_836_800_000));
+    glean.set_clock(clock.clone());
+
+    let ping = PingType::new("store1", true, true, vec![]);
+    glean.register_ping_type(&ping);
+    let metric = metrics::DatetimeMetric::new(
+        CommonMetricData {
+            name: "datetime_metric".into(),
+            category: "telemetry".into(),
+            send_in_pings: vec!["store1".into()],
+            disabled: false,
+            lifetime: Lifetime::Application,
+            ..Default::default()
+        },
+        TimeUnit::Second,
+    );
+
+    clock.advance(Duration::from_secs(90 * 60));
+    metric.set(&glean, None);
+    assert_eq!(
+        1_577_842_200,
+        metric.test_get_value(&glean, "store1").unwrap().timestamp()
+    );
+
+    let content = ping::PingMaker::new().collect(&glean, &ping, None).unwrap();
+    let parse = |time: &serde_json::Value| {
+        DateTime::parse_from_str(time.as_str().unwrap(), "%Y-%m-%dT%H:%M%:z")
+            .unwrap()
+            .timestamp()
+    };
+    assert_eq!(1_577_836_800, parse(&content["ping_info"]["start_time"]));
+    assert_eq!(1_577_842_200, parse(&content["ping_info"]["end_time"]));
+}
+
+#[test]
+fn ping_rate_limit_window_moves_with_the_clock() {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg = Configuration {
+        data_path: dir.path().into(),
+        application_id: GLOBAL_APPLICATION_ID.into(),
+        language_binding_name: "Rust".into(),
+        upload_enabled: true,
+        max_events: None,
+        delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: Some(1),
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
+    };
+    let mut glean = Glean::new(cfg).unwrap();
+    let clock = Arc::new(MockClock::new());
+    glean.set_clock(clock.clone());
+
+    let ping = PingType::new("custom", true, true, vec![]);
+    glean.register_ping_type(&ping);
+
+    assert!(glean.submit_ping(&ping, None).unwrap());
+    clock.advance(Duration::from_secs(59));
+    assert!(!glean.submit_ping(&ping, None).unwrap());
+    clock.advance(Duration::from_secs(1));
+    assert!(glean.submit_ping(&ping, None).unwrap());
+}
+
+#[test]
+fn storage_size_counts_the_database_and_pending_pings() {
+    let (mut glean, dir) = new_glean(None);
+    let database_size = || {
+        std::fs::metadata(database::database_file(dir.path()))
+            .unwrap()
+            .len()
+    };
+    assert!(database_size() > 0);
+    assert_eq!(database_size(), storage_size_bytes(dir.path()));
+
+    let ping = PingType::new("custom", true, true, vec![]);
+    glean.register_ping_type(&ping);
+    assert!(glean.submit_ping(&ping, None).unwrap());
+    let pending_size = util::dir_size(&dir.path().join(PENDING_PINGS_DIRECTORY));
+    assert!(pending_size > 0);
+    assert_eq!(
+        database_size() + pending_size,
+        storage_size_bytes(dir.path())
+    );
+
+    // Disabling upload removes the pending pings and submits a deletion-request ping.
+    glean.set_upload_enabled(false);
+    let deletion_request_size = util::dir_size(&dir.path().join(DELETION_REQUEST_PINGS_DIRECTORY));
+    assert!(deletion_request_size > 0);
+    assert_eq!(
+        database_size() + deletion_request_size,
+        storage_size_bytes(dir.path())
+    );
+}
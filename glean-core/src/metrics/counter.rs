@@ -48,6 +48,8 @@ impl CounterMetric {
     /// ## Notes
     ///
     /// Logs an error if the `amount` is 0 or negative.
+    /// If the counter would exceed `i32::MAX`, it saturates at that value
+    /// and an `ErrorType::InvalidOverflow` error is recorded.
     pub fn add(&self, glean: &Glean, amount: i32) {
         if !self.should_record(glean) {
             return;
@@ -64,14 +66,32 @@ impl CounterMetric {
             return;
         }
 
+        let mut overflowed = false;
         glean
             .storage()
             .record_with(glean, &self.meta, |old_value| match old_value {
                 Some(Metric::Counter(old_value)) => {
-                    Metric::Counter(old_value.saturating_add(amount))
+                    let new_value = old_value.checked_add(amount).unwrap_or_else(|| {
+                        overflowed = true;
+                        i32::MAX
+                    });
+                    Metric::Counter(new_value)
                 }
                 _ => Metric::Counter(amount),
-            })
+            });
+
+        if overflowed {
+            record_error(
+                glean,
+                &self.meta,
+                ErrorType::InvalidOverflow,
+                format!(
+                    "Adding {} overflowed the counter, saturated at i32::MAX",
+                    amount
+                ),
+                None,
+            );
+        }
     }
 
     /// **Test-only API (exported for FFI purposes).**
@@ -9,17 +9,40 @@ use crate::metrics::time_unit::TimeUnit;
 use crate::metrics::Metric;
 use crate::metrics::MetricType;
 use crate::storage::StorageManager;
-use crate::util::{get_iso_time_string, local_now_with_offset};
+use crate::util::get_iso_time_string;
 use crate::CommonMetricData;
 use crate::Glean;
 
-use chrono::{DateTime, FixedOffset, TimeZone};
+use chrono::{DateTime, FixedOffset, TimeZone, Timelike};
 
 /// A datetime type.
 ///
 /// Used to feed data to the `DatetimeMetric`.
 pub type Datetime = DateTime<FixedOffset>;
 
+/// Truncates a date/time to the given time unit, keeping its timezone offset.
+fn truncate_to(datetime: Datetime, time_unit: TimeUnit) -> Datetime {
+    use TimeUnit::*;
+    // safe unwraps: zeroing out time components always results in a valid date/time.
+    let truncated = match time_unit {
+        Nanosecond => Some(datetime),
+        Microsecond => datetime.with_nanosecond(datetime.nanosecond() / 1_000 * 1_000),
+        Millisecond => datetime.with_nanosecond(datetime.nanosecond() / 1_000_000 * 1_000_000),
+        Second => datetime.with_nanosecond(0),
+        Minute => datetime.with_nanosecond(0).and_then(|d| d.with_second(0)),
+        Hour => datetime
+            .with_nanosecond(0)
+            .and_then(|d| d.with_second(0))
+            .and_then(|d| d.with_minute(0)),
+        Day => datetime
+            .with_nanosecond(0)
+            .and_then(|d| d.with_second(0))
+            .and_then(|d| d.with_minute(0))
+            .and_then(|d| d.with_hour(0)),
+    };
+    truncated.unwrap()
+}
+
 /// A datetime metric.
 ///
 /// Used to record an absolute date and time, such as the time the user first ran
@@ -117,7 +140,7 @@ impl DatetimeMetric {
             return;
         }
 
-        let value = value.unwrap_or_else(local_now_with_offset);
+        let value = value.unwrap_or_else(|| glean.clock().now_with_offset());
         let value = Metric::Datetime(value, self.time_unit);
         glean.storage().record(glean, &self.meta, &value)
     }
@@ -143,6 +166,18 @@ impl DatetimeMetric {
         }
     }
 
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the currently stored value, with its timezone offset.
+    ///
+    /// The precision of this value is truncated to the `time_unit` precision.
+    ///
+    /// This doesn't clear the stored value.
+    pub fn test_get_value(&self, glean: &Glean, storage_name: &str) -> Option<Datetime> {
+        self.get_value(glean, storage_name)
+            .map(|datetime| truncate_to(datetime, self.time_unit))
+    }
+
     /// **Test-only API (exported for FFI purposes).**
     ///
     /// Gets the currently stored value as a String.
@@ -61,14 +61,18 @@ impl EventMetric {
     ///   the metric's `allowed_extra_keys` vector where the key's string is
     ///   looked up. If any key index is out of range, an error is reported and
     ///   no event is recorded.
+    ///
+    /// # Returns
+    ///
+    /// Whether a ping was submitted because the event storage reached its maximum capacity.
     pub fn record<M: Into<Option<HashMap<i32, String>>>>(
         &self,
         glean: &Glean,
         timestamp: u64,
         extra: M,
-    ) {
+    ) -> bool {
         if !self.should_record(glean) {
-            return;
+            return false;
         }
 
         let extra = extra.into();
@@ -91,7 +95,7 @@ impl EventMetric {
                         None => {
                             let msg = format!("Invalid key index {}", k);
                             record_error(glean, &self.meta, ErrorType::InvalidValue, msg, None);
-                            return;
+                            return false;
                         }
                     };
                 }
@@ -103,7 +107,57 @@ impl EventMetric {
 
         glean
             .event_storage()
-            .record(glean, &self.meta, timestamp, extra_strings);
+            .record(glean, &self.meta, timestamp, extra_strings)
+    }
+
+    /// Records an event, with the extra keys given by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `timestamp` - A monotonically increasing timestamp, in milliseconds.
+    /// * `extra` - A HashMap of (key, value) pairs. Each key must be one of the
+    ///   metric's `allowed_extra_keys`. If any key is not allowed, an error is
+    ///   reported and no event is recorded.
+    ///
+    /// # Returns
+    ///
+    /// Whether a ping was submitted because the event storage reached its maximum capacity.
+    pub fn record_with_string_keys(
+        &self,
+        glean: &Glean,
+        timestamp: u64,
+        extra: Option<HashMap<String, String>>,
+    ) -> bool {
+        if !self.should_record(glean) {
+            return false;
+        }
+
+        let extra_strings = match extra {
+            Some(extra) if !extra.is_empty() => {
+                let mut extra_strings = HashMap::new();
+                for (k, v) in extra.into_iter() {
+                    if !self.allowed_extra_keys.contains(&k) {
+                        let msg = format!("Invalid extra key '{}'", k);
+                        record_error(glean, &self.meta, ErrorType::InvalidValue, msg, None);
+                        return false;
+                    }
+                    let v = truncate_string_at_boundary_with_error(
+                        glean,
+                        &self.meta,
+                        v,
+                        MAX_LENGTH_EXTRA_KEY_VALUE,
+                    );
+                    extra_strings.insert(k, v);
+                }
+                Some(extra_strings)
+            }
+            _ => None,
+        };
+
+        glean
+            .event_storage()
+            .record(glean, &self.meta, timestamp, extra_strings)
     }
 
     /// **Test-only API (exported for FFI purposes).**
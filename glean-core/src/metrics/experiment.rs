@@ -214,6 +214,18 @@ impl ExperimentMetric {
         }
     }
 
+    /// Checks whether the experiment is currently active.
+    pub(crate) fn is_active(&self, glean: &Glean) -> bool {
+        matches!(
+            StorageManager.snapshot_metric(
+                glean.storage(),
+                INTERNAL_STORAGE,
+                &self.meta.identifier(glean),
+            ),
+            Some(Metric::Experiment(_))
+        )
+    }
+
     /// **Test-only API (exported for FFI purposes).**
     ///
     /// Gets the currently stored experiment data as a JSON representation of
@@ -221,12 +233,21 @@ impl ExperimentMetric {
     ///
     /// This doesn't clear the stored value.
     pub fn test_get_value_as_json_string(&self, glean: &Glean) -> Option<String> {
+        self.test_get_value(glean).map(|e| json!(e).to_string())
+    }
+
+    /// **Test-only API.**
+    ///
+    /// Gets the currently stored experiment data.
+    ///
+    /// This doesn't clear the stored value.
+    pub(crate) fn test_get_value(&self, glean: &Glean) -> Option<RecordedExperimentData> {
         match StorageManager.snapshot_metric(
             glean.storage(),
             INTERNAL_STORAGE,
             &self.meta.identifier(glean),
         ) {
-            Some(Metric::Experiment(e)) => Some(json!(e).to_string()),
+            Some(Metric::Experiment(e)) => Some(e),
             _ => None,
         }
     }
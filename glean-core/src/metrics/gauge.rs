@@ -0,0 +1,113 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error_recording::{record_error, ErrorType};
+use crate::metrics::Metric;
+use crate::metrics::MetricType;
+use crate::storage::StorageManager;
+use crate::CommonMetricData;
+use crate::Glean;
+
+/// A gauge metric.
+///
+/// Used to record a value that can increase and decrease, e.g. the number of open tabs.
+#[derive(Clone, Debug)]
+pub struct GaugeMetric {
+    meta: CommonMetricData,
+}
+
+impl MetricType for GaugeMetric {
+    fn meta(&self) -> &CommonMetricData {
+        &self.meta
+    }
+
+    fn meta_mut(&mut self) -> &mut CommonMetricData {
+        &mut self.meta
+    }
+}
+
+// IMPORTANT:
+//
+// When changing this implementation, make sure all the operations are
+// also declared in the related trait in `../traits/`.
+impl GaugeMetric {
+    /// Creates a new gauge metric.
+    pub fn new(meta: CommonMetricData) -> Self {
+        Self { meta }
+    }
+
+    /// Sets the gauge to `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `value` - The value to set the gauge to. Can be negative.
+    pub fn set(&self, glean: &Glean, value: i64) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        glean
+            .storage()
+            .record(glean, &self.meta, &Metric::Gauge(value))
+    }
+
+    /// Changes the gauge by `delta`.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `delta` - The amount to change the gauge by. Negative values decrease it.
+    ///
+    /// ## Notes
+    ///
+    /// If the gauge was not set before, it starts at 0.
+    /// If it would exceed `i64::MIN` or `i64::MAX`, it saturates at that value
+    /// and an `ErrorType::InvalidOverflow` error is recorded.
+    pub fn add(&self, glean: &Glean, delta: i64) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        let mut overflowed = false;
+        glean
+            .storage()
+            .record_with(glean, &self.meta, |old_value| match old_value {
+                Some(Metric::Gauge(old_value)) => {
+                    let new_value = old_value.checked_add(delta).unwrap_or_else(|| {
+                        overflowed = true;
+                        old_value.saturating_add(delta)
+                    });
+                    Metric::Gauge(new_value)
+                }
+                _ => Metric::Gauge(delta),
+            });
+
+        if overflowed {
+            record_error(
+                glean,
+                &self.meta,
+                ErrorType::InvalidOverflow,
+                format!("Adding {} overflowed the gauge, saturated", delta),
+                None,
+            );
+        }
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the currently stored value as an integer.
+    ///
+    /// This doesn't clear the stored value.
+    pub fn test_get_value(&self, glean: &Glean, storage_name: &str) -> Option<i64> {
+        match StorageManager.snapshot_metric(
+            glean.storage(),
+            storage_name,
+            &self.meta.identifier(glean),
+        ) {
+            Some(Metric::Gauge(i)) => Some(i),
+            _ => None,
+        }
+    }
+}
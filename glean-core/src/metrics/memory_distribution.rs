@@ -23,7 +23,7 @@ const MAX_BYTES: u64 = 1 << 40;
 /// A memory distribution metric.
 ///
 /// Memory distributions are used to accumulate and store memory sizes.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MemoryDistributionMetric {
     meta: CommonMetricData,
     memory_unit: MemoryUnit,
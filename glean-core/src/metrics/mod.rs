@@ -16,17 +16,22 @@ mod custom_distribution;
 mod datetime;
 mod event;
 mod experiment;
+mod gauge;
 mod jwe;
 mod labeled;
 mod memory_distribution;
 mod memory_unit;
+mod object;
 mod ping;
 mod quantity;
+mod rate;
 mod string;
 mod string_list;
+mod text;
 mod time_unit;
 mod timespan;
 mod timing_distribution;
+mod url;
 mod uuid;
 
 pub use crate::event_database::RecordedEvent;
@@ -42,6 +47,7 @@ pub use self::custom_distribution::CustomDistributionMetric;
 pub use self::datetime::DatetimeMetric;
 pub use self::event::EventMetric;
 pub(crate) use self::experiment::ExperimentMetric;
+pub use self::gauge::GaugeMetric;
 pub use crate::histogram::HistogramType;
 // Note: only expose RecordedExperimentData to tests in
 // the next line, so that glean-core\src\lib.rs won't fail to build.
@@ -53,14 +59,18 @@ pub use self::labeled::{
 };
 pub use self::memory_distribution::MemoryDistributionMetric;
 pub use self::memory_unit::MemoryUnit;
+pub use self::object::ObjectMetric;
 pub use self::ping::PingType;
 pub use self::quantity::QuantityMetric;
+pub use self::rate::RateMetric;
 pub use self::string::StringMetric;
 pub use self::string_list::StringListMetric;
+pub use self::text::TextMetric;
 pub use self::time_unit::TimeUnit;
 pub use self::timespan::TimespanMetric;
 pub use self::timing_distribution::TimerId;
 pub use self::timing_distribution::TimingDistributionMetric;
+pub use self::url::UrlMetric;
 pub use self::uuid::UuidMetric;
 
 /// A snapshot of all buckets and the accumulated sum of a distribution.
@@ -117,6 +127,16 @@ pub enum Metric {
     MemoryDistribution(Histogram<Functional>),
     /// A JWE metric. See [`JweMetric`](struct.JweMetric.html) for more information.
     Jwe(String),
+    /// A URL metric. See [`UrlMetric`](struct.UrlMetric.html) for more information.
+    Url(String),
+    /// A text metric. See [`TextMetric`](struct.TextMetric.html) for more information.
+    Text(String),
+    /// A gauge metric. See [`GaugeMetric`](struct.GaugeMetric.html) for more information.
+    Gauge(i64),
+    /// A rate metric. See [`RateMetric`](struct.RateMetric.html) for more information.
+    Rate(i32, i32),
+    /// An object metric, serialized as JSON. See [`ObjectMetric`](struct.ObjectMetric.html) for more information.
+    Object(String),
 }
 
 /// A `MetricType` describes common behavior across all metrics.
@@ -131,7 +151,12 @@ pub trait MetricType {
     ///
     /// This depends on the metrics own state, as determined by its metadata,
     /// and whether upload is enabled on the Glean object.
+    /// Glean's internal metrics are also not recorded if they were disabled in the configuration.
     fn should_record(&self, glean: &Glean) -> bool {
+        if self.meta().is_internal() && glean.internal_error_metrics_disabled() {
+            return false;
+        }
+
         glean.is_upload_enabled() && self.meta().should_record()
     }
 }
@@ -158,6 +183,11 @@ impl Metric {
             Metric::Uuid(_) => "uuid",
             Metric::MemoryDistribution(_) => "memory_distribution",
             Metric::Jwe(_) => "jwe",
+            Metric::Url(_) => "url",
+            Metric::Text(_) => "text",
+            Metric::Gauge(_) => "gauge",
+            Metric::Rate(..) => "rate",
+            Metric::Object(_) => "object",
         }
     }
 
@@ -182,6 +212,13 @@ impl Metric {
             Metric::Uuid(s) => json!(s),
             Metric::MemoryDistribution(hist) => json!(memory_distribution::snapshot(hist)),
             Metric::Jwe(s) => json!(s),
+            Metric::Url(s) => json!(s),
+            Metric::Text(s) => json!(s),
+            Metric::Gauge(g) => json!(g),
+            Metric::Rate(numerator, denominator) => {
+                json!({"numerator": numerator, "denominator": denominator})
+            }
+            Metric::Object(s) => serde_json::from_str(s).unwrap_or(JsonValue::Null),
         }
     }
 }
@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde_json::Value as JsonValue;
+
+use crate::error_recording::{record_error, ErrorType};
+use crate::metrics::Metric;
+use crate::metrics::MetricType;
+use crate::storage::StorageManager;
+use crate::CommonMetricData;
+use crate::Glean;
+
+/// An object metric.
+///
+/// Records a structured value as a JSON object or array.
+/// The language bindings are responsible for validating the value against the
+/// structure declared for the metric.
+#[derive(Clone, Debug)]
+pub struct ObjectMetric {
+    meta: CommonMetricData,
+}
+
+impl MetricType for ObjectMetric {
+    fn meta(&self) -> &CommonMetricData {
+        &self.meta
+    }
+
+    fn meta_mut(&mut self) -> &mut CommonMetricData {
+        &mut self.meta
+    }
+}
+
+// IMPORTANT:
+//
+// When changing this implementation, make sure all the operations are
+// also declared in the related trait in `../traits/`.
+impl ObjectMetric {
+    /// Creates a new object metric.
+    pub fn new(meta: CommonMetricData) -> Self {
+        Self { meta }
+    }
+
+    /// Sets to the specified JSON value.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `value` - The object or array to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// Records an `ErrorType::InvalidValue` error if the value is not an object or an array.
+    pub fn set(&self, glean: &Glean, value: JsonValue) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        if !value.is_object() && !value.is_array() {
+            self.record_schema_error(
+                glean,
+                format!("Expected an object or an array, got {}", value),
+            );
+            return;
+        }
+
+        let value = Metric::Object(value.to_string());
+        glean.storage().record(glean, &self.meta, &value)
+    }
+
+    /// Sets to the JSON value serialized in a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `value` - The serialized object or array to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// Records an `ErrorType::InvalidValue` error if the value is not valid JSON,
+    /// or not an object or an array.
+    pub fn set_string(&self, glean: &Glean, value: &str) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        match serde_json::from_str(value) {
+            Ok(value) => self.set(glean, value),
+            Err(e) => self.record_schema_error(glean, format!("Invalid JSON: {}", e)),
+        }
+    }
+
+    /// Records an `ErrorType::InvalidValue` error, without changing the stored value.
+    ///
+    /// Used by the language bindings when a value doesn't match the structure of the metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `message` - Why the value was rejected.
+    pub fn record_schema_error(&self, glean: &Glean, message: impl std::fmt::Display) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        record_error(glean, &self.meta, ErrorType::InvalidValue, message, None);
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the currently stored value as JSON.
+    ///
+    /// This doesn't clear the stored value.
+    pub fn test_get_value(&self, glean: &Glean, storage_name: &str) -> Option<JsonValue> {
+        match StorageManager.snapshot_metric(
+            glean.storage(),
+            storage_name,
+            &self.meta.identifier(glean),
+        ) {
+            Some(Metric::Object(s)) => serde_json::from_str(&s).ok(),
+            _ => None,
+        }
+    }
+}
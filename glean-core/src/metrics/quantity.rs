@@ -68,6 +68,62 @@ impl QuantityMetric {
             .record(glean, &self.meta, &Metric::Quantity(value))
     }
 
+    /// Increases the value by `amount`. Must be non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `amount` - The amount to increase by. Must be non-negative.
+    ///
+    /// ## Notes
+    ///
+    /// Logs an error if the `amount` is negative.
+    /// If the value would exceed `i64::MAX`, it saturates at that value
+    /// and an `ErrorType::InvalidOverflow` error is recorded.
+    pub fn add(&self, glean: &Glean, amount: i64) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        if amount < 0 {
+            record_error(
+                glean,
+                &self.meta,
+                ErrorType::InvalidValue,
+                format!("Added negative value {}", amount),
+                None,
+            );
+            return;
+        }
+
+        let mut overflowed = false;
+        glean
+            .storage()
+            .record_with(glean, &self.meta, |old_value| match old_value {
+                Some(Metric::Quantity(old_value)) => {
+                    let new_value = old_value.checked_add(amount).unwrap_or_else(|| {
+                        overflowed = true;
+                        i64::MAX
+                    });
+                    Metric::Quantity(new_value)
+                }
+                _ => Metric::Quantity(amount),
+            });
+
+        if overflowed {
+            record_error(
+                glean,
+                &self.meta,
+                ErrorType::InvalidOverflow,
+                format!(
+                    "Adding {} overflowed the quantity, saturated at i64::MAX",
+                    amount
+                ),
+                None,
+            );
+        }
+    }
+
     /// **Test-only API (exported for FFI purposes).**
     ///
     /// Gets the currently stored value as an integer.
@@ -0,0 +1,153 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error_recording::{record_error, ErrorType};
+use crate::metrics::Metric;
+use crate::metrics::MetricType;
+use crate::storage::StorageManager;
+use crate::CommonMetricData;
+use crate::Glean;
+
+/// The parts of a rate.
+#[derive(Clone, Copy, Debug)]
+enum Part {
+    Numerator,
+    Denominator,
+}
+
+impl Part {
+    fn name(self) -> &'static str {
+        match self {
+            Part::Numerator => "numerator",
+            Part::Denominator => "denominator",
+        }
+    }
+}
+
+/// A rate metric.
+///
+/// Used to count how often something happens relative to how often it could have happened,
+/// e.g. the number of crashes per session, as a numerator and a denominator.
+#[derive(Clone, Debug)]
+pub struct RateMetric {
+    meta: CommonMetricData,
+}
+
+impl MetricType for RateMetric {
+    fn meta(&self) -> &CommonMetricData {
+        &self.meta
+    }
+
+    fn meta_mut(&mut self) -> &mut CommonMetricData {
+        &mut self.meta
+    }
+}
+
+// IMPORTANT:
+//
+// When changing this implementation, make sure all the operations are
+// also declared in the related trait in `../traits/`.
+impl RateMetric {
+    /// Creates a new rate metric.
+    pub fn new(meta: CommonMetricData) -> Self {
+        Self { meta }
+    }
+
+    /// Increases the numerator by `amount`.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `amount` - The amount to increase by. Should be non-negative.
+    ///
+    /// ## Notes
+    ///
+    /// Logs an error if the `amount` is negative.
+    pub fn add_to_numerator(&self, glean: &Glean, amount: i32) {
+        self.add(glean, Part::Numerator, amount)
+    }
+
+    /// Increases the denominator by `amount`.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `amount` - The amount to increase by. Should be non-negative.
+    ///
+    /// ## Notes
+    ///
+    /// Logs an error if the `amount` is negative.
+    pub fn add_to_denominator(&self, glean: &Glean, amount: i32) {
+        self.add(glean, Part::Denominator, amount)
+    }
+
+    /// Increases one part of the rate by `amount`.
+    ///
+    /// If the part would exceed `i32::MAX`, it saturates at that value
+    /// and an `ErrorType::InvalidOverflow` error is recorded.
+    fn add(&self, glean: &Glean, part: Part, amount: i32) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        if amount < 0 {
+            record_error(
+                glean,
+                &self.meta,
+                ErrorType::InvalidValue,
+                format!("Added negative value {} to {}", amount, part.name()),
+                None,
+            );
+            return;
+        }
+
+        let mut overflowed = false;
+        let mut add = |old_value: i32| {
+            old_value.checked_add(amount).unwrap_or_else(|| {
+                overflowed = true;
+                i32::MAX
+            })
+        };
+        glean.storage().record_with(glean, &self.meta, |old_value| {
+            let (numerator, denominator) = match old_value {
+                Some(Metric::Rate(numerator, denominator)) => (numerator, denominator),
+                _ => (0, 0),
+            };
+            match part {
+                Part::Numerator => Metric::Rate(add(numerator), denominator),
+                Part::Denominator => Metric::Rate(numerator, add(denominator)),
+            }
+        });
+
+        if overflowed {
+            record_error(
+                glean,
+                &self.meta,
+                ErrorType::InvalidOverflow,
+                format!(
+                    "Adding {} overflowed the {}, saturated",
+                    amount,
+                    part.name()
+                ),
+                None,
+            );
+        }
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the currently stored value as a pair of numerator and denominator.
+    ///
+    /// This doesn't clear the stored value.
+    pub fn test_get_value(&self, glean: &Glean, storage_name: &str) -> Option<(i32, i32)> {
+        match StorageManager.snapshot_metric(
+            glean.storage(),
+            storage_name,
+            &self.meta.identifier(glean),
+        ) {
+            Some(Metric::Rate(numerator, denominator)) => Some((numerator, denominator)),
+            _ => None,
+        }
+    }
+}
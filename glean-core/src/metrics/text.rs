@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::metrics::Metric;
+use crate::metrics::MetricType;
+use crate::storage::StorageManager;
+use crate::util::truncate_string_at_boundary_with_error;
+use crate::CommonMetricData;
+use crate::Glean;
+
+// The maximum number of bytes a text may have: 200KB.
+const MAX_LENGTH_VALUE: usize = 200 * 1024;
+
+// Pings sent frequently and by every application, which should stay small.
+const PINGS_WITHOUT_TEXT: [&str; 3] = ["baseline", "metrics", "events"];
+
+/// A text metric.
+///
+/// Record an Unicode string value of arbitrary content, such as a stack trace.
+/// Texts are length-limited to `MAX_LENGTH_VALUE` bytes
+/// and can't be sent in the `baseline`, `metrics` and `events` pings.
+#[derive(Clone, Debug)]
+pub struct TextMetric {
+    meta: CommonMetricData,
+}
+
+impl MetricType for TextMetric {
+    fn meta(&self) -> &CommonMetricData {
+        &self.meta
+    }
+
+    fn meta_mut(&mut self) -> &mut CommonMetricData {
+        &mut self.meta
+    }
+}
+
+// IMPORTANT:
+//
+// When changing this implementation, make sure all the operations are
+// also declared in the related trait in `../traits/`.
+impl TextMetric {
+    /// Creates a new text metric.
+    ///
+    /// The `baseline`, `metrics` and `events` pings are removed from `send_in_pings`
+    /// and an error is logged for each of them.
+    pub fn new(mut meta: CommonMetricData) -> Self {
+        let identifier = meta.base_identifier();
+        meta.send_in_pings.retain(|ping| {
+            let allowed = !PINGS_WITHOUT_TEXT.contains(&ping.as_str());
+            if !allowed {
+                log::error!(
+                    "{}: Text metrics can't be sent in the '{}' ping",
+                    identifier,
+                    ping
+                );
+            }
+            allowed
+        });
+
+        Self { meta }
+    }
+
+    /// Sets to the specified value.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `value` - The text to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// Truncates the value if it is longer than `MAX_LENGTH_VALUE` bytes and logs an error.
+    pub fn set<S: Into<String>>(&self, glean: &Glean, value: S) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        let s = truncate_string_at_boundary_with_error(glean, &self.meta, value, MAX_LENGTH_VALUE);
+
+        let value = Metric::Text(s);
+        glean.storage().record(glean, &self.meta, &value)
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the currently stored value as a string.
+    ///
+    /// This doesn't clear the stored value.
+    pub fn test_get_value(&self, glean: &Glean, storage_name: &str) -> Option<String> {
+        match StorageManager.snapshot_metric(
+            glean.storage(),
+            storage_name,
+            &self.meta.identifier(glean),
+        ) {
+            Some(Metric::Text(s)) => Some(s),
+            _ => None,
+        }
+    }
+}
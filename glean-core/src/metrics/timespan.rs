@@ -73,6 +73,7 @@ impl TimespanMetric {
     /// Stops tracking time for the provided metric. Sets the metric to the elapsed time.
     ///
     /// This will record an error if no `start` was called.
+    /// If the clock went backwards, the elapsed time is set to zero and an error is recorded.
     pub fn set_stop(&mut self, glean: &Glean, stop_time: u64) {
         if !self.should_record(glean) {
             // Reset timer when disabled, so that we don't record timespans across
@@ -92,7 +93,19 @@ impl TimespanMetric {
             return;
         }
 
-        let duration = stop_time - self.start_time.take().unwrap();
+        let duration = match stop_time.checked_sub(self.start_time.take().unwrap()) {
+            Some(duration) => duration,
+            None => {
+                record_error(
+                    glean,
+                    &self.meta,
+                    ErrorType::InvalidValue,
+                    "Timespan stopped with negative duration",
+                    None,
+                );
+                0
+            }
+        };
         let duration = Duration::from_nanos(duration);
         self.set_raw(glean, duration, false);
     }
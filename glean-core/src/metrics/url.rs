@@ -0,0 +1,181 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error_recording::{record_error, ErrorType};
+use crate::metrics::Metric;
+use crate::metrics::MetricType;
+use crate::storage::StorageManager;
+use crate::CommonMetricData;
+use crate::Glean;
+
+// The maximum number of bytes a URL may have.
+const MAX_URL_LENGTH: usize = 8192;
+
+/// A URL metric.
+///
+/// Record a URL as an Unicode string.
+/// Only `http` and `https` URLs of at most `MAX_URL_LENGTH` bytes are recorded.
+/// The query string and fragment can be stripped before recording, to avoid sending sensitive data.
+#[derive(Clone, Debug)]
+pub struct UrlMetric {
+    meta: CommonMetricData,
+    redact_query: bool,
+}
+
+impl MetricType for UrlMetric {
+    fn meta(&self) -> &CommonMetricData {
+        &self.meta
+    }
+
+    fn meta_mut(&mut self) -> &mut CommonMetricData {
+        &mut self.meta
+    }
+}
+
+/// Checks that a URL has a `http` or `https` scheme, followed by a host.
+fn is_valid_url(value: &str) -> bool {
+    let scheme_end = match value.find("://") {
+        Some(index) => index,
+        None => return false,
+    };
+    let scheme = &value[..scheme_end];
+    let rest = &value[scheme_end + "://".len()..];
+
+    (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+        && !rest.is_empty()
+        && !rest.starts_with('/')
+}
+
+/// Removes the query string and the fragment from a URL.
+fn strip_query_and_fragment(value: &str) -> &str {
+    match value.find(&['?', '#'][..]) {
+        Some(index) => &value[..index],
+        None => value,
+    }
+}
+
+// IMPORTANT:
+//
+// When changing this implementation, make sure all the operations are
+// also declared in the related trait in `../traits/`.
+impl UrlMetric {
+    /// Creates a new URL metric.
+    pub fn new(meta: CommonMetricData) -> Self {
+        Self {
+            meta,
+            redact_query: false,
+        }
+    }
+
+    /// Creates a new URL metric recording only the scheme, host and path of URLs.
+    ///
+    /// The query string and fragment are stripped before the URL is checked and recorded.
+    pub fn with_redacted_query(meta: CommonMetricData) -> Self {
+        Self {
+            meta,
+            redact_query: true,
+        }
+    }
+
+    /// Sets to the specified URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `value` - The URL to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// If the metric was created with [`with_redacted_query`](#method.with_redacted_query),
+    /// the query string and fragment are stripped first.
+    /// If the URL is longer than `MAX_URL_LENGTH` bytes, an `ErrorType::InvalidOverflow` error is recorded.
+    /// If it is not a `http` or `https` URL, an `ErrorType::InvalidValue` error is recorded.
+    /// The stored value is not changed in both cases.
+    pub fn set<S: Into<String>>(&self, glean: &Glean, value: S) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        let mut value = value.into();
+        if self.redact_query {
+            let len = strip_query_and_fragment(&value).len();
+            value.truncate(len);
+        }
+
+        if value.len() > MAX_URL_LENGTH {
+            let msg = format!(
+                "URL length {} exceeds maximum of {}",
+                value.len(),
+                MAX_URL_LENGTH
+            );
+            record_error(glean, &self.meta, ErrorType::InvalidOverflow, msg, None);
+            return;
+        }
+
+        if !is_valid_url(&value) {
+            let msg = format!("Unexpected URL value '{}'", value);
+            record_error(glean, &self.meta, ErrorType::InvalidValue, msg, None);
+            return;
+        }
+
+        let value = Metric::Url(value);
+        glean.storage().record(glean, &self.meta, &value)
+    }
+
+    /// **Test-only API (exported for FFI purposes).**
+    ///
+    /// Gets the currently stored value as a string.
+    ///
+    /// This doesn't clear the stored value.
+    pub fn test_get_value(&self, glean: &Glean, storage_name: &str) -> Option<String> {
+        match StorageManager.snapshot_metric(
+            glean.storage(),
+            storage_name,
+            &self.meta.identifier(glean),
+        ) {
+            Some(Metric::Url(s)) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_http_urls_are_valid() {
+        assert!(is_valid_url("http://example.com"));
+        assert!(is_valid_url("https://example.com/path?query=1#fragment"));
+        assert!(is_valid_url("HTTPS://example.com"));
+
+        assert!(!is_valid_url(""));
+        assert!(!is_valid_url("example.com"));
+        assert!(!is_valid_url("https://"));
+        assert!(!is_valid_url("https:///path"));
+        assert!(!is_valid_url("ftp://example.com"));
+        assert!(!is_valid_url("data:text/plain,https://example.com"));
+        assert!(!is_valid_url("javascript://example.com"));
+    }
+
+    #[test]
+    fn query_and_fragment_are_stripped() {
+        assert_eq!(
+            "https://example.com/path",
+            strip_query_and_fragment("https://example.com/path?query=1#fragment")
+        );
+        assert_eq!(
+            "https://example.com/path",
+            strip_query_and_fragment("https://example.com/path#fragment?not=query")
+        );
+        assert_eq!(
+            "https://example.com/path",
+            strip_query_and_fragment("https://example.com/path")
+        );
+        assert_eq!(
+            "https://example.com",
+            strip_query_and_fragment("https://example.com?query=1")
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 use uuid::Uuid;
 
+use crate::error_recording::{record_error, ErrorType};
 use crate::metrics::Metric;
 use crate::metrics::MetricType;
 use crate::storage::StorageManager;
@@ -54,6 +55,31 @@ impl UuidMetric {
         glean.storage().record(glean, &self.meta, &value)
     }
 
+    /// Sets to the UUID parsed from the given string.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean instance this metric belongs to.
+    /// * `value` - The string representation of the UUID to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// If `value` is not a valid UUID, an `ErrorType::InvalidValue` error is recorded
+    /// and the stored value is not changed.
+    pub fn set_from_str(&self, glean: &Glean, value: &str) {
+        if !self.should_record(glean) {
+            return;
+        }
+
+        match Uuid::parse_str(value) {
+            Ok(uuid) => self.set(glean, uuid),
+            Err(_) => {
+                let msg = format!("Unexpected UUID value '{}'", value);
+                record_error(glean, &self.meta, ErrorType::InvalidValue, msg, None);
+            }
+        }
+    }
+
     /// Generates a new random UUID and set the metric to it.
     ///
     /// # Arguments
@@ -14,14 +14,31 @@ use serde_json::{json, Value as JsonValue};
 use crate::common_metric_data::{CommonMetricData, Lifetime};
 use crate::metrics::{CounterMetric, DatetimeMetric, Metric, MetricType, PingType, TimeUnit};
 use crate::storage::StorageManager;
-use crate::util::{get_iso_time_string, local_now_with_offset};
+use crate::util::get_iso_time_string;
 use crate::{
     Glean, Result, DELETION_REQUEST_PINGS_DIRECTORY, INTERNAL_STORAGE, PENDING_PINGS_DIRECTORY,
 };
 
+mod rate_limit;
+
+pub(crate) use rate_limit::PingRateLimiter;
+
 /// Collect a ping's data, assemble it into its full payload and store it on disk.
 pub struct PingMaker;
 
+/// The counter holding the sequence number of the ping with the given storage name.
+fn ping_seq_metric(storage_name: &str) -> CounterMetric {
+    // Sequence numbers are stored as a counter under a name that includes the storage name
+    CounterMetric::new(CommonMetricData {
+        name: format!("{}#sequence", storage_name),
+        // We don't need a category, the name is already unique
+        category: "".into(),
+        send_in_pings: vec![INTERNAL_STORAGE.into()],
+        lifetime: Lifetime::User,
+        ..Default::default()
+    })
+}
+
 fn merge(a: &mut JsonValue, b: &JsonValue) {
     match (a, b) {
         (&mut JsonValue::Object(ref mut a), &JsonValue::Object(ref b)) => {
@@ -51,33 +68,36 @@ impl PingMaker {
     ///
     /// This is crate-internal exclusively for enabling the migration tests.
     pub(super) fn get_ping_seq(&self, glean: &Glean, storage_name: &str) -> usize {
-        // Sequence numbers are stored as a counter under a name that includes the storage name
-        let seq = CounterMetric::new(CommonMetricData {
-            name: format!("{}#sequence", storage_name),
-            // We don't need a category, the name is already unique
-            category: "".into(),
-            send_in_pings: vec![INTERNAL_STORAGE.into()],
-            lifetime: Lifetime::User,
-            ..Default::default()
-        });
+        let current_seq = self.peek_ping_seq(glean, storage_name);
+
+        // Increase to next sequence id
+        ping_seq_metric(storage_name).add(glean, 1);
+
+        current_seq
+    }
 
-        let current_seq = match StorageManager.snapshot_metric(
+    /// Gets the sequence number for a given ping, without incrementing it.
+    fn peek_ping_seq(&self, glean: &Glean, storage_name: &str) -> usize {
+        let seq = ping_seq_metric(storage_name);
+        match StorageManager.snapshot_metric(
             glean.storage(),
             INTERNAL_STORAGE,
             &seq.meta().identifier(glean),
         ) {
-            Some(Metric::Counter(i)) => i,
+            Some(Metric::Counter(i)) => i as usize,
             _ => 0,
-        };
-
-        // Increase to next sequence id
-        seq.add(glean, 1);
-
-        current_seq as usize
+        }
     }
 
-    /// Gets the formatted start and end times for this ping and update for the next ping.
-    fn get_start_end_times(&self, glean: &Glean, storage_name: &str) -> (String, String) {
+    /// Gets the formatted start and end times for this ping.
+    ///
+    /// If `consume` is set, the start time is updated for the next ping.
+    fn get_start_end_times(
+        &self,
+        glean: &Glean,
+        storage_name: &str,
+        consume: bool,
+    ) -> (String, String) {
         let time_unit = TimeUnit::Minute;
 
         let start_time = DatetimeMetric::new(
@@ -96,10 +116,12 @@ impl PingMaker {
         let start_time_data = start_time
             .get_value(glean, INTERNAL_STORAGE)
             .unwrap_or_else(|| glean.start_time());
-        let end_time_data = local_now_with_offset();
+        let end_time_data = glean.clock().now_with_offset();
 
         // Update the start time with the current time.
-        start_time.set(glean, Some(end_time_data));
+        if consume {
+            start_time.set(glean, Some(end_time_data));
+        }
 
         // Format the times.
         let start_time_data = get_iso_time_string(start_time_data, time_unit);
@@ -107,10 +129,21 @@ impl PingMaker {
         (start_time_data, end_time_data)
     }
 
-    fn get_ping_info(&self, glean: &Glean, storage_name: &str, reason: Option<&str>) -> JsonValue {
-        let (start_time, end_time) = self.get_start_end_times(glean, storage_name);
+    fn get_ping_info(
+        &self,
+        glean: &Glean,
+        storage_name: &str,
+        reason: Option<&str>,
+        consume: bool,
+    ) -> JsonValue {
+        let (start_time, end_time) = self.get_start_end_times(glean, storage_name, consume);
+        let seq = if consume {
+            self.get_ping_seq(glean, storage_name)
+        } else {
+            self.peek_ping_seq(glean, storage_name)
+        };
         let mut map = json!({
-            "seq": self.get_ping_seq(glean, storage_name),
+            "seq": seq,
             "start_time": start_time,
             "end_time": end_time,
         });
@@ -161,7 +194,8 @@ impl PingMaker {
 
     /// Build the metadata JSON to be persisted with a ping.
     ///
-    /// Currently the only type of metadata we need to persist is the value of the `X-Debug-ID` header.
+    /// Currently the only type of metadata we need to persist are the values of the `X-Debug-ID`,
+    /// `X-Source-Tags` and `traceparent` headers.
     ///
     /// # Arguments
     ///
@@ -203,6 +237,16 @@ impl PingMaker {
                 );
         }
 
+        #[cfg(feature = "opentelemetry")]
+        if glean.trace_context_enabled() {
+            if let Some(traceparent) = crate::trace_context::current_traceparent() {
+                headers_map
+                    .as_object_mut()
+                    .unwrap() // safe unwrap, we created the object above
+                    .insert("traceparent".to_string(), JsonValue::String(traceparent));
+            }
+        }
+
         // safe unwrap, we created the object above
         if !headers_map.as_object().unwrap().is_empty() {
             Some(json!({
@@ -232,9 +276,42 @@ impl PingMaker {
         reason: Option<&str>,
     ) -> Option<JsonValue> {
         info!("Collecting {}", ping.name);
+        self.assemble(glean, ping, reason, true)
+    }
+
+    /// Assembles the payload the given ping would have if it was submitted now,
+    /// without changing any stored data.
+    ///
+    /// Unlike [`collect`](#method.collect), the data is not cleared
+    /// and the sequence number and start time are not advanced for the next ping.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - the Glean instance to collect data from.
+    /// * `ping` - the ping to inspect.
+    ///
+    /// # Returns
+    ///
+    /// A fully assembled JSON representation of the ping payload.
+    /// If there is no data stored for the ping, `None` is returned.
+    pub fn inspect(&self, glean: &Glean, ping: &PingType) -> Option<JsonValue> {
+        info!("Inspecting {}", ping.name);
+        self.assemble(glean, ping, None, false)
+    }
 
-        let metrics_data = StorageManager.snapshot_as_json(glean.storage(), &ping.name, true);
-        let events_data = glean.event_storage().snapshot_as_json(&ping.name, true);
+    /// Assembles the ping payload.
+    ///
+    /// If `consume` is set, the ping's data is cleared
+    /// and its sequence number and start time are advanced for the next ping.
+    fn assemble(
+        &self,
+        glean: &Glean,
+        ping: &PingType,
+        reason: Option<&str>,
+        consume: bool,
+    ) -> Option<JsonValue> {
+        let metrics_data = StorageManager.snapshot_as_json(glean.storage(), &ping.name, consume);
+        let events_data = glean.event_storage().snapshot_as_json(&ping.name, consume);
 
         let is_empty = metrics_data.is_none() && events_data.is_none();
         if !ping.send_if_empty && is_empty {
@@ -244,7 +321,7 @@ impl PingMaker {
             info!("Storage for {} empty. Ping will still be sent.", ping.name);
         }
 
-        let ping_info = self.get_ping_info(glean, &ping.name, reason);
+        let ping_info = self.get_ping_info(glean, &ping.name, reason, consume);
         let client_info = self.get_client_info(glean, ping.include_client_id);
 
         let mut json = json!({
@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Rate limiting of ping submissions.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The sliding window the submission limit applies to.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Pings that are never rate limited.
+const EXEMPT_PINGS: &[&str] = &["baseline", "deletion-request"];
+
+/// Limits how often each ping can be submitted within a sliding window of a minute.
+#[derive(Debug)]
+pub(crate) struct PingRateLimiter {
+    /// The maximum number of submissions per ping within the window.
+    max_per_window: u32,
+    /// The times of the submissions within the window, by ping name.
+    submissions: HashMap<String, VecDeque<Instant>>,
+}
+
+impl PingRateLimiter {
+    /// Creates a new rate limiter allowing `max_per_window` submissions per ping and minute.
+    pub fn new(max_per_window: u32) -> Self {
+        Self {
+            max_per_window,
+            submissions: HashMap::new(),
+        }
+    }
+
+    /// Records a submission of the given ping, unless it exceeds the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `ping_name` - The name of the ping being submitted.
+    /// * `now` - The time of the submission.
+    ///
+    /// # Returns
+    ///
+    /// Whether the ping can be submitted.
+    pub fn try_submit(&mut self, ping_name: &str, now: Instant) -> bool {
+        if EXEMPT_PINGS.contains(&ping_name) {
+            return true;
+        }
+
+        let submissions = self.submissions.entry(ping_name.to_string()).or_default();
+
+        // Forget the submissions that left the window.
+        while let Some(oldest) = submissions.front() {
+            if now.saturating_duration_since(*oldest) < RATE_LIMIT_WINDOW {
+                break;
+            }
+            submissions.pop_front();
+        }
+
+        if submissions.len() >= self.max_per_window as usize {
+            return false;
+        }
+
+        submissions.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn submissions_are_limited_per_ping() {
+        let mut limiter = PingRateLimiter::new(2);
+        let now = Instant::now();
+
+        assert!(limiter.try_submit("custom", now));
+        assert!(limiter.try_submit("custom", now));
+        assert!(!limiter.try_submit("custom", now));
+
+        // Other pings have their own limit.
+        assert!(limiter.try_submit("other", now));
+    }
+
+    #[test]
+    fn submissions_are_allowed_again_once_they_leave_the_window() {
+        let mut limiter = PingRateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(limiter.try_submit("custom", start));
+        assert!(limiter.try_submit("custom", start + Duration::from_secs(30)));
+        assert!(!limiter.try_submit("custom", start + Duration::from_secs(59)));
+
+        // The first submission left the window, the second one is still in it.
+        assert!(limiter.try_submit("custom", start + Duration::from_secs(60)));
+        assert!(!limiter.try_submit("custom", start + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn exempt_pings_are_never_limited() {
+        let mut limiter = PingRateLimiter::new(1);
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert!(limiter.try_submit("baseline", now));
+            assert!(limiter.try_submit("deletion-request", now));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Propagation of the OpenTelemetry trace context to pings.
+
+use opentelemetry::trace::{SpanContext, TraceContextExt};
+use opentelemetry::Context;
+
+/// The version of the W3C trace context format.
+const TRACEPARENT_VERSION: u8 = 0;
+
+/// Gets the `traceparent` header value for the current span.
+///
+/// Returns `None` if there is no active span.
+pub(crate) fn current_traceparent() -> Option<String> {
+    let context = Context::current();
+    traceparent(context.span().span_context())
+}
+
+/// Formats a span context as a `traceparent` header value,
+/// following the [W3C trace context](https://www.w3.org/TR/trace-context/#traceparent-header) format.
+fn traceparent(span_context: &SpanContext) -> Option<String> {
+    if !span_context.is_valid() {
+        return None;
+    }
+
+    Some(format!(
+        "{:02x}-{}-{}-{:02x}",
+        TRACEPARENT_VERSION,
+        span_context.trace_id(),
+        span_context.span_id(),
+        span_context.trace_flags().to_u8()
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use opentelemetry::trace::{SpanId, TraceFlags, TraceId, TraceState};
+
+    #[test]
+    fn traceparent_has_the_w3c_format() {
+        let span_context = SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            false,
+            TraceState::default(),
+        );
+        assert_eq!(
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string()),
+            traceparent(&span_context)
+        );
+    }
+
+    #[test]
+    fn no_traceparent_without_a_span() {
+        assert_eq!(None, traceparent(&SpanContext::empty_context()));
+        assert_eq!(None, current_traceparent());
+    }
+}
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::ErrorType;
+
 /// A description for the `CounterMetric` type.
 ///
 /// When changing this trait, make sure all the operations are
@@ -16,6 +18,8 @@ pub trait Counter {
     /// ## Notes
     ///
     /// Logs an error if the `amount` is 0 or negative.
+    /// If the counter would exceed `i32::MAX`, it saturates at that value
+    /// and an `ErrorType::InvalidOverflow` error is recorded.
     fn add(&self, amount: i32);
 
     /// **Exported for test purposes.**
@@ -24,4 +28,19 @@ pub trait Counter {
     ///
     /// This doesn't clear the stored value.
     fn test_get_value(&self, storage_name: &str) -> Option<i32>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
@@ -2,6 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::metrics::DistributionData;
+use crate::ErrorType;
+
 /// A description for the `CustomDistributionMetric` type.
 ///
 /// When changing this trait, make sure all the operations are
@@ -29,7 +32,7 @@ pub trait CustomDistribution {
     /// Gets the currently stored histogram.
     ///
     /// This doesn't clear the stored value.
-    fn test_get_value(&self, storage_name: &str);
+    fn test_get_value(&self, storage_name: &str) -> Option<DistributionData>;
 
     /// **Exported for test purposes.**
     ///
@@ -37,4 +40,19 @@ pub trait CustomDistribution {
     ///
     /// This doesn't clear the stored value.
     fn test_get_value_as_json_string(&self, storage_name: &str) -> Option<String>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
@@ -4,6 +4,8 @@
 
 #![allow(clippy::too_many_arguments)]
 
+use crate::ErrorType;
+
 /// A description for the `DatetimeMetric` type.
 ///
 /// When changing this trait, make sure all the operations are
@@ -50,4 +52,28 @@ pub trait Datetime {
     ///
     /// This doesn't clear the stored value.
     fn test_get_value_as_string(&self, storage_name: &str) -> Option<String>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value, with its timezone offset.
+    ///
+    /// The precision of this value is truncated to the `time_unit` precision.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<crate::metrics::Datetime>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
@@ -5,6 +5,7 @@
 use std::collections::HashMap;
 
 use crate::event_database::RecordedEvent;
+use crate::ErrorType;
 
 /// A description for the `EventMetric` type.
 ///
@@ -15,11 +16,11 @@ pub trait Event {
     ///
     /// # Arguments
     ///
-    /// * `extra` - A HashMap of (key, value) pairs. The key is an index into
-    ///   the metric's `allowed_extra_keys` vector where the key's string is
-    ///   looked up. If any key index is out of range, an error is reported and
-    ///   no event is recorded.
-    fn record<M: Into<Option<HashMap<i32, String>>>>(&self, extra: M);
+    /// * `extra` - A HashMap of (key, value) pairs. Each key must be one of the
+    ///   metric's `allowed_extra_keys`. If any key is not allowed, an error is
+    ///   reported and no event is recorded.
+    ///   Values longer than 100 bytes are truncated and an error is reported.
+    fn record(&self, extra: Option<HashMap<String, String>>);
 
     /// **Exported for test purposes.**
     ///
@@ -41,4 +42,19 @@ pub trait Event {
     ///
     /// This doesn't clear the stored value.
     fn test_get_value_as_json_string(&self, store_name: &str) -> String;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
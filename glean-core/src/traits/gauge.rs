@@ -0,0 +1,52 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::ErrorType;
+
+/// A description for the `GaugeMetric` type.
+///
+/// When changing this trait, make sure all the operations are
+/// implemented in the related type in `../metrics/`.
+pub trait Gauge {
+    /// Sets the gauge to `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to set the gauge to. Can be negative.
+    fn set(&self, value: i64);
+
+    /// Changes the gauge by `delta`.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The amount to change the gauge by. Negative values decrease it.
+    ///
+    /// ## Notes
+    ///
+    /// If the gauge would exceed `i64::MIN` or `i64::MAX`, it saturates at that value
+    /// and an error is logged.
+    fn add(&self, delta: i64);
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value as an integer.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<i64>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
+}
@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::metrics::MetricType;
+use crate::ErrorType;
 
 /// A description for the `LabeledMetric` type.
 ///
@@ -10,7 +10,7 @@ use crate::metrics::MetricType;
 /// implemented in the related type in `../metrics/`.
 pub trait Labeled<T>
 where
-    T: MetricType + Clone,
+    T: Clone,
 {
     /// Gets a specific metric for a given label.
     ///
@@ -25,9 +25,18 @@ where
     /// If an invalid label is used, the metric will be recorded in the special `OTHER_LABEL` label.
     fn get(&self, label: &str) -> T;
 
-    /// Gets the template submetric.
+    /// **Exported for test purposes.**
     ///
-    /// The template submetric is the actual metric that is cloned and modified
-    /// to record for a specific label.
-    fn get_submetric(&self) -> &T;
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
@@ -3,6 +3,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::metrics::DistributionData;
+use crate::ErrorType;
 
 /// A description for the `MemoryDistributionMetric` type.
 ///
@@ -60,4 +61,19 @@ pub trait MemoryDistribution {
     ///
     /// This doesn't clear the stored value.
     fn test_get_value_as_json_string(&self, storage_name: &str) -> Option<String>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
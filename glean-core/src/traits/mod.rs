@@ -11,15 +11,20 @@ mod counter;
 mod custom_distribution;
 mod datetime;
 mod event;
+mod gauge;
 mod jwe;
 mod labeled;
 mod memory_distribution;
+mod object;
 mod ping;
 mod quantity;
+mod rate;
 mod string;
 mod string_list;
+mod text;
 mod timespan;
 mod timing_distribution;
+mod url;
 mod uuid;
 
 pub use crate::event_database::RecordedEvent;
@@ -29,14 +34,19 @@ pub use self::counter::Counter;
 pub use self::custom_distribution::CustomDistribution;
 pub use self::datetime::Datetime;
 pub use self::event::Event;
+pub use self::gauge::Gauge;
 pub use self::jwe::Jwe;
 pub use self::labeled::Labeled;
 pub use self::memory_distribution::MemoryDistribution;
+pub use self::object::Object;
 pub use self::ping::Ping;
 pub use self::quantity::Quantity;
+pub use self::rate::Rate;
 pub use self::string::String;
 pub use self::string_list::StringList;
+pub use self::text::Text;
 pub use self::timespan::Timespan;
 pub use self::timing_distribution::TimingDistribution;
+pub use self::url::Url;
 pub use self::uuid::Uuid;
 pub use crate::histogram::HistogramType;
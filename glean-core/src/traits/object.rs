@@ -0,0 +1,47 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use serde_json::Value as JsonValue;
+
+use crate::ErrorType;
+
+/// A description for the `ObjectMetric` type.
+///
+/// When changing this trait, make sure all the operations are
+/// implemented in the related type in `../metrics/`.
+pub trait Object<T> {
+    /// Sets to the specified structured value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to set the metric to.
+    ///
+    /// ## Notes
+    ///
+    /// Logs an error if the value doesn't match the structure of the metric,
+    /// or is not serialized as an object or an array.
+    fn set(&self, value: T);
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the currently stored value as JSON.
+    ///
+    /// This doesn't clear the stored value.
+    fn test_get_value(&self, storage_name: &str) -> Option<JsonValue>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
+}
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::ErrorType;
+
 /// A description for the `QuantityMetric` type.
 ///
 /// When changing this trait, make sure all the operations are
@@ -18,10 +20,37 @@ pub trait Quantity {
     /// Logs an error if the `value` is negative.
     fn set(&self, value: i64);
 
+    /// Increases the value by `amount`. Must be non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to increase by. Must be non-negative.
+    ///
+    /// ## Notes
+    ///
+    /// Logs an error if the `amount` is negative.
+    /// If the value would exceed `i64::MAX`, it saturates at that value and an error is logged.
+    fn add(&self, amount: i64);
+
     /// **Exported for test purposes.**
     ///
     /// Gets the currently stored value as an integer.
     ///
     /// This doesn't clear the stored value.
     fn test_get_value(&self, storage_name: &str) -> Option<i64>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
@@ -4,6 +4,8 @@
 
 use std::time::Duration;
 
+use crate::ErrorType;
+
 /// A description for the `TimespanMetric` type.
 ///
 /// When changing this trait, make sure all the operations are
@@ -14,15 +16,15 @@ pub trait Timespan {
     /// This records an error if it's already tracking time (i.e. start was already
     /// called with no corresponding `stop`): in that case the original
     /// start time will be preserved.
-    fn set_start(&mut self, start_time: u64);
+    fn start(&self);
 
     /// Stops tracking time for the provided metric. Sets the metric to the elapsed time.
     ///
     /// This will record an error if no `start` was called.
-    fn set_stop(&mut self, stop_time: u64);
+    fn stop(&self);
 
     /// Aborts a previous `start` call. No error is recorded if no `start` was called.
-    fn cancel(&mut self);
+    fn cancel(&self);
 
     /// Explicitly sets the timespan value.
     ///
@@ -41,8 +43,23 @@ pub trait Timespan {
 
     /// **Exported for test purposes.**
     ///
-    /// Gets the currently stored value as an integer.
+    /// Gets the currently stored value as an integer, in the metric's time unit.
     ///
     /// This doesn't clear the stored value.
-    fn test_get_value(&self, storage_name: &str) -> Option<u64>;
+    fn test_get_value(&self, storage_name: &str) -> Option<i64>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
@@ -4,6 +4,7 @@
 
 use crate::metrics::DistributionData;
 use crate::metrics::TimerId;
+use crate::ErrorType;
 
 /// A description for the `TimingDistributionMetric` type.
 ///
@@ -12,18 +13,13 @@ use crate::metrics::TimerId;
 pub trait TimingDistribution {
     /// Starts tracking time for the provided metric.
     ///
-    /// This records an error if it’s already tracking time (i.e. start was already
-    /// called with no corresponding [stop]): in that case the original
-    /// start time will be preserved.
-    ///
-    /// # Arguments
-    ///
-    /// * `start_time` - Timestamp in nanoseconds.
+    /// Multiple timers can run at the same time,
+    /// each identified by the returned `TimerId`.
     ///
     /// # Returns
     ///
     /// A unique `TimerId` for the new timer.
-    fn set_start(&mut self, start_time: u64);
+    fn start(&self) -> TimerId;
 
     /// Stops tracking time for the provided metric and associated timer id.
     ///
@@ -35,10 +31,9 @@ pub trait TimingDistribution {
     /// * `id` - The `TimerId` to associate with this timing. This allows
     ///   for concurrent timing of events associated with different ids to the
     ///   same timespan metric.
-    /// * `stop_time` - Timestamp in nanoseconds.
-    fn set_stop_and_accumulate(&mut self, id: TimerId, stop_time: u64);
+    fn stop_and_accumulate(&self, id: TimerId);
 
-    /// Aborts a previous `set_start` call. No error is recorded if no `set_start`
+    /// Aborts a previous `start` call. No error is recorded if no `start`
     /// was called.
     ///
     /// # Arguments
@@ -46,7 +41,7 @@ pub trait TimingDistribution {
     /// * `id` - The `TimerId` to associate with this timing. This allows
     ///   for concurrent timing of events associated with different ids to the
     ///   same timing distribution metric.
-    fn cancel(&mut self, id: TimerId);
+    fn cancel(&self, id: TimerId);
 
     /// Accumulates the provided signed samples in the metric.
     ///
@@ -70,7 +65,7 @@ pub trait TimingDistribution {
     /// Discards any negative value in `samples` and report an `ErrorType::InvalidValue`
     /// for each of them. Reports an `ErrorType::InvalidOverflow` error for samples that
     /// are longer than `MAX_SAMPLE_TIME`.
-    fn accumulate_samples_signed(&mut self, samples: Vec<i64>);
+    fn accumulate_samples_signed(&self, samples: Vec<i64>);
 
     /// **Exported for test purposes.**
     ///
@@ -85,4 +80,19 @@ pub trait TimingDistribution {
     ///
     /// This doesn't clear the stored value.
     fn test_get_value_as_json_string(&self, storage_name: &str) -> Option<String>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
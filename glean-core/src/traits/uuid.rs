@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::ErrorType;
+
 /// A description for the `UuidMetric` type.
 ///
 /// When changing this trait, make sure all the operations are
@@ -14,6 +16,15 @@ pub trait Uuid {
     /// * `value` - The UUID to set the metric to.
     fn set(&self, value: uuid::Uuid);
 
+    /// Sets to the UUID parsed from the given string.
+    ///
+    /// If `value` is not a valid UUID, an error is recorded and the stored value is not changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string representation of the UUID to set the metric to.
+    fn set_from_str(&self, value: &str);
+
     /// Generates a new random UUID and set the metric to it.
     fn generate_and_set(&self) -> uuid::Uuid;
 
@@ -23,4 +34,19 @@ pub trait Uuid {
     ///
     /// This doesn't clear the stored value.
     fn test_get_value(&self, storage_name: &str) -> Option<String>;
+
+    /// **Exported for test purposes.**
+    ///
+    /// Gets the number of recorded errors for the given metric and error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The type of error
+    /// * `ping_name` - the optional name of the ping to retrieve the metric
+    ///                 for. Defaults to the first value in `send_in_pings`.
+    ///
+    /// # Returns
+    ///
+    /// The number of errors reported.
+    fn test_get_num_recorded_errors(&self, error: ErrorType, ping_name: Option<&str>) -> i32;
 }
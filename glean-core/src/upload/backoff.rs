@@ -0,0 +1,173 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Pausing uploads when the server asks for it or keeps failing.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+/// The backoff after the first server failure without a `Retry-After` header.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The maximum backoff after repeated server failures without a `Retry-After` header.
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+/// The maximum delay a `Retry-After` header can pause uploads for.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Parses the value of a `Retry-After` header.
+///
+/// The value is either a number of seconds or an HTTP-date, e.g. `Wed, 21 Oct 2015 07:28:00 GMT`.
+///
+/// # Arguments
+///
+/// * `value` - The header value.
+/// * `now` - The current time, to compute the delay until an HTTP-date.
+///
+/// # Returns
+///
+/// The time to wait, or `None` if the value can't be parsed.
+/// Dates in the past result in no delay, delays longer than 24 hours are capped.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds).min(MAX_RETRY_AFTER));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&Utc) - now;
+    Some(delay.to_std().unwrap_or_default().min(MAX_RETRY_AFTER))
+}
+
+/// Tracks when uploading can resume after the server asked us to back off.
+#[derive(Debug, Default)]
+pub struct Backoff {
+    /// The instant until which uploads are paused.
+    until: Option<Instant>,
+    /// The number of server failures in a row, used for the exponential backoff.
+    failures: u32,
+}
+
+impl Backoff {
+    /// Pauses uploads after a server failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The delay requested by the server in its `Retry-After` header, if any.
+    ///   Without it, the delay doubles with each failure in a row,
+    ///   starting at 1 second and capped at 5 minutes.
+    /// * `now` - The current monotonic time.
+    ///
+    /// # Returns
+    ///
+    /// The time uploads are paused for.
+    pub fn pause(&mut self, delay: Option<Duration>, now: Instant) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        let delay = delay.unwrap_or_else(|| {
+            let exponent = (self.failures - 1).min(31);
+            INITIAL_BACKOFF
+                .checked_mul(1 << exponent)
+                .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+        });
+        self.until = Some(
+            now.checked_add(delay)
+                .unwrap_or_else(|| now + MAX_RETRY_AFTER),
+        );
+        delay
+    }
+
+    /// Resets the backoff after a successful upload.
+    pub fn reset(&mut self) {
+        self.until = None;
+        self.failures = 0;
+    }
+
+    /// The time left from `now` until uploads can resume, if they are paused.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|remaining| *remaining > Duration::from_secs(0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parses_retry_after_seconds() {
+        let now = Utc::now();
+        assert_eq!(
+            Some(Duration::from_secs(120)),
+            parse_retry_after("120", now)
+        );
+        assert_eq!(Some(Duration::from_secs(0)), parse_retry_after(" 0 ", now));
+        assert_eq!(None, parse_retry_after("-5", now));
+        assert_eq!(None, parse_retry_after("soon", now));
+    }
+
+    #[test]
+    fn huge_retry_after_values_are_capped() {
+        let now = Utc::now();
+        assert_eq!(
+            Some(MAX_RETRY_AFTER),
+            parse_retry_after("18446744073709551615", now)
+        );
+        assert_eq!(
+            Some(MAX_RETRY_AFTER),
+            parse_retry_after("Fri, 31 Dec 9999 23:59:59 GMT", now)
+        );
+
+        // Even an unparsed delay too long to add to the current instant doesn't panic.
+        let now = Instant::now();
+        let mut backoff = Backoff::default();
+        assert_eq!(Duration::MAX, backoff.pause(Some(Duration::MAX), now));
+        assert_eq!(Some(MAX_RETRY_AFTER), backoff.remaining(now));
+    }
+
+    #[test]
+    fn parses_retry_after_http_dates() {
+        let now = Utc.ymd(2015, 10, 21).and_hms(7, 27, 30);
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now)
+        );
+
+        // Dates in the past don't delay uploads.
+        assert_eq!(
+            Some(Duration::from_secs(0)),
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now)
+        );
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_maximum() {
+        let now = Instant::now();
+        let mut backoff = Backoff::default();
+        assert_eq!(None, backoff.remaining(now));
+
+        let delays: Vec<u64> = (0..12)
+            .map(|_| backoff.pause(None, now).as_secs())
+            .collect();
+        assert_eq!(
+            vec![1, 2, 4, 8, 16, 32, 64, 128, 256, 300, 300, 300],
+            delays
+        );
+        assert_eq!(Some(Duration::from_secs(300)), backoff.remaining(now));
+
+        // A delay requested by the server is used as is.
+        assert_eq!(
+            Duration::from_secs(3600),
+            backoff.pause(Some(Duration::from_secs(3600)), now)
+        );
+
+        // Uploads resume once the delay passed.
+        let later = now + Duration::from_secs(3600);
+        assert_eq!(None, backoff.remaining(later));
+
+        backoff.reset();
+        assert_eq!(None, backoff.remaining(now));
+        assert_eq!(Duration::from_secs(1), backoff.pause(None, now));
+    }
+}
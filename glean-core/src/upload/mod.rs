@@ -9,7 +9,7 @@
 //! * Exposes `process_ping_upload_response` API to check the HTTP response from the ping upload
 //!   and either delete the corresponding ping from disk or re-enqueue it for sending.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryInto;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
@@ -17,17 +17,27 @@ use std::sync::{Arc, RwLock, RwLockWriteGuard};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use chrono::{TimeZone, Utc};
+
 use crate::error::ErrorKind;
 use crate::{internal_metrics::UploadMetrics, Glean};
+use backoff::{parse_retry_after, Backoff};
 use directory::{PingDirectoryManager, PingPayloadsByDirectory};
 use policy::Policy;
 pub use request::{HeaderMap, PingRequest};
 pub use result::{ffi_upload_result, UploadResult};
+use routing::EndpointRouter;
 
+mod backoff;
 mod directory;
 mod policy;
 mod request;
 mod result;
+mod routing;
+
+/// The time (in milliseconds) a requester is suggested to wait
+/// while the pending pings directories are being processed.
+const WAIT_TIME_FOR_PING_PROCESSING: u64 = 1000;
 
 #[derive(Debug)]
 struct RateLimiter {
@@ -47,7 +57,10 @@ enum RateLimiterState {
     /// The RateLimiter has not reached the maximum count and is still incrementing.
     Incrementing,
     /// The RateLimiter has reached the maximum count for the  current interval.
-    Throttled,
+    ///
+    /// This variant contains the remaining time (in milliseconds)
+    /// until the rate limiter is not throttled anymore.
+    Throttled(u64),
 }
 
 impl RateLimiter {
@@ -77,7 +90,7 @@ impl RateLimiter {
 
         // Safe unwrap, we already stated that `self.started` is not `None` above.
         let elapsed = self.started.unwrap().elapsed();
-        if elapsed > self.interval {
+        if elapsed >= self.interval {
             return true;
         }
 
@@ -95,7 +108,10 @@ impl RateLimiter {
         }
 
         if self.count == self.max_count {
-            return RateLimiterState::Throttled;
+            // `started` is always set after the reset check above.
+            let elapsed = self.started.map(|s| s.elapsed()).unwrap_or_default();
+            let remaining = self.interval.checked_sub(elapsed).unwrap_or_default();
+            return RateLimiterState::Throttled(as_millis_rounded_up(remaining));
         }
 
         self.count += 1;
@@ -103,6 +119,17 @@ impl RateLimiter {
     }
 }
 
+/// Converts a duration to milliseconds, rounding up,
+/// so that waiting the returned time always covers the whole duration.
+fn as_millis_rounded_up(duration: Duration) -> u64 {
+    let millis = duration.as_millis() as u64;
+    if duration > Duration::from_millis(millis) {
+        millis + 1
+    } else {
+        millis
+    }
+}
+
 /// An enum representing the possible upload tasks to be performed by an uploader.
 ///
 /// When asking for the next ping request to upload,
@@ -114,9 +141,13 @@ pub enum PingUploadTask {
     /// A PingRequest popped from the front of the queue.
     /// See [`PingRequest`](struct.PingRequest.html) for more information.
     Upload(PingRequest),
-    /// A flag signaling that the pending pings directories are not done being processed,
+    /// A flag signaling that the pending pings directories are not done being processed
+    /// or that uploads are currently rate limited,
     /// thus the requester should wait and come back later.
-    Wait,
+    ///
+    /// Contains the amount of time in milliseconds
+    /// the requester is suggested to wait before requesting a new task.
+    Wait(u64),
     /// A flag signaling that requester doesn't need to request any more upload tasks at this moment.
     ///
     /// There are three possibilities for this scenario:
@@ -151,6 +182,8 @@ pub struct PingUploadManager {
     /// To keep resource usage in check,
     /// we may want to limit the amount of pings sent in a given interval.
     rate_limiter: Option<RwLock<RateLimiter>>,
+    /// Pauses uploads after the server asked us to back off or keeps failing.
+    backoff: RwLock<Backoff>,
     /// The name of the programming language used by the binding creating this instance of PingUploadManager.
     ///
     /// This will be used to build the value User-Agent header for each ping request.
@@ -159,6 +192,10 @@ pub struct PingUploadManager {
     upload_metrics: UploadMetrics,
     /// Policies for ping storage, uploading and requests.
     policy: Policy,
+    /// Whether ping request bodies are gzip compressed.
+    compress_pings: bool,
+    /// The server endpoints of pings not sent to the default endpoint.
+    endpoint_router: EndpointRouter,
 }
 
 impl PingUploadManager {
@@ -181,9 +218,12 @@ impl PingUploadManager {
             recoverable_failure_count: AtomicU32::new(0),
             wait_attempt_count: AtomicU32::new(0),
             rate_limiter: None,
+            backoff: RwLock::new(Backoff::default()),
             language_binding_name: language_binding_name.into(),
             upload_metrics: UploadMetrics::new(),
             policy: Policy::default(),
+            compress_pings: true,
+            endpoint_router: EndpointRouter::default(),
         }
     }
 
@@ -232,6 +272,30 @@ impl PingUploadManager {
         upload_manager
     }
 
+    /// Sets whether ping request bodies are gzip compressed.
+    ///
+    /// Compression is enabled by default.
+    pub fn set_compress_pings(&mut self, compress_pings: bool) {
+        self.compress_pings = compress_pings;
+    }
+
+    /// Sets the server endpoints for pings not sent to the default endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoints` - The server endpoints by ping name. Ping names may contain `*` wildcards.
+    pub fn set_extra_server_endpoints(&mut self, endpoints: HashMap<String, String>) {
+        self.endpoint_router = EndpointRouter::new(endpoints);
+    }
+
+    /// Gets the server endpoint a ping request is sent to,
+    /// or `None` if it is sent to the default endpoint.
+    pub fn server_endpoint_for(&self, request: &PingRequest) -> Option<&str> {
+        request
+            .ping_name()
+            .and_then(|ping_name| self.endpoint_router.endpoint_for(ping_name))
+    }
+
     fn processed_pending_pings(&self) -> bool {
         self.processed_pending_pings.load(Ordering::SeqCst)
     }
@@ -262,6 +326,7 @@ impl PingUploadManager {
         )
         .document_id(document_id)
         .path(path)
+        .compress(self.compress_pings)
         .body(body);
 
         if let Some(headers) = headers {
@@ -315,7 +380,42 @@ impl PingUploadManager {
 
         log::trace!("Enqueuing ping {} at {}", document_id, path);
         if let Some(request) = self.build_ping_request(glean, document_id, path, body, headers) {
-            queue.push_back(request)
+            queue.push_back(request);
+            self.evict_pings_over_count_quota(glean, &mut queue);
+        }
+    }
+
+    /// Deletes the oldest pending pings while there are more than allowed by the count quota.
+    ///
+    /// Deletion-request pings are never deleted and don't count towards the quota.
+    fn evict_pings_over_count_quota(&self, glean: &Glean, queue: &mut VecDeque<PingRequest>) {
+        let max_count = self.policy.max_pending_pings_count();
+        let mut count = queue
+            .iter()
+            .filter(|request| !request.is_deletion_request())
+            .count() as u64;
+
+        while count > max_count {
+            // The queue is ordered oldest -> newest.
+            let oldest = match queue
+                .iter()
+                .position(|request| !request.is_deletion_request())
+            {
+                Some(oldest) => oldest,
+                None => break,
+            };
+            let request = queue.remove(oldest).unwrap();
+            log::warn!(
+                "More than {} pending pings, deleting the oldest ping {}.",
+                max_count,
+                request.document_id
+            );
+            if self.directory_manager.delete_file(&request.document_id) {
+                self.upload_metrics
+                    .deleted_pings_after_quota_hit
+                    .add(glean, 1);
+            }
+            count -= 1;
         }
     }
 
@@ -424,13 +524,38 @@ impl PingUploadManager {
     ///
     /// # Arguments
     ///
-    /// * `interval` - the amount of seconds in each rate limiting window.
+    /// * `interval` - the duration of each rate limiting window.
     /// * `max_tasks` - the maximum amount of task requests allowed per interval.
-    pub fn set_rate_limiter(&mut self, interval: u64, max_tasks: u32) {
-        self.rate_limiter = Some(RwLock::new(RateLimiter::new(
-            Duration::from_secs(interval),
-            max_tasks,
-        )));
+    pub fn set_rate_limiter(&mut self, interval: Duration, max_tasks: u32) {
+        self.rate_limiter = Some(RwLock::new(RateLimiter::new(interval, max_tasks)));
+    }
+
+    /// Sets the maximum number of pending pings.
+    ///
+    /// Once there are more pending pings, the oldest are deleted.
+    /// Deletion-request pings are never deleted and don't count towards this limit.
+    ///
+    /// Call [`enforce_pending_pings_count`](#method.enforce_pending_pings_count)
+    /// to apply a lowered limit to the pings already enqueued.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_count` - The maximum number of pending pings.
+    pub fn set_max_pending_pings_count(&mut self, max_count: u64) {
+        self.policy.set_max_pending_pings_count(Some(max_count));
+    }
+
+    /// Deletes the oldest enqueued pings exceeding the maximum number of pending pings.
+    ///
+    /// # Arguments
+    ///
+    /// * `glean` - The Glean object holding the database.
+    pub fn enforce_pending_pings_count(&self, glean: &Glean) {
+        let mut queue = self
+            .queue
+            .write()
+            .expect("Can't write to pending pings queue.");
+        self.evict_pings_over_count_quota(glean, &mut queue);
     }
 
     /// Reads a ping file, creates a `PingRequest` and adds it to the queue.
@@ -470,12 +595,12 @@ impl PingUploadManager {
         //
         // We want to limit the amount of PingUploadTask::Wait returned in a row,
         // in case we reach MAX_WAIT_ATTEMPTS we want to actually return PingUploadTask::Done.
-        let wait_or_done = || {
+        let wait_or_done = |time: u64| {
             self.wait_attempt_count.fetch_add(1, Ordering::SeqCst);
             if self.wait_attempt_count() > self.policy.max_wait_attempts() {
                 PingUploadTask::Done
             } else {
-                PingUploadTask::Wait
+                PingUploadTask::Wait(time)
             }
         };
 
@@ -483,7 +608,7 @@ impl PingUploadManager {
             log::info!(
                 "Tried getting an upload task, but processing is ongoing. Will come back later."
             );
-            return wait_or_done();
+            return wait_or_done(WAIT_TIME_FOR_PING_PROCESSING);
         }
 
         // This is a no-op in case there are no cached pings.
@@ -502,15 +627,27 @@ impl PingUploadManager {
             .expect("Can't write to pending pings queue.");
         match queue.front() {
             Some(request) => {
+                let backoff = self
+                    .backoff
+                    .read()
+                    .expect("Can't read the upload backoff.")
+                    .remaining(glean.clock().monotonic_now());
+                if let Some(remaining) = backoff {
+                    log::info!(
+                        "Tried getting an upload task, but the server asked us to back off."
+                    );
+                    return wait_or_done(as_millis_rounded_up(remaining));
+                }
+
                 if let Some(rate_limiter) = &self.rate_limiter {
                     let mut rate_limiter = rate_limiter
                         .write()
                         .expect("Can't write to the rate limiter.");
-                    if rate_limiter.get_state() == RateLimiterState::Throttled {
+                    if let RateLimiterState::Throttled(remaining) = rate_limiter.get_state() {
                         log::info!(
                             "Tried getting an upload task, but we are throttled at the moment."
                         );
-                        return wait_or_done();
+                        return wait_or_done(remaining);
                     }
                 }
 
@@ -537,6 +674,67 @@ impl PingUploadManager {
         }
     }
 
+    /// Gets the number of pending pings.
+    ///
+    /// This includes the pings from the pending pings directories not enqueued yet.
+    pub fn pending_pings_count(&self) -> usize {
+        let queued = self
+            .queue
+            .read()
+            .expect("Can't read pending pings queue.")
+            .len();
+        let cached = self
+            .cached_pings
+            .read()
+            .expect("Can't read pending pings cache.")
+            .len();
+        queued + cached
+    }
+
+    /// Gets the maximum number of pending pings, or `None` if it is not limited.
+    pub fn max_pending_pings_count(&self) -> Option<u64> {
+        Some(self.policy.max_pending_pings_count()).filter(|&max_count| max_count != u64::MAX)
+    }
+
+    /// Gets the document ids of the queued pings with the given name, in queue order.
+    pub fn queued_doc_ids(&self, ping_name: &str) -> Vec<String> {
+        let queue = self.queue.read().expect("Can't read pending pings queue.");
+
+        // The upload path has the form
+        // `/submit/<application_id>/<ping_name>/<schema_version>/<document_id>`.
+        queue
+            .iter()
+            .filter(|request| request.path.rsplit('/').nth(2) == Some(ping_name))
+            .map(|request| request.document_id.clone())
+            .collect()
+    }
+
+    /// Gets the request body of the most recently queued ping with the given name.
+    ///
+    /// This is the body as it is uploaded, i.e. compressed unless compression is disabled.
+    pub fn queued_body(&self, ping_name: &str) -> Option<Vec<u8>> {
+        let queue = self.queue.read().expect("Can't read pending pings queue.");
+
+        queue
+            .iter()
+            .rev()
+            .find(|request| request.ping_name() == Some(ping_name))
+            .map(|request| request.body.clone())
+    }
+
+    /// Gets the JSON payload of the most recently queued ping with the given name.
+    ///
+    /// The payload is decompressed and pretty-printed.
+    pub fn queued_payload(&self, ping_name: &str) -> Option<String> {
+        let queue = self.queue.read().expect("Can't read pending pings queue.");
+
+        queue
+            .iter()
+            .rev()
+            .find(|request| request.ping_name() == Some(ping_name))
+            .and_then(PingRequest::pretty_body)
+    }
+
     /// Gets the next `PingUploadTask`.
     ///
     /// # Arguments
@@ -550,13 +748,12 @@ impl PingUploadManager {
     pub fn get_upload_task(&self, glean: &Glean, log_ping: bool) -> PingUploadTask {
         let task = self.get_upload_task_internal(glean, log_ping);
 
-        if task != PingUploadTask::Wait && self.wait_attempt_count() > 0 {
+        let is_wait = matches!(task, PingUploadTask::Wait(_));
+        if !is_wait && self.wait_attempt_count() > 0 {
             self.wait_attempt_count.store(0, Ordering::SeqCst);
         }
 
-        if (task == PingUploadTask::Wait || task == PingUploadTask::Done)
-            && self.recoverable_failure_count() > 0
-        {
+        if (is_wait || task == PingUploadTask::Done) && self.recoverable_failure_count() > 0 {
             self.recoverable_failure_count.store(0, Ordering::SeqCst);
         }
 
@@ -614,13 +811,48 @@ impl PingUploadManager {
             metric.add(glean, 1);
         }
 
-        match status {
-            HttpStatus(status @ 200..=299) => {
-                log::info!("Ping {} successfully sent {}.", document_id, status);
+        match &status {
+            HttpStatus(code @ 200..=299) | HttpStatusWithRetryAfter(code @ 200..=299, _) => {
+                log::info!("Ping {} successfully sent {}.", document_id, code);
                 self.directory_manager.delete_file(document_id);
+                self.backoff
+                    .write()
+                    .expect("Can't write to the upload backoff.")
+                    .reset();
+            }
+
+            // The server is overloaded or asks us to slow down:
+            // pause all uploads, for as long as the server asked for if it did.
+            HttpStatus(429)
+            | HttpStatus(500..=599)
+            | HttpStatusWithRetryAfter(429, _)
+            | HttpStatusWithRetryAfter(500..=599, _) => {
+                let clock = glean.clock();
+                let retry_after = match &status {
+                    HttpStatusWithRetryAfter(_, value) => {
+                        parse_retry_after(value, Utc.timestamp_millis(clock.now_millis()))
+                    }
+                    _ => None,
+                };
+                let delay = self
+                    .backoff
+                    .write()
+                    .expect("Can't write to the upload backoff.")
+                    .pause(retry_after, clock.monotonic_now());
+                log::info!(
+                    "Server failure while attempting to send ping {}, will retry in {:?}. Error was {:?}",
+                    document_id,
+                    delay,
+                    status
+                );
+                self.enqueue_ping_from_file(glean, document_id);
+                self.recoverable_failure_count
+                    .fetch_add(1, Ordering::SeqCst);
             }
 
-            UnrecoverableFailure | HttpStatus(400..=499) => {
+            UnrecoverableFailure
+            | HttpStatus(400..=499)
+            | HttpStatusWithRetryAfter(400..=499, _) => {
                 log::error!(
                     "Unrecoverable upload failure while attempting to send ping {}. Error was {:?}",
                     document_id,
@@ -629,13 +861,13 @@ impl PingUploadManager {
                 self.directory_manager.delete_file(document_id);
             }
 
-            RecoverableFailure | HttpStatus(_) => {
+            RecoverableFailure | HttpStatus(_) | HttpStatusWithRetryAfter(..) => {
                 log::info!(
                     "Recoverable upload failure while attempting to send ping {}, will retry. Error was {:?}",
                     document_id,
                     status
                 );
-                self.enqueue_ping_from_file(glean, &document_id);
+                self.enqueue_ping_from_file(glean, document_id);
                 self.recoverable_failure_count
                     .fetch_add(1, Ordering::SeqCst);
             }
@@ -714,12 +946,13 @@ mod test {
     use std::thread;
     use std::time::Duration;
 
+    use serde_json::{json, Value as JsonValue};
     use uuid::Uuid;
 
     use super::UploadResult::*;
     use super::*;
     use crate::metrics::PingType;
-    use crate::{tests::new_glean, PENDING_PINGS_DIRECTORY};
+    use crate::{tests::new_glean, MockClock, PENDING_PINGS_DIRECTORY};
 
     const PATH: &str = "/submit/app_id/ping_name/schema_version/doc_id";
 
@@ -749,6 +982,63 @@ mod test {
         }
     }
 
+    #[test]
+    fn uncompressed_ping_requests_contain_the_original_body() {
+        let (glean, dir) = new_glean(None);
+
+        let mut upload_manager = PingUploadManager::no_policy(dir.path());
+        upload_manager.set_compress_pings(false);
+
+        let body = r#"{"ping_info":{"seq":1}}"#;
+        upload_manager.enqueue_ping(&glean, &Uuid::new_v4().to_string(), PATH, body, None);
+
+        match upload_manager.get_upload_task(&glean, false) {
+            PingUploadTask::Upload(request) => {
+                assert_eq!(body.as_bytes(), &request.body[..]);
+                assert!(!request.headers.contains_key("Content-Encoding"));
+            }
+            _ => panic!("Expected upload manager to return the next request!"),
+        }
+    }
+
+    #[test]
+    fn queued_body_is_taken_from_the_latest_ping_with_the_name() {
+        let (glean, dir) = new_glean(None);
+
+        let mut upload_manager = PingUploadManager::no_policy(dir.path());
+        upload_manager.set_compress_pings(false);
+
+        let first = Uuid::new_v4().to_string();
+        let second = Uuid::new_v4().to_string();
+        let other = Uuid::new_v4().to_string();
+        let path = |name: &str, doc_id: &str| format!("/submit/app-id/{}/1/{}", name, doc_id);
+        upload_manager.enqueue_ping(&glean, &first, &path("custom", &first), "first", None);
+        upload_manager.enqueue_ping(&glean, &second, &path("custom", &second), "second", None);
+        upload_manager.enqueue_ping(&glean, &other, &path("other", &other), "other", None);
+
+        assert_eq!(
+            Some(b"second".to_vec()),
+            upload_manager.queued_body("custom")
+        );
+        assert_eq!(None, upload_manager.queued_body("missing"));
+    }
+
+    #[test]
+    fn queued_payload_is_decompressed() {
+        let (glean, dir) = new_glean(None);
+
+        let upload_manager = PingUploadManager::no_policy(dir.path());
+
+        let doc_id = Uuid::new_v4().to_string();
+        let path = format!("/submit/app-id/custom/1/{}", doc_id);
+        upload_manager.enqueue_ping(&glean, &doc_id, &path, r#"{"ping_info":{"seq":3}}"#, None);
+
+        let payload: JsonValue =
+            serde_json::from_str(&upload_manager.queued_payload("custom").unwrap()).unwrap();
+        assert_eq!(json!({"ping_info": {"seq": 3}}), payload);
+        assert_eq!(None, upload_manager.queued_payload("missing"));
+    }
+
     #[test]
     fn returns_as_many_ping_requests_as_there_are() {
         let (glean, dir) = new_glean(None);
@@ -785,7 +1075,7 @@ mod test {
         // Add a rate limiter to the upload mangager with max of 10 pings every 3 seconds.
         let secs_per_interval = 3;
         let max_pings_per_interval = 10;
-        upload_manager.set_rate_limiter(secs_per_interval, 10);
+        upload_manager.set_rate_limiter(Duration::from_secs(secs_per_interval), 10);
 
         // Enqueue the max number of pings allowed per uploading window
         for _ in 0..max_pings_per_interval {
@@ -803,11 +1093,15 @@ mod test {
         // Enqueue just one more ping
         upload_manager.enqueue_ping(&glean, &Uuid::new_v4().to_string(), PATH, "", None);
 
-        // Verify that we are indeed told to wait because we are at capacity
-        assert_eq!(
-            PingUploadTask::Wait,
-            upload_manager.get_upload_task(&glean, false)
-        );
+        // Verify that we are indeed told to wait because we are at capacity,
+        // at most until the current uploading window ends.
+        match upload_manager.get_upload_task(&glean, false) {
+            PingUploadTask::Wait(time) => {
+                assert!(time > 0);
+                assert!(time <= secs_per_interval * 1000);
+            }
+            _ => panic!("Expected upload manager to return a wait task!"),
+        }
 
         // Wait for the uploading window to reset
         thread::sleep(Duration::from_secs(secs_per_interval));
@@ -967,6 +1261,8 @@ mod test {
     #[test]
     fn processes_correctly_server_error_upload_response() {
         let (mut glean, _) = new_glean(None);
+        let clock = Arc::new(MockClock::new());
+        glean.set_clock(clock.clone());
 
         // Register a ping for testing
         let ping_type = PingType::new("test", true, /* send_if_empty */ true, vec![]);
@@ -981,6 +1277,13 @@ mod test {
                 // Simulate the processing of a client error
                 let document_id = request.document_id;
                 glean.process_ping_upload_response(&document_id, HttpStatus(500));
+                // Verify uploads are paused for the initial backoff of 1 second
+                let time = match glean.get_upload_task() {
+                    PingUploadTask::Wait(time) => time,
+                    _ => panic!("Expected upload manager to return a wait task!"),
+                };
+                assert_eq!(1000, time);
+                clock.advance(Duration::from_millis(time));
                 // Verify this ping was indeed re-enqueued
                 match glean.get_upload_task() {
                     PingUploadTask::Upload(request) => {
@@ -996,6 +1299,55 @@ mod test {
         assert_eq!(glean.get_upload_task(), PingUploadTask::Done);
     }
 
+    #[test]
+    fn pauses_uploads_as_requested_by_retry_after() {
+        let (mut glean, _) = new_glean(None);
+        let clock = Arc::new(MockClock::at_millis(1_445_412_450_000));
+        glean.set_clock(clock.clone());
+
+        let ping_type = PingType::new("test", true, /* send_if_empty */ true, vec![]);
+        glean.register_ping_type(&ping_type);
+        glean.submit_ping(&ping_type, None).unwrap();
+
+        let document_id = match glean.get_upload_task() {
+            PingUploadTask::Upload(request) => request.document_id,
+            _ => panic!("Expected upload manager to return the next request!"),
+        };
+
+        // `Retry-After` in seconds.
+        glean.process_ping_upload_response(
+            &document_id,
+            HttpStatusWithRetryAfter(429, "120".into()),
+        );
+        match glean.get_upload_task() {
+            PingUploadTask::Wait(time) => assert_eq!(120_000, time),
+            _ => panic!("Expected upload manager to return a wait task!"),
+        }
+
+        // `Retry-After` as an HTTP-date, a minute after the clock's time.
+        glean.process_ping_upload_response(
+            &document_id,
+            HttpStatusWithRetryAfter(503, "Wed, 21 Oct 2015 07:28:30 GMT".into()),
+        );
+        match glean.get_upload_task() {
+            PingUploadTask::Wait(time) => assert_eq!(60_000, time),
+            _ => panic!("Expected upload manager to return a wait task!"),
+        }
+
+        // The ping is kept for the next attempt.
+        assert_eq!(
+            vec![document_id.clone()],
+            glean.test_get_pending_ping_doc_ids("test")
+        );
+
+        // Uploads resume once the clock passed the delay.
+        clock.advance(Duration::from_secs(60));
+        match glean.get_upload_task() {
+            PingUploadTask::Upload(request) => assert_eq!(document_id, request.document_id),
+            _ => panic!("Expected upload manager to return the next request!"),
+        }
+    }
+
     #[test]
     fn processes_correctly_unrecoverable_upload_response() {
         let (mut glean, dir) = new_glean(None);
@@ -1311,6 +1663,53 @@ mod test {
         );
     }
 
+    #[test]
+    fn number_quota_is_enforced_when_enqueueing_new_pings() {
+        let (mut glean, dir) = new_glean(None);
+
+        // Register a ping for testing
+        let ping_type = PingType::new("test", true, /* send_if_empty */ true, vec![]);
+        glean.register_ping_type(&ping_type);
+
+        // How many pings we allow at maximum
+        let count_quota = 3;
+        glean.set_max_pending_pings(count_quota);
+
+        // Submit the ping more often than allowed,
+        // keeping track of the order the pings were enqueued in.
+        let n = 10;
+        let mut submitted_pings = vec![];
+        for _ in 0..n {
+            glean.submit_ping(&ping_type, None).unwrap();
+            let queue = glean.upload_manager.queue.read().unwrap();
+            submitted_pings.push(queue.back().unwrap().document_id.clone());
+        }
+
+        // Only the newest pings are left, in the order they were submitted.
+        for ping_id in &submitted_pings[n - count_quota..] {
+            match glean.get_upload_task() {
+                PingUploadTask::Upload(request) => assert_eq!(&request.document_id, ping_id),
+                _ => panic!("Expected upload manager to return the next request!"),
+            }
+        }
+        assert_eq!(glean.get_upload_task(), PingUploadTask::Done);
+
+        // The oldest pings were deleted from disk.
+        let pending_pings_dir = dir.path().join(PENDING_PINGS_DIRECTORY);
+        assert_eq!(count_quota, pending_pings_dir.read_dir().unwrap().count());
+
+        // Verify that the correct number of deleted pings was recorded
+        assert_eq!(
+            (n - count_quota) as i32,
+            glean
+                .upload_manager
+                .upload_metrics
+                .deleted_pings_after_quota_hit
+                .test_get_value(&glean, "metrics")
+                .unwrap()
+        );
+    }
+
     #[test]
     fn size_and_count_quota_work_together_size_first() {
         let (mut glean, dir) = new_glean(None);
@@ -1478,7 +1877,10 @@ mod test {
         // which will allow us to test the limitations around returning too many of those in a row.
         let secs_per_interval = 5;
         let max_pings_per_interval = 1;
-        upload_manager.set_rate_limiter(secs_per_interval, max_pings_per_interval);
+        upload_manager.set_rate_limiter(
+            Duration::from_secs(secs_per_interval),
+            max_pings_per_interval,
+        );
 
         // Enqueue two pings
         upload_manager.enqueue_ping(&glean, &Uuid::new_v4().to_string(), PATH, "", None);
@@ -1494,10 +1896,8 @@ mod test {
         // we should be throttled and thus get a PingUploadTask::Wait.
         // Check that we are indeed allowed to get this response as many times as expected.
         for _ in 0..max_wait_attempts {
-            assert_eq!(
-                upload_manager.get_upload_task(&glean, false),
-                PingUploadTask::Wait
-            );
+            let task = upload_manager.get_upload_task(&glean, false);
+            assert!(matches!(task, PingUploadTask::Wait(_)));
         }
 
         // Check that after we get PingUploadTask::Wait the allowed number of times,
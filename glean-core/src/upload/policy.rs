@@ -105,7 +105,6 @@ impl Policy {
         self.max_pending_pings_directory_size = v;
     }
 
-    #[cfg(test)]
     pub fn set_max_pending_pings_count(&mut self, v: Option<u64>) {
         self.max_pending_pings_count = v;
     }
@@ -62,6 +62,7 @@ pub struct Builder {
     body: Option<Vec<u8>>,
     headers: HeaderMap,
     body_max_size: usize,
+    compress: bool,
 }
 
 impl Builder {
@@ -89,6 +90,7 @@ impl Builder {
             body: None,
             headers,
             body_max_size,
+            compress: true,
         }
     }
 
@@ -104,10 +106,19 @@ impl Builder {
         self
     }
 
+    /// Sets whether the body of this request is gzip compressed.
+    ///
+    /// Compression is enabled by default.
+    /// This needs to be set before setting the body.
+    pub fn compress(mut self, value: bool) -> Self {
+        self.compress = value;
+        self
+    }
+
     /// Sets the body for this request.
     ///
-    /// This method will also attempt to gzip the body contents
-    /// and add headers related to the body that was just added.
+    /// Unless compression was disabled, this method will also attempt to gzip the body contents.
+    /// It adds headers related to the body that was just added.
     ///
     /// Namely these headers are the "Content-Length" with the length of the body
     /// and in case we are successfull on gzipping the contents, the "Content-Encoding"="gzip".
@@ -121,12 +132,15 @@ impl Builder {
     pub fn body<S: Into<String>>(mut self, value: S) -> Self {
         // Attempt to gzip the body contents.
         let original_as_string = value.into();
-        let gzipped_content = gzip_content(
-            self.path
-                .as_ref()
-                .expect("Path must be set before attempting to set the body"),
-            original_as_string.as_bytes(),
-        );
+        let path = self
+            .path
+            .as_ref()
+            .expect("Path must be set before attempting to set the body");
+        let gzipped_content = if self.compress {
+            gzip_content(path, original_as_string.as_bytes())
+        } else {
+            None
+        };
         let add_gzip_header = gzipped_content.is_some();
         let body = gzipped_content.unwrap_or_else(|| original_as_string.into_bytes());
 
@@ -210,12 +224,15 @@ impl PingRequest {
 
     /// Verifies if current request is for a deletion-request ping.
     pub fn is_deletion_request(&self) -> bool {
+        self.ping_name() == Some("deletion-request")
+    }
+
+    /// Gets the name of the ping this request is for.
+    ///
+    /// Returns `None` if the path is not a submission path.
+    pub fn ping_name(&self) -> Option<&str> {
         // The path format should be `/submit/<app_id>/<ping_name>/<schema_version/<doc_id>`
-        self.path
-            .split('/')
-            .nth(3)
-            .map(|url| url == "deletion-request")
-            .unwrap_or(false)
+        self.path.split('/').nth(3)
     }
 
     /// Decompresses and pretty-format the ping payload
@@ -288,4 +305,62 @@ mod test {
 
         assert!(request.is_err());
     }
+
+    #[test]
+    fn ping_name_is_taken_from_the_path() {
+        let request = PingRequest::builder(/* language_binding_name */ "Rust", 1024 * 1024)
+            .document_id("woop")
+            .path("/submit/app-id/crash-main/1/woop")
+            .body("{}")
+            .build()
+            .unwrap();
+        assert_eq!(Some("crash-main"), request.ping_name());
+        assert!(!request.is_deletion_request());
+
+        let request = PingRequest::builder(/* language_binding_name */ "Rust", 1024 * 1024)
+            .document_id("woop")
+            .path("/random")
+            .body("{}")
+            .build()
+            .unwrap();
+        assert_eq!(None, request.ping_name());
+    }
+
+    #[test]
+    fn compressed_body_decompresses_to_the_original() {
+        let body = r#"{"ping_info":{"seq":1}}"#;
+        let request = PingRequest::builder(/* language_binding_name */ "Rust", 1024 * 1024)
+            .document_id("woop")
+            .path("/random/path/doesnt/matter")
+            .body(body)
+            .build()
+            .unwrap();
+
+        assert_eq!("gzip", request.headers["Content-Encoding"]);
+        assert_eq!(
+            request.body.len().to_string(),
+            request.headers["Content-Length"]
+        );
+
+        let mut decompressed = String::new();
+        GzDecoder::new(&request.body[..])
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(body, decompressed);
+    }
+
+    #[test]
+    fn body_is_not_compressed_if_disabled() {
+        let body = r#"{"ping_info":{"seq":1}}"#;
+        let request = PingRequest::builder(/* language_binding_name */ "Rust", 1024 * 1024)
+            .document_id("woop")
+            .path("/random/path/doesnt/matter")
+            .compress(false)
+            .body(body)
+            .build()
+            .unwrap();
+
+        assert!(!request.headers.contains_key("Content-Encoding"));
+        assert_eq!(body.as_bytes(), &request.body[..]);
+    }
 }
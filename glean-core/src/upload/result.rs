@@ -28,7 +28,7 @@ pub mod ffi_upload_result {
 use ffi_upload_result::*;
 
 /// The result of an attempted ping upload.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum UploadResult {
     /// A recoverable failure.
     ///
@@ -46,6 +46,13 @@ pub enum UploadResult {
     ///
     /// This can still indicate an error, depending on the status code.
     HttpStatus(u32),
+
+    /// A HTTP response code, with the value of the response's `Retry-After` header.
+    ///
+    /// Servers send this header e.g. with `429` or `503` responses,
+    /// to ask clients to pause uploading for a while.
+    /// The value is either a number of seconds or an HTTP-date.
+    HttpStatusWithRetryAfter(u32, String),
 }
 
 impl From<u32> for UploadResult {
@@ -72,10 +79,13 @@ impl UploadResult {
     /// Failures are recorded in the `ping_upload_failure` metric.
     pub fn get_label(&self) -> Option<&str> {
         match self {
-            UploadResult::HttpStatus(200..=299) => None,
-            UploadResult::HttpStatus(400..=499) => Some("status_code_4xx"),
-            UploadResult::HttpStatus(500..=599) => Some("status_code_5xx"),
-            UploadResult::HttpStatus(_) => Some("status_code_unknown"),
+            UploadResult::HttpStatus(status)
+            | UploadResult::HttpStatusWithRetryAfter(status, _) => match status {
+                200..=299 => None,
+                400..=499 => Some("status_code_4xx"),
+                500..=599 => Some("status_code_5xx"),
+                _ => Some("status_code_unknown"),
+            },
             UploadResult::UnrecoverableFailure => Some("unrecoverable"),
             UploadResult::RecoverableFailure => Some("recoverable"),
         }
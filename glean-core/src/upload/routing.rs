@@ -0,0 +1,150 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Routing of pings to server endpoints other than the default one.
+
+use std::collections::HashMap;
+
+/// Maps ping names to the server endpoints their pings are sent to.
+///
+/// A pattern either matches a ping name exactly,
+/// or contains `*` wildcards, each matching any sequence of characters.
+/// Exact patterns take precedence over wildcard patterns.
+/// If several wildcard patterns match, the longest one is used.
+#[derive(Debug, Default)]
+pub(crate) struct EndpointRouter {
+    /// Server endpoints by ping name.
+    exact: HashMap<String, String>,
+    /// Patterns with wildcards and their server endpoints, longest pattern first.
+    wildcards: Vec<(String, String)>,
+}
+
+impl EndpointRouter {
+    /// Creates a new router.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoints` - The server endpoints by ping name pattern.
+    pub fn new(endpoints: HashMap<String, String>) -> Self {
+        let (wildcards, exact): (HashMap<_, _>, HashMap<_, _>) = endpoints
+            .into_iter()
+            .partition(|(pattern, _)| pattern.contains('*'));
+
+        let mut wildcards: Vec<_> = wildcards.into_iter().collect();
+        // Sorting by the pattern too keeps the order stable for patterns of the same length.
+        wildcards.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        Self { exact, wildcards }
+    }
+
+    /// Gets the server endpoint for pings with the given name.
+    ///
+    /// # Returns
+    ///
+    /// The server endpoint, or `None` if the default endpoint should be used.
+    pub fn endpoint_for(&self, ping_name: &str) -> Option<&str> {
+        if let Some(endpoint) = self.exact.get(ping_name) {
+            return Some(endpoint);
+        }
+
+        self.wildcards
+            .iter()
+            .find(|(pattern, _)| matches_wildcard(pattern, ping_name))
+            .map(|(_, endpoint)| &endpoint[..])
+    }
+}
+
+/// Checks if a name matches a pattern containing `*` wildcards.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // The pattern contains at least one wildcard, so there is a first and a last part.
+    let first = parts.next().unwrap_or_default();
+    let last = parts.next_back().unwrap_or_default();
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+
+    // Parts between wildcards need to appear in order, without overlapping.
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn router(endpoints: &[(&str, &str)]) -> EndpointRouter {
+        EndpointRouter::new(
+            endpoints
+                .iter()
+                .map(|(pattern, endpoint)| (pattern.to_string(), endpoint.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn wildcards_match_any_sequence() {
+        assert!(matches_wildcard("*", ""));
+        assert!(matches_wildcard("*", "crash"));
+        assert!(matches_wildcard("crash-*", "crash-"));
+        assert!(matches_wildcard("crash-*", "crash-main"));
+        assert!(matches_wildcard("*-crash", "main-crash"));
+        assert!(matches_wildcard("a*b*c", "abc"));
+        assert!(matches_wildcard("a*b*c", "a-b-b-c"));
+
+        assert!(!matches_wildcard("crash-*", "crash"));
+        assert!(!matches_wildcard("crash-*", "main-crash"));
+        assert!(!matches_wildcard("a*b*c", "ac"));
+        assert!(!matches_wildcard("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn unmatched_pings_use_the_default_endpoint() {
+        let router = router(&[("crash", "https://crash.example.com")]);
+
+        assert_eq!(None, router.endpoint_for("baseline"));
+        assert_eq!(None, router.endpoint_for("crash-main"));
+        assert_eq!(None, EndpointRouter::default().endpoint_for("crash"));
+    }
+
+    #[test]
+    fn exact_patterns_take_precedence() {
+        let router = router(&[
+            ("crash*", "https://wildcard.example.com"),
+            ("crash", "https://exact.example.com"),
+        ]);
+
+        assert_eq!(
+            Some("https://exact.example.com"),
+            router.endpoint_for("crash")
+        );
+        assert_eq!(
+            Some("https://wildcard.example.com"),
+            router.endpoint_for("crash-main")
+        );
+    }
+
+    #[test]
+    fn longest_wildcard_pattern_is_used() {
+        let router = router(&[
+            ("*", "https://any.example.com"),
+            ("crash-*", "https://crash.example.com"),
+        ]);
+
+        assert_eq!(
+            Some("https://crash.example.com"),
+            router.endpoint_for("crash-main")
+        );
+        assert_eq!(
+            Some("https://any.example.com"),
+            router.endpoint_for("metrics")
+        );
+    }
+}
@@ -2,13 +2,31 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use chrono::{DateTime, FixedOffset, Local};
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset};
 
 use crate::error_recording::{record_error, ErrorType};
 use crate::metrics::TimeUnit;
 use crate::CommonMetricData;
 use crate::Glean;
 
+/// Gets the total size of the files in the given directory, not including subdirectories.
+///
+/// Files that can't be read are not counted.
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok()?.metadata().ok())
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
 /// Generates a pipeline-friendly string
 /// that replaces non alphanumeric characters with dashes.
 pub fn sanitize_application_id(application_id: &str) -> String {
@@ -44,14 +62,6 @@ pub fn get_iso_time_string(datetime: DateTime<FixedOffset>, truncate_to: TimeUni
     datetime.format(truncate_to.format_pattern()).to_string()
 }
 
-/// Get the current date & time with a fixed-offset timezone.
-///
-/// This converts from the `Local` timezone into its fixed-offset equivalent.
-pub(crate) fn local_now_with_offset() -> DateTime<FixedOffset> {
-    let now: DateTime<Local> = Local::now();
-    now.with_timezone(now.offset())
-}
-
 /// Truncates a string, ensuring that it doesn't end in the middle of a codepoint.
 ///
 /// # Arguments
@@ -237,21 +247,6 @@ mod test {
         assert_eq!("1985-07-03+01:00", get_iso_time_string(dt, TimeUnit::Day));
     }
 
-    #[test]
-    fn local_now_gets_the_time() {
-        let now = Local::now();
-        let fixed_now = local_now_with_offset();
-
-        // We can't compare across differing timezones, so we just compare the UTC timestamps.
-        // The second timestamp should be just a few nanoseconds later.
-        assert!(
-            fixed_now.naive_utc() >= now.naive_utc(),
-            "Time mismatch. Local now: {:?}, Fixed now: {:?}",
-            now,
-            fixed_now
-        );
-    }
-
     #[test]
     fn truncate_safely_test() {
         let value = "电脑坏了".to_string();
@@ -48,15 +48,20 @@ pub fn new_glean(tempdir: Option<tempfile::TempDir>) -> (Glean, tempfile::TempDi
         Some(tempdir) => tempdir,
         None => tempfile::tempdir().unwrap(),
     };
-    let tmpname = dir.path().display().to_string();
-
     let cfg = glean_core::Configuration {
-        data_path: tmpname,
+        data_path: dir.path().into(),
         application_id: GLOBAL_APPLICATION_ID.into(),
         language_binding_name: "Rust".into(),
         upload_enabled: true,
         max_events: None,
         delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
     };
     let glean = Glean::new(cfg).unwrap();
 
@@ -174,4 +174,8 @@ fn saturates_at_boundary() {
         i32::max_value(),
         counter.test_get_value(&glean, "store1").unwrap()
     );
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, counter.meta(), ErrorType::InvalidOverflow, None)
+    );
 }
@@ -185,3 +185,32 @@ fn test_that_truncation_works() {
         );
     }
 }
+
+#[test]
+fn test_get_value_is_truncated_and_keeps_the_offset() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = DatetimeMetric::new(
+        CommonMetricData {
+            name: "datetime_metric".into(),
+            category: "telemetry".into(),
+            send_in_pings: vec!["store1".into()],
+            disabled: false,
+            lifetime: Lifetime::User,
+            ..Default::default()
+        },
+        TimeUnit::Minute,
+    );
+
+    let datetime = FixedOffset::west(5 * 3600)
+        .ymd(1985, 7, 3)
+        .and_hms_nano(12, 9, 14, 1_560_274);
+    metric.set(&glean, Some(datetime));
+
+    let expected = FixedOffset::west(5 * 3600)
+        .ymd(1985, 7, 3)
+        .and_hms(12, 9, 0);
+    let value = metric.test_get_value(&glean, "store1").unwrap();
+    assert_eq!(expected, value);
+    assert_eq!(-5 * 3600, value.offset().local_minus_utc());
+}
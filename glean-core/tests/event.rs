@@ -7,9 +7,10 @@ use crate::common::*;
 
 use std::collections::HashMap;
 use std::fs;
+use std::time::Duration;
 
 use glean_core::metrics::*;
-use glean_core::{CommonMetricData, Lifetime};
+use glean_core::{test_get_num_recorded_errors, CommonMetricData, ErrorType, Lifetime};
 
 #[test]
 fn record_properly_records_without_optional_arguments() {
@@ -288,3 +289,217 @@ fn snapshot_sorts_the_timestamps() {
             .unwrap()
     );
 }
+
+#[test]
+fn record_with_string_keys_validates_the_keys() {
+    let (glean, _t) = new_glean(None);
+
+    let store_names: Vec<String> = vec!["store1".into()];
+
+    let test_event = EventMetric::new(
+        CommonMetricData {
+            name: "testEvent".into(),
+            category: "ui".into(),
+            send_in_pings: store_names,
+            disabled: false,
+            lifetime: Lifetime::Ping,
+            ..Default::default()
+        },
+        vec!["extra1".into(), "truncatedExtra".into()],
+    );
+
+    let test_value = "LeanGleanByFrank";
+    let mut extra: HashMap<String, String> = HashMap::new();
+    extra.insert("extra1".into(), test_value.to_string());
+    extra.insert("truncatedExtra".into(), test_value.repeat(10));
+    test_event.record_with_string_keys(&glean, 0, Some(extra));
+
+    let mut extra: HashMap<String, String> = HashMap::new();
+    extra.insert("unknown".into(), test_value.to_string());
+    test_event.record_with_string_keys(&glean, 1, Some(extra));
+
+    let events = test_event.test_get_value(&glean, "store1").unwrap();
+    assert_eq!(1, events.len());
+    let extra = events[0].extra.as_ref().unwrap();
+    assert_eq!(test_value, extra["extra1"]);
+    assert_eq!(test_value.repeat(10)[0..100], extra["truncatedExtra"]);
+
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, test_event.meta(), ErrorType::InvalidValue, None)
+    );
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, test_event.meta(), ErrorType::InvalidOverflow, None)
+    );
+}
+
+#[test]
+fn duplicate_events_within_the_dedup_window_are_recorded_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg = glean_core::Configuration {
+        data_path: dir.path().into(),
+        application_id: GLOBAL_APPLICATION_ID.into(),
+        language_binding_name: "Rust".into(),
+        upload_enabled: true,
+        max_events: None,
+        delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: Some(Duration::from_millis(50)),
+        enable_trace_context: false,
+    };
+    let glean = glean_core::Glean::new(cfg).unwrap();
+
+    let click = EventMetric::new(
+        CommonMetricData {
+            name: "click".into(),
+            category: "ui".into(),
+            send_in_pings: vec!["store1".into()],
+            ..Default::default()
+        },
+        vec!["button".into()],
+    );
+
+    let mut extra = HashMap::new();
+    extra.insert(0, "ok".to_string());
+
+    // The same event twice within one frame: only the first one is recorded.
+    click.record(&glean, 1000, extra.clone());
+    click.record(&glean, 1010, extra.clone());
+
+    let events = click.test_get_value(&glean, "store1").unwrap();
+    assert_eq!(1, events.len());
+    assert_eq!(1000, events[0].timestamp);
+
+    let suppressed = LabeledMetric::new(
+        CounterMetric::new(CommonMetricData {
+            name: "event_duplicates_suppressed".into(),
+            category: "glean.error".into(),
+            send_in_pings: vec!["metrics".into()],
+            ..Default::default()
+        }),
+        None,
+    );
+    assert_eq!(
+        Some(1),
+        suppressed.get("ui.click").test_get_value(&glean, "metrics")
+    );
+
+    // Events with different extras or outside of the window are not duplicates.
+    let mut other_extra = HashMap::new();
+    other_extra.insert(0, "cancel".to_string());
+    click.record(&glean, 1020, other_extra);
+    click.record(&glean, 1100, extra);
+
+    let events = click.test_get_value(&glean, "store1").unwrap();
+    assert_eq!(3, events.len());
+    assert_eq!(
+        Some(1),
+        suppressed.get("ui.click").test_get_value(&glean, "metrics")
+    );
+}
+
+#[test]
+fn events_with_the_same_timestamp_keep_their_recording_order() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = EventMetric::new(
+        CommonMetricData {
+            name: "ordered".into(),
+            category: "telemetry".into(),
+            send_in_pings: vec!["store1".into()],
+            lifetime: Lifetime::Ping,
+            ..Default::default()
+        },
+        vec!["thread".into(), "seq".into()],
+    );
+
+    // Every thread records the same timestamps, out of order.
+    let timestamps = [300, 100, 300, 200, 100, 300];
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let glean = &glean;
+            let metric = &metric;
+            scope.spawn(move || {
+                for (seq, timestamp) in timestamps.iter().enumerate() {
+                    let mut extra = HashMap::new();
+                    extra.insert(0, thread.to_string());
+                    extra.insert(1, seq.to_string());
+                    metric.record(glean, *timestamp, extra);
+                }
+            });
+        }
+    });
+
+    let snapshot = glean
+        .event_storage()
+        .snapshot_as_json("store1", false)
+        .unwrap();
+    let events = snapshot.as_array().unwrap();
+    assert_eq!(4 * timestamps.len(), events.len());
+
+    // Ordered by timestamp, and within a thread by recording order for the same timestamp.
+    let mut last_seq: HashMap<(i64, String), usize> = HashMap::new();
+    let mut last_timestamp = 0;
+    for event in events {
+        let timestamp = event["timestamp"].as_i64().unwrap();
+        assert!(timestamp >= last_timestamp);
+        last_timestamp = timestamp;
+
+        let thread = event["extra"]["thread"].as_str().unwrap().to_string();
+        let seq: usize = event["extra"]["seq"].as_str().unwrap().parse().unwrap();
+        if let Some(previous) = last_seq.insert((timestamp, thread), seq) {
+            assert!(previous < seq);
+        }
+    }
+
+    // Snapshotting again gives the same order.
+    assert_eq!(
+        snapshot,
+        glean
+            .event_storage()
+            .snapshot_as_json("store1", false)
+            .unwrap()
+    );
+}
+
+#[test]
+fn events_loaded_after_a_restart_keep_their_order() {
+    let (glean, t) = new_glean(None);
+
+    let metric = EventMetric::new(
+        CommonMetricData {
+            name: "ordered".into(),
+            category: "telemetry".into(),
+            send_in_pings: vec!["events".into()],
+            lifetime: Lifetime::Ping,
+            ..Default::default()
+        },
+        vec!["seq".into()],
+    );
+
+    for (seq, timestamp) in [5, 5, 1, 5].iter().enumerate() {
+        let mut extra = HashMap::new();
+        extra.insert(0, seq.to_string());
+        metric.record(&glean, *timestamp, extra);
+    }
+    drop(glean);
+
+    let (mut glean, t) = new_glean(Some(t));
+    glean.register_ping_type(&PingType::new("events", true, false, vec![]));
+    assert!(glean.on_ready_to_submit_pings());
+
+    let pings = get_queued_pings(t.path()).unwrap();
+    assert_eq!(1, pings.len());
+    let order: Vec<_> = pings[0].1["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["extra"]["seq"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(vec!["2", "0", "1", "3"], order);
+}
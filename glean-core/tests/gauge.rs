@@ -0,0 +1,159 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+mod common;
+use crate::common::*;
+
+use serde_json::json;
+
+use glean_core::metrics::*;
+use glean_core::storage::StorageManager;
+use glean_core::{test_get_num_recorded_errors, ErrorType};
+use glean_core::{CommonMetricData, Lifetime};
+
+fn new_gauge(send_in_pings: Vec<String>) -> GaugeMetric {
+    GaugeMetric::new(CommonMetricData {
+        name: "gauge_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings,
+        disabled: false,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn gauge_serializer_should_correctly_serialize_gauges() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = new_gauge(vec!["store1".into()]);
+    metric.set(&glean, -5);
+
+    let snapshot = StorageManager
+        .snapshot_as_json(glean.storage(), "store1", true)
+        .unwrap();
+    assert_eq!(json!({"gauge": {"telemetry.gauge_metric": -5}}), snapshot);
+}
+
+#[test]
+fn gauges_default_to_the_ping_lifetime() {
+    let metric = new_gauge(vec!["store1".into()]);
+    assert_eq!(Lifetime::Ping, metric.meta().lifetime);
+}
+
+#[test]
+fn gauges_are_stored_separately_from_counters() {
+    let (glean, _t) = new_glean(None);
+
+    let gauge = new_gauge(vec!["store1".into()]);
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    });
+
+    gauge.set(&glean, 3);
+    counter.add(&glean, 1);
+
+    let snapshot = StorageManager
+        .snapshot_as_json(glean.storage(), "store1", true)
+        .unwrap();
+    assert_eq!(
+        json!({
+            "gauge": {"telemetry.gauge_metric": 3},
+            "counter": {"telemetry.counter_metric": 1},
+        }),
+        snapshot
+    );
+}
+
+#[test]
+fn set_value_properly_sets_the_value_in_all_stores() {
+    let (glean, _t) = new_glean(None);
+    let store_names: Vec<String> = vec!["store1".into(), "store2".into()];
+
+    let metric = new_gauge(store_names.clone());
+    metric.set(&glean, 10);
+    metric.set(&glean, 0);
+
+    for store_name in store_names {
+        assert_eq!(Some(0), metric.test_get_value(&glean, &store_name));
+    }
+}
+
+#[test]
+fn adding_negative_deltas_decreases_the_gauge() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = new_gauge(vec!["store1".into()]);
+
+    // Adding to an unset gauge starts from 0.
+    metric.add(&glean, -2);
+    assert_eq!(Some(-2), metric.test_get_value(&glean, "store1"));
+
+    metric.add(&glean, 5);
+    metric.add(&glean, -3);
+    assert_eq!(Some(0), metric.test_get_value(&glean, "store1"));
+
+    metric.add(&glean, 0);
+    assert_eq!(Some(0), metric.test_get_value(&glean, "store1"));
+
+    assert!(test_get_num_recorded_errors(
+        &glean,
+        metric.meta(),
+        ErrorType::InvalidOverflow,
+        Some("store1")
+    )
+    .is_err());
+}
+
+#[test]
+fn adding_saturates_at_the_bounds() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = new_gauge(vec!["store1".into()]);
+
+    metric.set(&glean, i64::MAX - 1);
+    metric.add(&glean, 2);
+    assert_eq!(Some(i64::MAX), metric.test_get_value(&glean, "store1"));
+
+    metric.set(&glean, i64::MIN + 1);
+    metric.add(&glean, -2);
+    assert_eq!(Some(i64::MIN), metric.test_get_value(&glean, "store1"));
+
+    assert_eq!(
+        Ok(2),
+        test_get_num_recorded_errors(
+            &glean,
+            metric.meta(),
+            ErrorType::InvalidOverflow,
+            Some("store1")
+        )
+    );
+}
+
+#[test]
+fn gauges_are_included_in_submitted_pings() {
+    let (mut glean, _t) = new_glean(None);
+
+    let ping = PingType::new("store1", true, false, vec![]);
+    glean.register_ping_type(&ping);
+
+    let metric = new_gauge(vec!["store1".into()]);
+    metric.set(&glean, 7);
+    metric.add(&glean, -9);
+
+    assert!(ping.submit(&glean, None).unwrap());
+
+    let queued_pings = get_queued_pings(glean.get_data_path()).unwrap();
+    assert_eq!(1, queued_pings.len());
+    let (_, payload, _) = &queued_pings[0];
+    assert_eq!(
+        json!(-2),
+        payload["metrics"]["gauge"]["telemetry.gauge_metric"]
+    );
+
+    // The gauge has a `ping` lifetime, so it's cleared after submission.
+    assert_eq!(None, metric.test_get_value(&glean, "store1"));
+}
@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+mod common;
+use crate::common::*;
+
+use serde_json::json;
+
+use glean_core::metrics::*;
+use glean_core::storage::StorageManager;
+use glean_core::{test_get_num_recorded_errors, ErrorType};
+use glean_core::{CommonMetricData, Lifetime};
+
+fn new_object_metric() -> ObjectMetric {
+    ObjectMetric::new(CommonMetricData {
+        name: "object_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings: vec!["store1".into()],
+        disabled: false,
+        lifetime: Lifetime::User,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn object_serializer_should_correctly_serialize_objects() {
+    let (mut tempdir, _) = tempdir();
+
+    let value =
+        json!({"colour": "red", "sizes": [1, 2], "nested": {"enabled": true, "name": null}});
+    {
+        // We give tempdir to the `new_glean` function...
+        let (glean, dir) = new_glean(Some(tempdir));
+        // And then we get it back once that function returns.
+        tempdir = dir;
+
+        new_object_metric().set(&glean, value.clone());
+
+        let snapshot = StorageManager
+            .snapshot_as_json(glean.storage(), "store1", true)
+            .unwrap();
+        assert_eq!(
+            json!({"object": {"telemetry.object_metric": value}}),
+            snapshot
+        );
+    }
+
+    // Make a new Glean instance here, which should force reloading of the data from disk
+    // so we can ensure it persisted, because it has User lifetime
+    {
+        let (glean, _) = new_glean(Some(tempdir));
+        let snapshot = StorageManager
+            .snapshot_as_json(glean.storage(), "store1", true)
+            .unwrap();
+        assert_eq!(
+            json!({"object": {"telemetry.object_metric": value}}),
+            snapshot
+        );
+    }
+}
+
+#[test]
+fn arrays_and_serialized_objects_are_recorded() {
+    let (glean, _t) = new_glean(None);
+    let metric = new_object_metric();
+
+    metric.set(&glean, json!([{"id": 1}, {"id": 2}]));
+    assert_eq!(
+        Some(json!([{"id": 1}, {"id": 2}])),
+        metric.test_get_value(&glean, "store1")
+    );
+
+    metric.set_string(&glean, r#"{"id": 3}"#);
+    assert_eq!(
+        Some(json!({"id": 3})),
+        metric.test_get_value(&glean, "store1")
+    );
+}
+
+#[test]
+fn values_other_than_objects_and_arrays_record_an_error() {
+    let (glean, _t) = new_glean(None);
+    let metric = new_object_metric();
+
+    metric.set(&glean, json!({"id": 1}));
+    metric.set(&glean, json!(null));
+    metric.set(&glean, json!(5));
+    metric.set_string(&glean, "{\"id\": ");
+
+    // The previous value is kept.
+    assert_eq!(
+        Some(json!({"id": 1})),
+        metric.test_get_value(&glean, "store1")
+    );
+    assert_eq!(
+        Ok(3),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn schema_errors_are_recorded() {
+    let (glean, _t) = new_glean(None);
+    let metric = new_object_metric();
+
+    metric.record_schema_error(&glean, "missing property 'id'");
+
+    assert_eq!(None, metric.test_get_value(&glean, "store1"));
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidValue, None)
+    );
+}
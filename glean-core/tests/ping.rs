@@ -101,3 +101,128 @@ fn empty_pings_with_flag_are_sent() {
     assert_eq!(false, ping2.submit(&glean, None).unwrap());
     assert_eq!(1, get_queued_pings(glean.get_data_path()).unwrap().len());
 }
+
+#[test]
+fn deregistered_pings_are_unknown() {
+    let (mut glean, _) = new_glean(None);
+
+    let ping = PingType::new("custom", true, true, vec![]);
+    glean.register_ping_type(&ping);
+    assert!(glean.submit_ping_by_name("custom", None).unwrap());
+
+    assert!(glean.deregister_ping_type("custom"));
+    assert!(glean.get_ping_by_name("custom").is_none());
+    assert!(!glean.submit_ping_by_name("custom", None).unwrap());
+    assert_eq!(1, get_queued_pings(glean.get_data_path()).unwrap().len());
+
+    // Deregistering twice is a no-op.
+    assert!(!glean.deregister_ping_type("custom"));
+}
+
+#[test]
+fn registered_ping_names_keep_the_registration_order() {
+    let (mut glean, _) = new_glean(None);
+
+    for name in &["c", "a", "b"] {
+        glean.register_ping_type(&PingType::new(*name, true, true, vec![]));
+    }
+    assert_eq!(vec!["c", "a", "b"], glean.registered_ping_names());
+
+    // Registering again keeps the position, deregistering removes it.
+    glean.register_ping_type(&PingType::new("c", true, false, vec![]));
+    assert!(glean.deregister_ping_type("a"));
+    assert_eq!(vec!["c", "b"], glean.registered_ping_names());
+
+    glean.register_ping_type(&PingType::new("a", true, true, vec![]));
+    assert_eq!(vec!["c", "b", "a"], glean.registered_ping_names());
+}
+
+#[test]
+fn ping_submissions_are_rate_limited() {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg = glean_core::Configuration {
+        data_path: dir.path().into(),
+        application_id: GLOBAL_APPLICATION_ID.into(),
+        language_binding_name: "Rust".into(),
+        upload_enabled: true,
+        max_events: None,
+        delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: Some(10),
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context: false,
+    };
+    let mut glean = glean_core::Glean::new(cfg).unwrap();
+
+    let ping = PingType::new("custom", true, true, vec![]);
+    glean.register_ping_type(&ping);
+
+    for _ in 0..200 {
+        glean.submit_ping(&ping, None).unwrap();
+    }
+    assert_eq!(10, glean.test_get_pending_ping_doc_ids("custom").len());
+
+    // Every dropped submission is recorded.
+    let rate_limit_exceeded = LabeledMetric::new(
+        CounterMetric::new(CommonMetricData {
+            name: "ping_rate_limit_exceeded".into(),
+            category: "glean.error".into(),
+            send_in_pings: vec!["metrics".into()],
+            lifetime: glean_core::Lifetime::Ping,
+            ..Default::default()
+        }),
+        None,
+    );
+    assert_eq!(
+        Some(190),
+        rate_limit_exceeded
+            .get("custom")
+            .test_get_value(&glean, "metrics")
+    );
+
+    // The `baseline` ping is exempt.
+    let baseline = PingType::new("baseline", true, true, vec![]);
+    glean.register_ping_type(&baseline);
+    for _ in 0..20 {
+        glean.submit_ping(&baseline, None).unwrap();
+    }
+    assert_eq!(20, glean.test_get_pending_ping_doc_ids("baseline").len());
+}
+
+#[test]
+fn metrics_ping_reports_metric_writes_since_the_last_one() {
+    let (mut glean, _temp) = new_glean(None);
+
+    let ping = PingType::new("metrics", true, false, vec![]);
+    glean.register_ping_type(&ping);
+
+    let counter = CounterMetric::new(CommonMetricData {
+        name: "counter".into(),
+        category: "local".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    });
+    let write_count = |glean: &glean_core::Glean| {
+        let mut pings = get_queued_pings(glean.get_data_path()).unwrap();
+        pings.sort_by_key(|(_, payload, _)| payload["ping_info"]["seq"].as_i64());
+        let (_, payload, _) = pings.last().unwrap();
+        payload["metrics"]["counter"]["glean.metrics.write_count"].clone()
+    };
+
+    for _ in 0..5 {
+        counter.add(&glean, 1);
+    }
+    assert!(ping.submit(&glean, None).unwrap());
+    assert_eq!(5, write_count(&glean));
+
+    counter.add(&glean, 1);
+    counter.add(&glean, 1);
+    assert!(ping.submit(&glean, None).unwrap());
+    assert_eq!(2, write_count(&glean));
+
+    // Nothing was written in this window, so there's nothing to send.
+    assert!(!ping.submit(&glean, None).unwrap());
+}
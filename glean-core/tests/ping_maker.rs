@@ -208,3 +208,35 @@ fn metadata_is_correctly_added_when_necessary() {
     let headers = metadata.as_ref().unwrap().get("headers").unwrap();
     assert_eq!(headers.get("X-Debug-ID").unwrap(), "valid-tag");
 }
+
+#[test]
+fn inspecting_a_ping_does_not_change_the_next_submission() {
+    let (glean, ping_maker, ping_type, _t) = set_up_basic_ping();
+
+    let inspected = glean.assemble_ping_for_inspection("store1").unwrap();
+    assert_eq!(
+        true,
+        inspected["metrics"]["boolean"]["telemetry.boolean_metric"]
+    );
+    assert_eq!(0, inspected["ping_info"]["seq"]);
+
+    // Inspecting again sees the same data.
+    let inspected_again = glean.assemble_ping_for_inspection("store1").unwrap();
+    assert_eq!(inspected["metrics"], inspected_again["metrics"]);
+    assert_eq!(0, inspected_again["ping_info"]["seq"]);
+
+    // The real submission still contains the data, and it's the first ping.
+    let content = ping_maker.collect(&glean, &ping_type, None).unwrap();
+    assert_eq!(inspected["metrics"], content["metrics"]);
+    assert_eq!(0, content["ping_info"]["seq"]);
+    assert_eq!(
+        inspected["ping_info"]["start_time"],
+        content["ping_info"]["start_time"]
+    );
+
+    // The submission advanced the sequence number.
+    let inspected = glean.assemble_ping_for_inspection("store1").unwrap();
+    assert_eq!(1, inspected["ping_info"]["seq"]);
+
+    assert!(glean.assemble_ping_for_inspection("unknown").is_none());
+}
@@ -116,3 +116,38 @@ fn quantities_must_not_set_when_passed_negative() {
         test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidValue, None)
     );
 }
+
+#[test]
+fn adding_accumulates_and_saturates() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = QuantityMetric::new(CommonMetricData {
+        name: "quantity_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings: vec!["store1".into()],
+        disabled: false,
+        lifetime: Lifetime::Application,
+        ..Default::default()
+    });
+
+    metric.add(&glean, 10);
+    metric.add(&glean, 0);
+    metric.add(&glean, 32);
+    assert_eq!(Some(42), metric.test_get_value(&glean, "store1"));
+
+    // Negative amounts are rejected.
+    metric.add(&glean, -1);
+    assert_eq!(Some(42), metric.test_get_value(&glean, "store1"));
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidValue, None)
+    );
+
+    // Overflowing clamps to the maximum.
+    metric.add(&glean, i64::MAX);
+    assert_eq!(Some(i64::MAX), metric.test_get_value(&glean, "store1"));
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidOverflow, None)
+    );
+}
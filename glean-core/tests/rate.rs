@@ -0,0 +1,120 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+mod common;
+use crate::common::*;
+
+use serde_json::json;
+
+use glean_core::metrics::*;
+use glean_core::storage::StorageManager;
+use glean_core::{test_get_num_recorded_errors, ErrorType};
+use glean_core::{CommonMetricData, Lifetime};
+
+fn new_rate(send_in_pings: Vec<String>) -> RateMetric {
+    RateMetric::new(CommonMetricData {
+        name: "rate_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings,
+        disabled: false,
+        lifetime: Lifetime::Ping,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn rate_serializer_should_correctly_serialize_rates() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = new_rate(vec!["store1".into()]);
+    metric.add_to_numerator(&glean, 2);
+    metric.add_to_denominator(&glean, 5);
+
+    let snapshot = StorageManager
+        .snapshot_as_json(glean.storage(), "store1", true)
+        .unwrap();
+    assert_eq!(
+        json!({"rate": {"telemetry.rate_metric": {"numerator": 2, "denominator": 5}}}),
+        snapshot
+    );
+}
+
+#[test]
+fn rates_with_a_zero_denominator_are_serialized() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = new_rate(vec!["store1".into()]);
+    metric.add_to_numerator(&glean, 3);
+    assert_eq!(Some((3, 0)), metric.test_get_value(&glean, "store1"));
+
+    let snapshot = StorageManager
+        .snapshot_as_json(glean.storage(), "store1", true)
+        .unwrap();
+    assert_eq!(
+        json!({"rate": {"telemetry.rate_metric": {"numerator": 3, "denominator": 0}}}),
+        snapshot
+    );
+}
+
+#[test]
+fn adding_properly_accumulates_in_all_stores() {
+    let (glean, _t) = new_glean(None);
+    let store_names: Vec<String> = vec!["store1".into(), "store2".into()];
+
+    let metric = new_rate(store_names.clone());
+    metric.add_to_numerator(&glean, 1);
+    metric.add_to_denominator(&glean, 10);
+    metric.add_to_numerator(&glean, 2);
+    metric.add_to_denominator(&glean, 0);
+
+    for store_name in store_names {
+        assert_eq!(Some((3, 10)), metric.test_get_value(&glean, &store_name));
+    }
+}
+
+#[test]
+fn negative_amounts_are_not_recorded() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = new_rate(vec!["store1".into()]);
+    metric.add_to_numerator(&glean, 1);
+    metric.add_to_numerator(&glean, -1);
+    metric.add_to_denominator(&glean, -5);
+
+    assert_eq!(Some((1, 0)), metric.test_get_value(&glean, "store1"));
+    assert_eq!(
+        Ok(2),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn large_values_accumulate_and_saturate() {
+    let (glean, _t) = new_glean(None);
+
+    let metric = new_rate(vec!["store1".into()]);
+    for _ in 0..4 {
+        metric.add_to_numerator(&glean, i32::MAX / 4);
+        metric.add_to_denominator(&glean, i32::MAX / 2);
+    }
+    assert_eq!(
+        Some((i32::MAX / 4 * 4, i32::MAX)),
+        metric.test_get_value(&glean, "store1")
+    );
+    // The denominator overflowed on the last two additions.
+    assert_eq!(
+        Ok(2),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidOverflow, None)
+    );
+
+    metric.add_to_numerator(&glean, i32::MAX);
+    assert_eq!(
+        Some((i32::MAX, i32::MAX)),
+        metric.test_get_value(&glean, "store1")
+    );
+    assert_eq!(
+        Ok(3),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidOverflow, None)
+    );
+}
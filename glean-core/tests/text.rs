@@ -0,0 +1,98 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+mod common;
+use crate::common::*;
+
+use serde_json::json;
+
+use glean_core::metrics::*;
+use glean_core::storage::StorageManager;
+use glean_core::{test_get_num_recorded_errors, ErrorType};
+use glean_core::{CommonMetricData, Lifetime};
+
+fn new_text_metric(send_in_pings: Vec<String>) -> TextMetric {
+    TextMetric::new(CommonMetricData {
+        name: "text_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings,
+        disabled: false,
+        lifetime: Lifetime::User,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn text_serializer_should_correctly_serialize_texts() {
+    let (mut tempdir, _) = tempdir();
+
+    {
+        // We give tempdir to the `new_glean` function...
+        let (glean, dir) = new_glean(Some(tempdir));
+        // And then we get it back once that function returns.
+        tempdir = dir;
+
+        new_text_metric(vec!["store1".into()]).set(&glean, "test_text_value");
+
+        let snapshot = StorageManager
+            .snapshot_as_json(glean.storage(), "store1", true)
+            .unwrap();
+        assert_eq!(
+            json!({"text": {"telemetry.text_metric": "test_text_value"}}),
+            snapshot
+        );
+    }
+
+    // Make a new Glean instance here, which should force reloading of the data from disk
+    // so we can ensure it persisted, because it has User lifetime
+    {
+        let (glean, _) = new_glean(Some(tempdir));
+        let snapshot = StorageManager
+            .snapshot_as_json(glean.storage(), "store1", true)
+            .unwrap();
+        assert_eq!(
+            json!({"text": {"telemetry.text_metric": "test_text_value"}}),
+            snapshot
+        );
+    }
+}
+
+#[test]
+fn long_texts_are_truncated() {
+    let (glean, _t) = new_glean(None);
+    let metric = new_text_metric(vec!["store1".into()]);
+
+    let kilobyte = "x".repeat(1024);
+    metric.set(&glean, kilobyte.clone());
+    assert_eq!(Some(kilobyte), metric.test_get_value(&glean, "store1"));
+
+    metric.set(&glean, "x".repeat(200 * 1024 + 1));
+    assert_eq!(
+        Some("x".repeat(200 * 1024)),
+        metric.test_get_value(&glean, "store1")
+    );
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidOverflow, None)
+    );
+}
+
+#[test]
+fn texts_are_not_sent_in_builtin_pings() {
+    let (glean, _t) = new_glean(None);
+    let metric = new_text_metric(vec![
+        "baseline".into(),
+        "store1".into(),
+        "metrics".into(),
+        "events".into(),
+    ]);
+    assert_eq!(vec!["store1".to_string()], metric.meta().send_in_pings);
+
+    metric.set(&glean, "test_text_value");
+    assert_eq!(None, metric.test_get_value(&glean, "metrics"));
+    assert_eq!(
+        Some("test_text_value".to_string()),
+        metric.test_get_value(&glean, "store1")
+    );
+}
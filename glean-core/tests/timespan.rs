@@ -204,6 +204,38 @@ fn cancel_does_not_store() {
     assert_eq!(None, metric.test_get_value(&glean, "store1"));
 }
 
+#[test]
+fn negative_duration_is_clamped_to_zero() {
+    let (glean, _t) = new_glean(None);
+
+    let mut metric = TimespanMetric::new(
+        CommonMetricData {
+            name: "timespan_metric".into(),
+            category: "telemetry".into(),
+            send_in_pings: vec!["store1".into()],
+            disabled: false,
+            lifetime: Lifetime::Ping,
+            ..Default::default()
+        },
+        TimeUnit::Nanosecond,
+    );
+
+    // The clock went backwards between start and stop.
+    metric.set_start(&glean, 100);
+    metric.set_stop(&glean, 40);
+
+    assert_eq!(Some(0), metric.test_get_value(&glean, "store1"));
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(
+            &glean,
+            metric.meta(),
+            ErrorType::InvalidValue,
+            Some("store1")
+        )
+    );
+}
+
 #[test]
 fn nothing_stored_before_stop() {
     let (glean, _t) = new_glean(None);
@@ -0,0 +1,113 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#![cfg(feature = "opentelemetry")]
+
+mod common;
+use crate::common::*;
+
+use std::thread;
+use std::time::Duration;
+
+use opentelemetry::trace::{TraceContextExt, Tracer, TracerProvider as _};
+use opentelemetry::Context;
+use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+
+use glean_core::metrics::PingType;
+use glean_core::upload::{PingRequest, PingUploadTask};
+use glean_core::{Configuration, Glean};
+
+fn new_glean_with_trace_context(enable_trace_context: bool) -> (Glean, tempfile::TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg = Configuration {
+        data_path: dir.path().into(),
+        application_id: GLOBAL_APPLICATION_ID.into(),
+        language_binding_name: "Rust".into(),
+        upload_enabled: true,
+        max_events: None,
+        delay_ping_lifetime_io: false,
+        compress_pings: true,
+        max_pings_per_minute: None,
+        extra_server_endpoints: Default::default(),
+        disable_internal_error_metrics: false,
+        rate_limit: None,
+        event_dedup_window: None,
+        enable_trace_context,
+    };
+    (Glean::new(cfg).unwrap(), dir)
+}
+
+/// Gets the next ping request, waiting for the pending pings directory to be processed.
+fn next_request(glean: &Glean) -> PingRequest {
+    for _ in 0..100 {
+        match glean.get_upload_task() {
+            PingUploadTask::Upload(request) => return request,
+            _ => thread::sleep(Duration::from_millis(10)),
+        }
+    }
+    panic!("No ping was queued for upload");
+}
+
+#[test]
+fn pings_carry_the_traceparent_of_the_current_span() {
+    let (mut glean, _t) = new_glean_with_trace_context(true);
+    let ping = PingType::new("traced", true, /* send_if_empty */ true, vec![]);
+    glean.register_ping_type(&ping);
+
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let tracer = provider.tracer("glean-test");
+
+    let span_context = tracer.in_span("submit", |cx| {
+        assert!(glean.submit_ping(&ping, None).unwrap());
+        cx.span().span_context().clone()
+    });
+
+    let request = next_request(&glean);
+    let expected = format!(
+        "00-{}-{}-01",
+        span_context.trace_id(),
+        span_context.span_id()
+    );
+    assert_eq!(Some(&expected), request.headers.get("traceparent"));
+
+    // The header refers to the span that was exported.
+    let spans = exporter.get_finished_spans().unwrap();
+    assert_eq!(1, spans.len());
+    assert_eq!(span_context.trace_id(), spans[0].span_context.trace_id());
+    assert_eq!(span_context.span_id(), spans[0].span_context.span_id());
+}
+
+#[test]
+fn no_traceparent_without_a_span() {
+    let (mut glean, _t) = new_glean_with_trace_context(true);
+    let ping = PingType::new("traced", true, /* send_if_empty */ true, vec![]);
+    glean.register_ping_type(&ping);
+
+    // Make sure no span is active on this thread.
+    let _guard = Context::new().attach();
+    assert!(glean.submit_ping(&ping, None).unwrap());
+
+    let request = next_request(&glean);
+    assert!(!request.headers.contains_key("traceparent"));
+}
+
+#[test]
+fn no_traceparent_if_disabled() {
+    let (mut glean, _t) = new_glean_with_trace_context(false);
+    let ping = PingType::new("traced", true, /* send_if_empty */ true, vec![]);
+    glean.register_ping_type(&ping);
+
+    let provider = TracerProvider::builder().build();
+    let tracer = provider.tracer("glean-test");
+    tracer.in_span("submit", |_cx| {
+        assert!(glean.submit_ping(&ping, None).unwrap());
+    });
+
+    let request = next_request(&glean);
+    assert!(!request.headers.contains_key("traceparent"));
+}
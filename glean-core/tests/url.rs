@@ -0,0 +1,96 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+mod common;
+use crate::common::*;
+
+use serde_json::json;
+
+use glean_core::metrics::*;
+use glean_core::storage::StorageManager;
+use glean_core::{test_get_num_recorded_errors, ErrorType};
+use glean_core::{CommonMetricData, Lifetime};
+
+fn new_url_metric() -> UrlMetric {
+    UrlMetric::new(CommonMetricData {
+        name: "url_metric".into(),
+        category: "telemetry".into(),
+        send_in_pings: vec!["store1".into()],
+        disabled: false,
+        lifetime: Lifetime::User,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn url_serializer_should_correctly_serialize_urls() {
+    let (mut tempdir, _) = tempdir();
+
+    {
+        // We give tempdir to the `new_glean` function...
+        let (glean, dir) = new_glean(Some(tempdir));
+        // And then we get it back once that function returns.
+        tempdir = dir;
+
+        new_url_metric().set(&glean, "https://example.com/path?query=1");
+
+        let snapshot = StorageManager
+            .snapshot_as_json(glean.storage(), "store1", true)
+            .unwrap();
+        assert_eq!(
+            json!({"url": {"telemetry.url_metric": "https://example.com/path?query=1"}}),
+            snapshot
+        );
+    }
+
+    // Make a new Glean instance here, which should force reloading of the data from disk
+    // so we can ensure it persisted, because it has User lifetime
+    {
+        let (glean, _) = new_glean(Some(tempdir));
+        let snapshot = StorageManager
+            .snapshot_as_json(glean.storage(), "store1", true)
+            .unwrap();
+        assert_eq!(
+            json!({"url": {"telemetry.url_metric": "https://example.com/path?query=1"}}),
+            snapshot
+        );
+    }
+}
+
+#[test]
+fn urls_without_http_scheme_are_rejected() {
+    let (glean, _t) = new_glean(None);
+    let metric = new_url_metric();
+
+    metric.set(&glean, "https://example.com");
+    metric.set(&glean, "ftp://example.com");
+    metric.set(&glean, "example.com");
+
+    // The valid value is kept.
+    assert_eq!(
+        Some("https://example.com".to_string()),
+        metric.test_get_value(&glean, "store1")
+    );
+    assert_eq!(
+        Ok(2),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidValue, None)
+    );
+}
+
+#[test]
+fn long_urls_are_rejected() {
+    let (glean, _t) = new_glean(None);
+    let metric = new_url_metric();
+
+    let prefix = "https://example.com/";
+    let longest = format!("{}{}", prefix, "x".repeat(8192 - prefix.len()));
+    metric.set(&glean, longest.clone());
+    assert_eq!(Some(longest), metric.test_get_value(&glean, "store1"));
+
+    metric.set(&glean, format!("{}{}", prefix, "x".repeat(8192)));
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, metric.meta(), ErrorType::InvalidOverflow, None)
+    );
+}
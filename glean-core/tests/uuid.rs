@@ -9,7 +9,7 @@ use serde_json::json;
 
 use glean_core::metrics::*;
 use glean_core::storage::StorageManager;
-use glean_core::{CommonMetricData, Lifetime};
+use glean_core::{test_get_num_recorded_errors, CommonMetricData, ErrorType, Lifetime};
 
 #[test]
 fn uuid_is_generated_and_stored() {
@@ -112,3 +112,31 @@ fn set_properly_sets_the_value_in_all_stores() {
         );
     }
 }
+
+#[test]
+fn set_from_str_rejects_invalid_uuids() {
+    let (glean, _t) = new_glean(None);
+
+    let uuid = UuidMetric::new(CommonMetricData {
+        name: "uuid".into(),
+        category: "local".into(),
+        send_in_pings: vec!["store1".into()],
+        ..Default::default()
+    });
+
+    uuid.set_from_str(&glean, "3ed58b4c-ec8f-4d31-a8b4-59d0d8f7e1b6");
+    assert_eq!(
+        "3ed58b4c-ec8f-4d31-a8b4-59d0d8f7e1b6",
+        uuid.test_get_value(&glean, "store1").unwrap()
+    );
+
+    uuid.set_from_str(&glean, "not-a-uuid");
+    assert_eq!(
+        "3ed58b4c-ec8f-4d31-a8b4-59d0d8f7e1b6",
+        uuid.test_get_value(&glean, "store1").unwrap()
+    );
+    assert_eq!(
+        Ok(1),
+        test_get_num_recorded_errors(&glean, uuid.meta(), ErrorType::InvalidValue, None)
+    );
+}